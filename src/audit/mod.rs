@@ -0,0 +1,222 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use surrealdb::types::Datetime;
+use tracing::warn;
+use tracing_appender::non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// The file name prefix of audit log files
+const AUDIT_FILE_PREFIX: &str = "surrealmcp-audit";
+
+/// The file name suffix of audit log files
+const AUDIT_FILE_SUFFIX: &str = "jsonl";
+
+tokio::task_local! {
+    /// The tool call which is currently being handled
+    static CALL: CallContext;
+}
+
+/// How often a new audit log file is started
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum AuditRotation {
+    /// Start a new file every minute
+    Minutely,
+    /// Start a new file every hour
+    Hourly,
+    /// Start a new file every day
+    #[default]
+    Daily,
+    /// Always write to the same file
+    Never,
+}
+
+impl From<AuditRotation> for Rotation {
+    fn from(rotation: AuditRotation) -> Self {
+        match rotation {
+            AuditRotation::Minutely => Rotation::MINUTELY,
+            AuditRotation::Hourly => Rotation::HOURLY,
+            AuditRotation::Daily => Rotation::DAILY,
+            AuditRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+/// The configuration of the audit log
+#[derive(Clone, Debug)]
+pub struct AuditConfig {
+    /// The directory to write audit log files to
+    pub directory: String,
+    /// How often a new audit log file is started
+    pub rotation: AuditRotation,
+}
+
+/// The tool call which a statement is executed for
+#[derive(Clone, Debug, Default)]
+pub struct CallContext {
+    /// The name of the tool which was called
+    pub tool: String,
+    /// The authenticated subject of the bearer token, if any
+    pub subject: Option<String>,
+}
+
+impl CallContext {
+    /// Run a future with this tool call as the current tool call
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CALL.scope(self, f).await
+    }
+
+    /// Get the tool call which is currently being handled, if any
+    pub fn current() -> Option<CallContext> {
+        CALL.try_with(|v| v.clone()).ok()
+    }
+}
+
+/// A single entry in the audit log
+#[derive(Debug, Serialize)]
+pub struct AuditEntry<'a> {
+    /// The time the statement completed
+    pub timestamp: String,
+    /// The id of the client session
+    pub connection_id: &'a str,
+    /// The authenticated subject of the bearer token, if any
+    pub subject: Option<&'a str>,
+    /// The SurrealDB endpoint the statement was executed on
+    pub endpoint: Option<&'a str>,
+    /// The namespace the statement was executed in
+    pub namespace: Option<&'a str>,
+    /// The database the statement was executed in
+    pub database: Option<&'a str>,
+    /// The named connection the statement was executed on, if not the default
+    pub connection: Option<&'a str>,
+    /// The name of the tool which executed the statement
+    pub tool: Option<&'a str>,
+    /// The id of the query
+    pub query_id: u64,
+    /// The SurrealQL which was executed
+    pub query: &'a str,
+    /// The names of the parameters bound to the query
+    pub parameters: Vec<&'a str>,
+    /// Whether the query succeeded (ok) or failed (error)
+    pub outcome: &'static str,
+    /// The error message if the query failed
+    pub error: Option<&'a str>,
+    /// The time taken to execute the query in milliseconds
+    pub duration_ms: u128,
+}
+
+/// A durable audit log of executed statements, written as JSON lines
+pub struct AuditLog {
+    /// The writer which appends to the current audit log file
+    writer: NonBlocking,
+    /// The guard which flushes buffered entries when dropped
+    _guard: WorkerGuard,
+}
+
+impl AuditLog {
+    /// Open the audit log in a directory, creating the directory if necessary
+    pub fn open(config: &AuditConfig) -> Result<Self> {
+        let directory = Path::new(&config.directory);
+        // Create the audit log directory
+        std::fs::create_dir_all(directory).map_err(|e| {
+            anyhow!(
+                "Failed to create audit log directory {}: {e}",
+                directory.display()
+            )
+        })?;
+        // Create the rolling audit log file
+        let appender = RollingFileAppender::builder()
+            .rotation(config.rotation.into())
+            .filename_prefix(AUDIT_FILE_PREFIX)
+            .filename_suffix(AUDIT_FILE_SUFFIX)
+            .build(directory)
+            .map_err(|e| anyhow!("Failed to open audit log {}: {e}", directory.display()))?;
+        // Write entries on a background thread, blocking rather than dropping entries when busy
+        let (writer, guard) = NonBlockingBuilder::default().lossy(false).finish(appender);
+        Ok(Self {
+            writer,
+            _guard: guard,
+        })
+    }
+
+    /// Append an entry to the audit log
+    pub fn record(&self, entry: &AuditEntry) {
+        // Serialize the entry as a single line
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize audit log entry");
+                return;
+            }
+        };
+        line.push(b'\n');
+        // Append the line to the audit log file
+        if let Err(e) = self.writer.clone().write_all(&line) {
+            warn!(error = %e, "Failed to write audit log entry");
+        }
+    }
+}
+
+/// Get the current time as an RFC 3339 timestamp
+pub fn timestamp() -> String {
+    Datetime::now().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let directory =
+            std::env::temp_dir().join(format!("surrealmcp-audit-{:x}", rand::random::<u64>()));
+        let config = AuditConfig {
+            directory: directory.to_string_lossy().to_string(),
+            rotation: AuditRotation::Never,
+        };
+        // The current tool call is only available within its scope
+        assert!(CallContext::current().is_none());
+        let context = CallContext {
+            tool: "query".to_string(),
+            subject: Some("user@example.com".to_string()),
+        };
+        let current = context
+            .scope(async { CallContext::current() })
+            .await
+            .unwrap();
+        assert_eq!(current.tool, "query");
+        // Entries are written as JSON lines
+        let log = AuditLog::open(&config).unwrap();
+        for query_id in 0..2 {
+            log.record(&AuditEntry {
+                timestamp: timestamp(),
+                connection_id: "conn",
+                subject: current.subject.as_deref(),
+                endpoint: Some("memory"),
+                namespace: Some("test"),
+                database: None,
+                connection: None,
+                tool: Some(&current.tool),
+                query_id,
+                query: "SELECT * FROM person WHERE age > $age",
+                parameters: vec!["age"],
+                outcome: "ok",
+                error: None,
+                duration_ms: 1,
+            });
+        }
+        drop(log);
+        let contents = std::fs::read_to_string(directory.join("surrealmcp-audit.jsonl")).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|v| serde_json::from_str(v).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["query_id"], 1);
+        assert_eq!(lines[0]["subject"], "user@example.com");
+        assert_eq!(lines[0]["parameters"], serde_json::json!(["age"]));
+        assert!(lines[0]["timestamp"].as_str().unwrap().ends_with('Z'));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,5 +1,10 @@
 use clap::{Parser, Subcommand};
 
+use crate::audit::AuditRotation;
+use crate::db::SigninLevel;
+use crate::tools::ConfirmDestructive;
+use crate::utils::JsonEncoding;
+
 #[derive(Parser)]
 #[command(name = "surrealmcp")]
 #[command(about = "SurrealDB MCP Server")]
@@ -28,6 +33,23 @@ pub enum Commands {
         /// The SurrealDB password to use
         #[arg(short, long, env = "SURREALDB_PASS")]
         pass: Option<String>,
+        /// The level at which to sign in to SurrealDB
+        #[arg(
+            long,
+            env = "SURREALDB_SIGNIN_LEVEL",
+            value_enum,
+            default_value = "root"
+        )]
+        signin_level: SigninLevel,
+        /// The SurrealDB record access method to sign in with
+        #[arg(long, env = "SURREALDB_ACCESS")]
+        access: Option<String>,
+        /// The secret passed to the record access method as `$key` along with the subject
+        #[arg(long, env = "SURREALDB_ACCESS_KEY")]
+        access_key: Option<String>,
+        /// Whether to authenticate with SurrealDB using the bearer token of the MCP client
+        #[arg(long, env = "SURREAL_MCP_FORWARD_TOKEN", default_value = "false")]
+        forward_token: bool,
         /// The MCP server bind address (host:port)
         #[arg(long, env = "SURREAL_MCP_BIND_ADDRESS", group = "server")]
         bind_address: Option<String>,
@@ -43,6 +65,9 @@ pub enum Commands {
         /// Whether to require authentication for the MCP server
         #[arg(long, env = "SURREAL_MCP_AUTH_DISABLED", default_value = "false")]
         auth_disabled: bool,
+        /// Whether tool calls and resource reads require MCP scopes in the bearer token
+        #[arg(long, env = "SURREAL_MCP_REQUIRE_SCOPES", default_value = "false")]
+        require_scopes: bool,
         /// The URL address that the MCP server is accessible at
         #[arg(
             long,
@@ -64,6 +89,126 @@ pub enum Commands {
             default_value = "https://mcp.surrealdb.com/"
         )]
         auth_audience: String,
+        /// Comma-separated list of trusted token issuers (SurrealDB Cloud if not set)
+        #[arg(long, env = "SURREAL_MCP_AUTH_ISSUER", value_delimiter = ',')]
+        auth_issuer: Vec<String>,
+        /// The JWKS URL of the trusted issuers (discovered using OpenID Connect if not set)
+        #[arg(long, env = "SURREAL_MCP_AUTH_JWKS_URL")]
+        auth_jwks_url: Option<String>,
+        /// Comma-separated list of allowed JWT signature algorithms
+        #[arg(long, env = "SURREAL_MCP_AUTH_ALGORITHMS", value_delimiter = ',')]
+        auth_algorithms: Vec<String>,
+        /// The number of seconds to cache fetched signing keys for (default: 3600)
+        #[arg(long, env = "SURREAL_MCP_AUTH_JWKS_CACHE_TTL")]
+        auth_jwks_cache_ttl: Option<u64>,
+        /// The base64url-encoded 256-bit key for decrypting JWE tokens
+        #[arg(long, env = "SURREAL_MCP_AUTH_JWE_KEY")]
+        auth_jwe_key: Option<String>,
+        /// The path to a file containing the key for decrypting JWE tokens
+        #[arg(long, env = "SURREAL_MCP_AUTH_JWE_KEY_FILE")]
+        auth_jwe_key_file: Option<String>,
+        /// Whether to accept JWE tokens by validating only their header when no key is set
+        #[arg(
+            long,
+            env = "SURREAL_MCP_AUTH_INSECURE_JWE_HEADERS",
+            default_value = "false"
+        )]
+        auth_insecure_jwe_headers: bool,
+        /// The token introspection endpoint (RFC 7662) for validating opaque tokens
+        #[arg(long, env = "SURREAL_MCP_AUTH_INTROSPECTION_URL")]
+        auth_introspection_url: Option<String>,
+        /// The client ID to authenticate with the introspection endpoint
+        #[arg(long, env = "SURREAL_MCP_AUTH_INTROSPECTION_CLIENT_ID")]
+        auth_introspection_client_id: Option<String>,
+        /// The client secret to authenticate with the introspection endpoint
+        #[arg(long, env = "SURREAL_MCP_AUTH_INTROSPECTION_CLIENT_SECRET")]
+        auth_introspection_client_secret: Option<String>,
+        /// Whether to refuse queries and tools which modify data or schema
+        #[arg(long, env = "SURREAL_MCP_READ_ONLY", default_value = "false")]
+        read_only: bool,
+        /// The default JSON encoding of tool parameters and results
+        #[arg(
+            long,
+            env = "SURREAL_MCP_JSON_ENCODING",
+            value_enum,
+            default_value = "simple"
+        )]
+        json_encoding: JsonEncoding,
+        /// Maximum number of rows returned in a single page of results (0 for unlimited)
+        #[arg(long, env = "SURREAL_MCP_MAX_RESULT_ROWS", default_value = "1000")]
+        max_result_rows: usize,
+        /// Maximum size in bytes of a single page of results (0 for unlimited)
+        #[arg(long, env = "SURREAL_MCP_MAX_RESULT_BYTES", default_value = "1048576")]
+        max_result_bytes: usize,
+        /// Maximum size in bytes of the remaining rows held for cursors in each client session (0 for unlimited)
+        #[arg(
+            long,
+            env = "SURREAL_MCP_MAX_BUFFERED_RESULT_BYTES",
+            default_value = "16777216"
+        )]
+        max_buffered_result_bytes: usize,
+        /// Default timeout in seconds for queries (0 for no timeout)
+        #[arg(long, env = "SURREAL_MCP_QUERY_TIMEOUT", default_value = "60")]
+        query_timeout: u64,
+        /// Maximum number of concurrent live queries in each client session
+        #[arg(long, env = "SURREAL_MCP_MAX_LIVE_QUERIES", default_value = "10")]
+        max_live_queries: usize,
+        /// Time in seconds after which an idle transaction is rolled back (0 for never)
+        #[arg(long, env = "SURREAL_MCP_TRANSACTION_TIMEOUT", default_value = "300")]
+        transaction_timeout: u64,
+        /// Maximum number of pooled connections to the configured endpoint in HTTP mode (0 to disable pooling)
+        #[arg(long, env = "SURREAL_MCP_POOL_MAX_SIZE", default_value = "10")]
+        pool_max_size: usize,
+        /// Time in seconds after which a pooled connection without sessions is closed
+        #[arg(long, env = "SURREAL_MCP_POOL_IDLE_TIMEOUT", default_value = "300")]
+        pool_idle_timeout: u64,
+        /// Interval in seconds between health checks of pooled connections
+        #[arg(
+            long,
+            env = "SURREAL_MCP_POOL_HEALTH_CHECK_INTERVAL",
+            default_value = "30"
+        )]
+        pool_health_check_interval: u64,
+        /// When destructive operations require confirmation from the client user
+        #[arg(
+            long,
+            env = "SURREAL_MCP_CONFIRM_DESTRUCTIVE",
+            value_enum,
+            default_value = "never"
+        )]
+        confirm_destructive: ConfirmDestructive,
+        /// Run destructive operations without confirmation if the client does not support elicitation
+        #[arg(long, env = "SURREAL_MCP_ALLOW_UNCONFIRMED", default_value = "false")]
+        allow_unconfirmed: bool,
+        /// Directory to write a JSON-lines audit log of every executed query to
+        #[arg(long, env = "SURREAL_MCP_AUDIT_LOG")]
+        audit_log: Option<String>,
+        /// How often a new audit log file is started
+        #[arg(
+            long,
+            env = "SURREAL_MCP_AUDIT_LOG_ROTATION",
+            value_enum,
+            default_value = "daily"
+        )]
+        audit_log_rotation: AuditRotation,
+        /// Comma-separated list of tools to expose (all tools if not set)
+        #[arg(long, env = "SURREAL_MCP_ALLOW_TOOLS", value_delimiter = ',')]
+        allow_tools: Vec<String>,
+        /// Comma-separated list of tools to never expose
+        #[arg(long, env = "SURREAL_MCP_DENY_TOOLS", value_delimiter = ',')]
+        deny_tools: Vec<String>,
+        /// Comma-separated list of endpoint patterns which clients can connect to
+        #[arg(long, env = "SURREAL_MCP_ALLOW_ENDPOINTS", value_delimiter = ',')]
+        allow_endpoints: Vec<String>,
+        /// Comma-separated list of namespace patterns which clients can use
+        #[arg(long, env = "SURREAL_MCP_ALLOW_NAMESPACES", value_delimiter = ',')]
+        allow_namespaces: Vec<String>,
+        /// Comma-separated list of database patterns which clients can use
+        #[arg(long, env = "SURREAL_MCP_ALLOW_DATABASES", value_delimiter = ',')]
+        allow_databases: Vec<String>,
+        /// Path to a TOML configuration file
+        #[arg(long, env = "SURREAL_MCP_CONFIG")]
+        config: Option<String>,
         /// SurrealDB Cloud access token (used instead of fetching tokens)
         #[arg(long, env = "SURREAL_MCP_CLOUD_ACCESS_TOKEN")]
         cloud_access_token: Option<String>,
@@ -22,7 +22,7 @@ pub struct CloudUser {
 }
 
 /// An organization in SurrealDB Cloud
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CloudOrganization {
     pub id: String,
     pub name: String,
@@ -36,7 +36,7 @@ pub struct CloudOrganization {
 }
 
 /// A plan in SurrealDB Cloud
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CloudPlan {
     pub id: String,
     pub name: String,
@@ -45,7 +45,7 @@ pub struct CloudPlan {
 }
 
 /// A cloud instance in SurrealDB Cloud
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CloudInstance {
     pub id: String,
     pub name: String,
@@ -69,14 +69,14 @@ pub struct CloudInstanceAuth {
 }
 
 /// A cloud instance status in SurrealDB Cloud
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CloudInstanceStatus {
     pub phase: String,
     pub db_backups: Vec<CloudInstanceBackup>,
 }
 
 /// A cloud instance backup in SurrealDB Cloud
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CloudInstanceBackup {
     pub snapshot_started_at: String,
     pub snapshot_id: String,
@@ -0,0 +1,668 @@
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::db::SigninLevel;
+
+/// Configuration loaded from the file specified with `--config`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The tools which are exposed by the server
+    pub tools: ToolsConfig,
+    /// The validation of bearer tokens
+    pub auth: AuthConfig,
+    /// The endpoints, namespaces, and databases which clients can use
+    pub connections: ConnectionsConfig,
+    /// Named connection profiles which clients can connect to
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+impl ConfigFile {
+    /// Load and parse a TOML configuration file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        // Read the configuration file contents
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read configuration file {}: {e}", path.display()))?;
+        // Parse the configuration file contents
+        Self::parse(&contents)
+            .map_err(|e| anyhow!("Failed to parse configuration file {}: {e}", path.display()))
+    }
+
+    /// Parse a TOML configuration string
+    pub fn parse(contents: &str) -> Result<Self> {
+        let config: Self = toml::from_str(contents).map_err(|e| anyhow!(e.to_string()))?;
+        // Each profile can only specify its password in one way
+        for (name, profile) in &config.profiles {
+            if profile.password.is_some() && profile.password_env.is_some() {
+                return Err(anyhow!(
+                    "Profile '{name}' can not specify both password and password_env"
+                ));
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Allow- and deny-lists of tool names
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolsConfig {
+    /// The tools which are exposed (all tools if empty)
+    pub allow: Vec<String>,
+    /// The tools which are never exposed, taking precedence over `allow`
+    pub deny: Vec<String>,
+}
+
+impl ToolsConfig {
+    /// Extend the lists with additional tool names, skipping duplicates
+    pub fn merge(mut self, allow: Vec<String>, deny: Vec<String>) -> Self {
+        for name in allow {
+            if !self.allow.contains(&name) {
+                self.allow.push(name);
+            }
+        }
+        for name in deny {
+            if !self.deny.contains(&name) {
+                self.deny.push(name);
+            }
+        }
+        self
+    }
+
+    /// Check whether a tool is enabled by this configuration
+    pub fn is_enabled(&self, name: &str) -> bool {
+        // Denied tools are always disabled
+        if self.deny.iter().any(|v| v == name) {
+            return false;
+        }
+        // An empty allow-list enables all tools
+        self.allow.is_empty() || self.allow.iter().any(|v| v == name)
+    }
+
+    /// Get every tool name referenced by this configuration
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.allow
+            .iter()
+            .chain(self.deny.iter())
+            .map(String::as_str)
+    }
+}
+
+/// Allow-lists of the endpoints, namespaces, and databases which clients can use
+///
+/// Each entry is a pattern, in which `*` matches any number of characters and
+/// `?` matches a single character.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConnectionsConfig {
+    /// The endpoints which can be connected to (any endpoint if empty)
+    pub allow_endpoints: Vec<String>,
+    /// The namespaces which can be used (any namespace if empty)
+    pub allow_namespaces: Vec<String>,
+    /// The databases which can be used (any database if empty)
+    pub allow_databases: Vec<String>,
+    /// Whether embedded engines are only allowed by patterns for embedded engines
+    #[serde(skip)]
+    pub deny_embedded: bool,
+}
+
+impl ConnectionsConfig {
+    /// Extend the lists with additional patterns, skipping duplicates
+    pub fn merge(
+        mut self,
+        endpoints: Vec<String>,
+        namespaces: Vec<String>,
+        databases: Vec<String>,
+    ) -> Self {
+        let lists = [
+            (&mut self.allow_endpoints, endpoints),
+            (&mut self.allow_namespaces, namespaces),
+            (&mut self.allow_databases, databases),
+        ];
+        for (list, patterns) in lists {
+            for pattern in patterns {
+                if !list.contains(&pattern) {
+                    list.push(pattern);
+                }
+            }
+        }
+        self
+    }
+
+    /// Check whether an endpoint is allowed by this configuration
+    ///
+    /// Patterns for network endpoints (`scheme://host:port`) are matched against
+    /// the scheme, host, and port of the endpoint, ignoring its path, and match
+    /// any port if none is specified. Other patterns, such as `memory`, `cloud:*`,
+    /// or `rocksdb:/data/*`, are matched against the whole endpoint. Endpoints
+    /// containing credentials or `..` path segments are never allowed. When
+    /// embedded engines are denied, they must match a pattern for an embedded
+    /// engine, such as `memory`, rather than a pattern such as `*`.
+    pub fn is_endpoint_allowed(&self, endpoint: &str) -> bool {
+        // Embedded engines must be explicitly allowed when they are denied
+        let embedded = self.deny_embedded && is_embedded(endpoint);
+        // An empty allow-list allows all endpoints
+        if self.allow_endpoints.is_empty() {
+            return !embedded;
+        }
+        // Refuse endpoints which could escape an allowed directory
+        if endpoint.split(['/', '\\']).any(|v| v == "..") {
+            return false;
+        }
+        // Check the endpoint against each pattern
+        self.allow_endpoints.iter().any(|pattern| {
+            // Only patterns for a specific embedded engine allow embedded engines
+            if embedded && (!is_embedded(pattern) || scheme(pattern).contains(['*', '?'])) {
+                return false;
+            }
+            match (
+                EndpointParts::parse(pattern),
+                EndpointParts::parse(endpoint),
+            ) {
+                (Some(p), Some(e)) => {
+                    glob(&p.scheme, &e.scheme)
+                        && glob(&p.host, &e.host)
+                        && p.port
+                            .is_none_or(|port| e.port.is_some_and(|v| glob(port, v)))
+                }
+                _ if pattern.contains("://") || endpoint.contains("://") => false,
+                _ => glob(pattern, endpoint),
+            }
+        })
+    }
+
+    /// Check whether a namespace is allowed by this configuration
+    pub fn is_namespace_allowed(&self, namespace: &str) -> bool {
+        self.allow_namespaces.is_empty() || self.allow_namespaces.iter().any(|v| glob(v, namespace))
+    }
+
+    /// Check whether a database is allowed by this configuration
+    pub fn is_database_allowed(&self, database: &str) -> bool {
+        self.allow_databases.is_empty() || self.allow_databases.iter().any(|v| glob(v, database))
+    }
+}
+
+/// Check whether an endpoint uses an engine embedded within the server
+///
+/// Only network endpoints (`http`, `https`, `ws`, and `wss`) and SurrealDB Cloud
+/// instances (`cloud:*`) connect to another server, while other engines, such
+/// as `memory`, `file:`, `rocksdb:`, `surrealkv:`, or `tikv:`, run a datastore
+/// within the server with full access to it.
+pub fn is_embedded(endpoint: &str) -> bool {
+    !["http", "https", "ws", "wss", "cloud"].contains(&scheme(endpoint).as_str())
+}
+
+/// Get the lowercase scheme of an endpoint, or the whole endpoint without one
+fn scheme(endpoint: &str) -> String {
+    match endpoint.split_once("://") {
+        Some((scheme, _)) => scheme,
+        None => endpoint.split(':').next().unwrap_or_default(),
+    }
+    .to_ascii_lowercase()
+}
+
+/// The scheme, host, and port of a network endpoint
+struct EndpointParts<'a> {
+    /// The lowercase scheme of the endpoint
+    scheme: String,
+    /// The lowercase host of the endpoint
+    host: String,
+    /// The port of the endpoint, if specified
+    port: Option<&'a str>,
+}
+
+impl<'a> EndpointParts<'a> {
+    /// Split a `scheme://host:port/path` endpoint into its parts
+    ///
+    /// Returns `None` for endpoints which are not network endpoints, or which
+    /// contain credentials.
+    fn parse(endpoint: &'a str) -> Option<Self> {
+        let (scheme, rest) = endpoint.split_once("://")?;
+        // Get the authority before any path, query, or fragment
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        // Refuse endpoints containing credentials
+        if authority.contains('@') {
+            return None;
+        }
+        // Split the host and port, allowing for IPv6 addresses
+        let (host, port) = match authority.rfind(':') {
+            Some(i) if !authority[i..].contains(']') => {
+                (&authority[..i], Some(&authority[i + 1..]))
+            }
+            _ => (authority, None),
+        };
+        Some(Self {
+            scheme: scheme.to_lowercase(),
+            host: host.to_lowercase(),
+            port,
+        })
+    }
+}
+
+/// Match a value against a pattern, where `*` matches any number of characters
+/// and `?` matches a single character
+fn glob(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    // The position of the last `*`, and the value position it was tried at
+    let mut star = None;
+    let (mut p, mut v) = (0, 0);
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, v));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            // Backtrack to let the last `*` match one more character
+            _ => match star {
+                Some((sp, sv)) => {
+                    star = Some((sp, sv + 1));
+                    p = sp + 1;
+                    v = sv + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    // Any remaining pattern characters must all be `*`
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A named connection profile, whose credentials are never revealed to clients
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// A description of the profile for clients choosing between profiles
+    pub description: Option<String>,
+    /// The SurrealDB endpoint URL to connect to
+    pub endpoint: String,
+    /// The namespace to use
+    pub namespace: Option<String>,
+    /// The database to use
+    pub database: Option<String>,
+    /// The username to sign in with
+    pub username: Option<String>,
+    /// The password to sign in with
+    pub password: Option<String>,
+    /// The environment variable containing the password to sign in with
+    pub password_env: Option<String>,
+    /// The level at which to sign in
+    #[serde(default)]
+    pub signin_level: SigninLevel,
+    /// The record access method to sign in with
+    pub access: Option<String>,
+}
+
+impl ProfileConfig {
+    /// Get the password of the profile, reading it from the environment if specified
+    pub fn password(&self) -> Result<Option<String>> {
+        match &self.password_env {
+            Some(name) => std::env::var(name)
+                .map(Some)
+                .map_err(|_| anyhow!("The environment variable {name} is not set")),
+            None => Ok(self.password.clone()),
+        }
+    }
+}
+
+/// Trusted identity providers and the validation of their bearer tokens
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// The identity providers whose tokens are trusted (SurrealDB Cloud if empty)
+    pub issuers: Vec<IssuerConfig>,
+    /// The algorithms which token signatures may use (all asymmetric algorithms if empty)
+    pub algorithms: Vec<String>,
+    /// The number of seconds to cache fetched signing keys for
+    pub jwks_cache_ttl: Option<u64>,
+    /// The base64url-encoded key for decrypting JWE tokens
+    pub jwe_key: Option<String>,
+    /// The path to a file containing the key for decrypting JWE tokens
+    pub jwe_key_file: Option<String>,
+    /// Whether JWE tokens are accepted by validating only their header when no key is set
+    pub insecure_jwe_headers: bool,
+    /// The token introspection endpoint for validating opaque tokens
+    pub introspection_url: Option<String>,
+    /// The client ID to authenticate with the introspection endpoint
+    pub introspection_client_id: Option<String>,
+    /// The client secret to authenticate with the introspection endpoint
+    pub introspection_client_secret: Option<String>,
+}
+
+/// An identity provider whose tokens are trusted
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IssuerConfig {
+    /// The expected `iss` claim of tokens from this identity provider
+    pub issuer: String,
+    /// The URL of the JSON Web Key Set (discovered using OpenID Connect if not set)
+    pub jwks_url: Option<String>,
+}
+
+impl AuthConfig {
+    /// Extend the configuration with options specified on the command line
+    ///
+    /// The JWKS URL applies to each of the additional issuers, and the
+    /// algorithms and cache TTL replace those in the file when specified.
+    pub fn merge(
+        mut self,
+        issuers: Vec<String>,
+        jwks_url: Option<String>,
+        algorithms: Vec<String>,
+        jwks_cache_ttl: Option<u64>,
+    ) -> Self {
+        for issuer in issuers {
+            if !self.issuers.iter().any(|v| v.issuer == issuer) {
+                self.issuers.push(IssuerConfig {
+                    issuer,
+                    jwks_url: jwks_url.clone(),
+                });
+            }
+        }
+        if !algorithms.is_empty() {
+            self.algorithms = algorithms;
+        }
+        if jwks_cache_ttl.is_some() {
+            self.jwks_cache_ttl = jwks_cache_ttl;
+        }
+        self
+    }
+
+    /// Replace the JWE decryption key with one specified on the command line
+    pub fn with_jwe_key(mut self, key: Option<String>, file: Option<String>) -> Self {
+        if key.is_some() || file.is_some() {
+            self.jwe_key = key;
+            self.jwe_key_file = file;
+        }
+        self
+    }
+
+    /// Accept JWE tokens without decrypting them if enabled on the command line
+    pub fn with_insecure_jwe_headers(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.insecure_jwe_headers = true;
+        }
+        self
+    }
+
+    /// Replace the token introspection options with those specified on the command line
+    pub fn with_introspection(
+        mut self,
+        url: Option<String>,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+    ) -> Self {
+        if url.is_some() {
+            self.introspection_url = url;
+        }
+        if client_id.is_some() {
+            self.introspection_client_id = client_id;
+        }
+        if client_secret.is_some() {
+            self.introspection_client_secret = client_secret;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tools_config() {
+        let config = ConfigFile::parse(
+            r#"
+            [tools]
+            allow = ["query", "select", "create_cloud_instance"]
+            deny = ["create_cloud_instance"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.tools.allow.len(), 3);
+        assert_eq!(config.tools.deny, vec!["create_cloud_instance"]);
+        // An empty file is valid
+        let config = ConfigFile::parse("").unwrap();
+        assert!(config.tools.allow.is_empty());
+        assert!(config.tools.deny.is_empty());
+        // Unknown keys are rejected
+        assert!(ConfigFile::parse("[tools]\nenable = [\"query\"]").is_err());
+        assert!(ConfigFile::parse("[tool]\nallow = [\"query\"]").is_err());
+    }
+
+    #[test]
+    fn test_parse_auth_config() {
+        let config = ConfigFile::parse(
+            r#"
+            [auth]
+            algorithms = ["RS256"]
+            jwks_cache_ttl = 600
+
+            [[auth.issuers]]
+            issuer = "https://keycloak.example.com/realms/main"
+
+            [[auth.issuers]]
+            issuer = "https://auth.example.com/"
+            jwks_url = "https://auth.example.com/keys.json"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.auth.issuers.len(), 2);
+        assert_eq!(config.auth.issuers[0].jwks_url, None);
+        assert_eq!(config.auth.jwks_cache_ttl, Some(600));
+        // Command line issuers are added, and other options replace the file
+        let auth = config.auth.merge(
+            vec![
+                "https://auth.example.com/".into(),
+                "https://login.example.com/".into(),
+            ],
+            Some("https://login.example.com/jwks".into()),
+            vec!["ES256".into()],
+            None,
+        );
+        assert_eq!(auth.issuers.len(), 3);
+        assert_eq!(
+            auth.issuers[2].jwks_url.as_deref(),
+            Some("https://login.example.com/jwks")
+        );
+        assert_eq!(auth.algorithms, vec!["ES256"]);
+        assert_eq!(auth.jwks_cache_ttl, Some(600));
+        // Command line keys replace the key in the file
+        let config = ConfigFile::parse(
+            r#"
+            [auth]
+            jwe_key_file = "/etc/surrealmcp/jwe.key"
+            introspection_url = "https://auth.example.com/introspect"
+            introspection_client_id = "surrealmcp"
+            "#,
+        )
+        .unwrap();
+        let auth = config
+            .auth
+            .with_jwe_key(Some("a2V5".into()), None)
+            .with_insecure_jwe_headers(false)
+            .with_introspection(None, None, Some("secret".into()));
+        assert_eq!(auth.jwe_key.as_deref(), Some("a2V5"));
+        assert_eq!(auth.jwe_key_file, None);
+        assert!(!auth.insecure_jwe_headers);
+        assert!(
+            auth.clone()
+                .with_insecure_jwe_headers(true)
+                .insecure_jwe_headers
+        );
+        assert_eq!(
+            auth.introspection_url.as_deref(),
+            Some("https://auth.example.com/introspect")
+        );
+        assert_eq!(auth.introspection_client_id.as_deref(), Some("surrealmcp"));
+        assert_eq!(auth.introspection_client_secret.as_deref(), Some("secret"));
+        // Unknown keys are rejected
+        assert!(ConfigFile::parse("[[auth.issuers]]\nissuer = \"a\"\njwks = \"b\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let config = ConfigFile::parse(
+            r#"
+            [profiles.dev]
+            endpoint = "memory"
+
+            [profiles.staging]
+            description = "Staging database"
+            endpoint = "wss://staging.example.com"
+            namespace = "app"
+            database = "main"
+            username = "reader"
+            password_env = "SURREALMCP_TEST_UNSET_PASSWORD"
+            signin_level = "database"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.profiles.keys().collect::<Vec<_>>(),
+            vec!["dev", "staging"]
+        );
+        let staging = &config.profiles["staging"];
+        assert_eq!(staging.signin_level, SigninLevel::Database);
+        assert!(staging.password().is_err());
+        assert_eq!(config.profiles["dev"].signin_level, SigninLevel::Root);
+        assert_eq!(config.profiles["dev"].password().unwrap(), None);
+        // Profiles require an endpoint, and unknown keys are rejected
+        assert!(ConfigFile::parse("[profiles.dev]\nnamespace = \"app\"").is_err());
+        assert!(ConfigFile::parse("[profiles.dev]\nendpoint = \"memory\"\nurl = \"a\"").is_err());
+        // Profiles can not specify both a password and a password variable
+        let res = ConfigFile::parse(
+            "[profiles.dev]\nendpoint = \"memory\"\npassword = \"a\"\npassword_env = \"B\"",
+        );
+        assert!(
+            res.unwrap_err()
+                .to_string()
+                .contains("both password and password_env")
+        );
+    }
+
+    #[test]
+    fn test_glob() {
+        assert!(glob("memory", "memory"));
+        assert!(glob("app_*", "app_production"));
+        assert!(glob("*.example.com", "db.eu.example.com"));
+        assert!(glob("db?", "db1"));
+        assert!(glob("*", ""));
+        assert!(!glob("app_*", "other"));
+        assert!(!glob("db?", "db10"));
+        assert!(!glob("*.example.com", "example.com.evil.com"));
+    }
+
+    #[test]
+    fn test_connections_config() {
+        let config = ConfigFile::parse(
+            r#"
+            [connections]
+            allow_endpoints = ["wss://*.example.com", "ws://localhost:8000", "memory", "rocksdb:/data/*"]
+            allow_namespaces = ["app_*"]
+            "#,
+        )
+        .unwrap();
+        let connections = config.connections.merge(
+            vec!["cloud:*".into(), "memory".into()],
+            vec![],
+            vec!["main".into()],
+        );
+        assert_eq!(connections.allow_endpoints.len(), 5);
+        // Network endpoints are matched on their scheme, host, and port
+        assert!(connections.is_endpoint_allowed("wss://db.example.com/rpc"));
+        assert!(connections.is_endpoint_allowed("WSS://DB.example.com:443"));
+        assert!(connections.is_endpoint_allowed("ws://localhost:8000/rpc"));
+        assert!(!connections.is_endpoint_allowed("ws://localhost:8001"));
+        assert!(!connections.is_endpoint_allowed("ws://localhost"));
+        assert!(!connections.is_endpoint_allowed("ws://db.example.com"));
+        assert!(!connections.is_endpoint_allowed("wss://evil.com/.example.com"));
+        assert!(!connections.is_endpoint_allowed("wss://db.example.com@evil.com"));
+        assert!(!connections.is_endpoint_allowed("http://169.254.169.254"));
+        // Other endpoints are matched as a whole
+        assert!(connections.is_endpoint_allowed("memory"));
+        assert!(connections.is_endpoint_allowed("cloud:abc123"));
+        assert!(connections.is_endpoint_allowed("rocksdb:/data/app"));
+        assert!(!connections.is_endpoint_allowed("rocksdb:/data/../etc/passwd"));
+        assert!(!connections.is_endpoint_allowed("file:/etc/passwd"));
+        // Namespaces and databases are matched against their patterns
+        assert!(connections.is_namespace_allowed("app_production"));
+        assert!(!connections.is_namespace_allowed("system"));
+        assert!(connections.is_database_allowed("main"));
+        assert!(!connections.is_database_allowed("other"));
+        // Empty lists allow everything
+        let connections = ConnectionsConfig::default();
+        assert!(connections.is_endpoint_allowed("file:/etc/passwd"));
+        assert!(connections.is_namespace_allowed("system"));
+    }
+
+    #[test]
+    fn test_deny_embedded_engines() {
+        let mut connections = ConnectionsConfig {
+            deny_embedded: true,
+            ..Default::default()
+        };
+        // Embedded engines are denied, while other endpoints are allowed
+        for endpoint in [
+            "memory",
+            "mem://",
+            "file:/data",
+            "rocksdb:/data",
+            "SurrealKV://data",
+        ] {
+            assert!(!connections.is_endpoint_allowed(endpoint), "{endpoint}");
+        }
+        assert!(!connections.is_endpoint_allowed("tikv://pd:2379"));
+        assert!(connections.is_endpoint_allowed("wss://db.example.com"));
+        assert!(connections.is_endpoint_allowed("cloud:abc123"));
+        // Embedded engines are only allowed by patterns for embedded engines
+        connections.allow_endpoints = vec!["*".into(), "m?m*".into(), "rocksdb:/data/*".into()];
+        assert!(!connections.is_endpoint_allowed("memory"));
+        assert!(!connections.is_endpoint_allowed("file:/etc/passwd"));
+        assert!(connections.is_endpoint_allowed("rocksdb:/data/app"));
+        assert!(connections.is_endpoint_allowed("cloud:abc123"));
+    }
+
+    #[test]
+    fn test_tools_config_is_enabled() {
+        // All tools are enabled by default
+        let config = ToolsConfig::default();
+        assert!(config.is_enabled("query"));
+        assert!(config.is_enabled("connect_endpoint"));
+        // Only allowed tools are enabled
+        let config = ToolsConfig::default().merge(vec!["query".into(), "select".into()], vec![]);
+        assert!(config.is_enabled("query"));
+        assert!(config.is_enabled("select"));
+        assert!(!config.is_enabled("create"));
+        // Denied tools take precedence over allowed tools
+        let config = config.merge(vec![], vec!["select".into()]);
+        assert!(config.is_enabled("query"));
+        assert!(!config.is_enabled("select"));
+        // Denied tools are disabled without an allow-list
+        let config = ToolsConfig::default().merge(vec![], vec!["connect_endpoint".into()]);
+        assert!(config.is_enabled("query"));
+        assert!(!config.is_enabled("connect_endpoint"));
+    }
+
+    #[test]
+    fn test_tools_config_merge() {
+        let config = ToolsConfig {
+            allow: vec!["query".into()],
+            deny: vec!["create".into()],
+        }
+        .merge(
+            vec!["query".into(), "select".into()],
+            vec!["create".into(), "delete".into()],
+        );
+        assert_eq!(config.allow, vec!["query", "select"]);
+        assert_eq!(config.deny, vec!["create", "delete"]);
+        assert_eq!(config.names().count(), 4);
+    }
+}
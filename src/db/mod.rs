@@ -1,13 +1,83 @@
 use anyhow::{Result, anyhow};
-use surrealdb::{Surreal, engine::any, engine::any::Any, opt::auth::Root};
+use serde::Deserialize;
+use surrealdb::types::{Object, Value};
+use surrealdb::{
+    Surreal,
+    engine::any,
+    engine::any::Any,
+    opt::auth::{Database, Namespace, Record, Root},
+};
 use tracing::{debug, instrument};
 
+/// The level at which client connections sign in to SurrealDB
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SigninLevel {
+    /// Sign in as a root user
+    #[default]
+    Root,
+    /// Sign in as a namespace user
+    Namespace,
+    /// Sign in as a database user
+    Database,
+    /// Sign in as a record user using a record access method
+    Record,
+}
+
+/// How client connections authenticate with SurrealDB
+#[derive(Clone, Debug, Default)]
+pub struct SigninConfig {
+    /// The level at which to sign in with the configured credentials
+    pub level: SigninLevel,
+    /// The record access method to sign in with (`DEFINE ACCESS ... TYPE RECORD`)
+    pub access: Option<String>,
+    /// The secret passed to record access methods as `$key` along with the subject
+    pub access_key: Option<String>,
+    /// Whether to authenticate with the bearer token of the MCP client
+    pub forward_token: bool,
+}
+
+/// The credentials which a client connection authenticates with
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Credentials {
+    /// The level at which to sign in
+    pub level: SigninLevel,
+    /// The username to sign in with
+    pub username: Option<String>,
+    /// The password to sign in with
+    pub password: Option<String>,
+    /// The record access method to sign in with
+    pub access: Option<String>,
+    /// The authenticated subject, passed to record access methods as `$sub`
+    pub subject: Option<String>,
+    /// The secret which proves the subject was authenticated, passed as `$key`
+    pub access_key: Option<String>,
+    /// A token to authenticate with instead of signing in
+    pub token: Option<String>,
+}
+
+impl Credentials {
+    /// Get the level at which these credentials authenticate with SurrealDB
+    ///
+    /// Returns `token` when authenticating with a token, and `none` when no
+    /// credentials are specified and the connection is unauthenticated.
+    pub fn auth_level(&self) -> &'static str {
+        match (self.level, &self.username, &self.password) {
+            _ if self.token.is_some() => "token",
+            (SigninLevel::Record, _, _) => "record",
+            (_, None, _) | (_, _, None) => "none",
+            (SigninLevel::Namespace, _, _) => "namespace",
+            (SigninLevel::Database, _, _) => "database",
+            (SigninLevel::Root, _, _) => "root",
+        }
+    }
+}
+
 /// Create a new SurrealDB connection for a client
-#[instrument(skip(username, password, namespace, database), fields(url = %url))]
+#[instrument(skip(credentials, namespace, database), fields(url = %url, level = ?credentials.level))]
 pub async fn create_client_connection(
     url: &str,
-    username: Option<&str>,
-    password: Option<&str>,
+    credentials: &Credentials,
     namespace: Option<&str>,
     database: Option<&str>,
 ) -> Result<Surreal<Any>, anyhow::Error> {
@@ -22,20 +92,26 @@ pub async fn create_client_connection(
 
     // Output debugging information
     debug!("Successfully connected to SurrealDB instance");
+    // Authenticate the session and select the namespace and database
+    prepare_session(&instance, credentials, namespace, database).await?;
+    // Output debugging information
+    debug!("Successfully established SurrealDB connection");
+    // Return the instance
+    Ok(instance)
+}
+
+/// Authenticate a SurrealDB session and select its namespace and database
+///
+/// This is used for new connections, and for sessions cloned from a shared
+/// connection, which each authenticate with their own credentials.
+pub async fn prepare_session(
+    instance: &Surreal<Any>,
+    credentials: &Credentials,
+    namespace: Option<&str>,
+    database: Option<&str>,
+) -> Result<()> {
     // Attempt to authenticate if specified
-    if let (Some(username), Some(password)) = (username, password) {
-        debug!("Attempting authentication with username: {}", username);
-        let _jwt = instance
-            .signin(Root {
-                username: username.to_string(),
-                password: password.to_string(),
-            })
-            .await
-            .map_err(|e| anyhow!(e.to_string()))?;
-        debug!("Authentication successful");
-    } else {
-        debug!("No authentication credentials provided");
-    }
+    signin(instance, credentials, namespace, database).await?;
     // Set namespace if provided
     if let Some(ns) = namespace {
         debug!("Setting namespace: {}", ns);
@@ -52,10 +128,117 @@ pub async fn create_client_connection(
             .await
             .map_err(|e| anyhow!(e.to_string()))?;
     }
-    // Output debugging information
-    debug!("Successfully established SurrealDB connection");
-    // Return the instance
-    Ok(instance)
+    // The session is ready to use
+    Ok(())
+}
+
+/// Authenticate a SurrealDB connection using the given credentials
+///
+/// A token takes precedence over signing in. Root signin is skipped if no
+/// username and password are specified, while namespace, database, and record
+/// signin require the namespace and database which they sign in to.
+async fn signin(
+    instance: &Surreal<Any>,
+    credentials: &Credentials,
+    namespace: Option<&str>,
+    database: Option<&str>,
+) -> Result<()> {
+    // Authenticate with a token if specified
+    if let Some(token) = &credentials.token {
+        debug!("Attempting authentication with token");
+        instance
+            .authenticate(token.clone())
+            .await
+            .map_err(|e| anyhow!(e.to_string()))?;
+        debug!("Authentication successful");
+        return Ok(());
+    }
+    // Get the namespace and database to sign in to
+    let ns = || {
+        namespace
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("A namespace is required to sign in at this level"))
+    };
+    let db = || {
+        database
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("A database is required to sign in at this level"))
+    };
+    // Get the username and password to sign in with
+    let user = credentials.username.clone();
+    let pass = credentials.password.clone();
+    let result = match (credentials.level, user, pass) {
+        // Record users sign in using an access method
+        (SigninLevel::Record, user, pass) => {
+            let access = credentials.access.clone().ok_or_else(|| {
+                anyhow!("An access method is required to sign in as a record user")
+            })?;
+            debug!(
+                "Attempting record authentication with access method: {}",
+                access
+            );
+            // The subject can only be trusted by the access method along with the secret
+            if credentials.subject.is_some() && credentials.access_key.is_none() {
+                return Err(anyhow!(
+                    "An access key is required to pass the authenticated subject to a record access method"
+                ));
+            }
+            // Pass the credentials, subject, and secret as access method parameters
+            let mut params = Object::new();
+            let fields = [
+                ("user", user),
+                ("pass", pass),
+                ("sub", credentials.subject.clone()),
+                ("key", credentials.access_key.clone()),
+            ];
+            for (key, value) in fields {
+                if let Some(value) = value {
+                    params.insert(key.to_string(), Value::String(value));
+                }
+            }
+            instance
+                .signin(Record {
+                    namespace: ns()?,
+                    database: db()?,
+                    access,
+                    params: Value::Object(params),
+                })
+                .await
+        }
+        // Other users require a username and password
+        (_, Some(username), Some(password)) => {
+            debug!("Attempting authentication with username: {}", username);
+            match credentials.level {
+                SigninLevel::Namespace => {
+                    instance
+                        .signin(Namespace {
+                            namespace: ns()?,
+                            username,
+                            password,
+                        })
+                        .await
+                }
+                SigninLevel::Database => {
+                    instance
+                        .signin(Database {
+                            namespace: ns()?,
+                            database: db()?,
+                            username,
+                            password,
+                        })
+                        .await
+                }
+                _ => instance.signin(Root { username, password }).await,
+            }
+        }
+        _ => {
+            debug!("No authentication credentials provided");
+            return Ok(());
+        }
+    };
+    result.map_err(|e| anyhow!(e.to_string()))?;
+    debug!("Authentication successful");
+    Ok(())
 }
 
 /// Create a new SurrealDB connection for a client using a token
@@ -106,3 +289,112 @@ pub async fn create_client_connection_with_token(
     // Return the instance
     Ok(instance)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signin_levels() {
+        let instance = create_client_connection(
+            "mem://",
+            &Credentials::default(),
+            Some("test"),
+            Some("test"),
+        )
+        .await
+        .unwrap();
+        instance
+            .query(
+                "DEFINE USER reader ON NAMESPACE PASSWORD 'secret' ROLES VIEWER;
+                DEFINE ACCESS account ON DATABASE TYPE RECORD
+                    SIGNIN (SELECT * FROM user WHERE email = $sub AND $key = 'server-secret');
+                CREATE user:alice SET email = 'alice@example.com';",
+            )
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        // Record users are mapped from the authenticated subject
+        let record = |subject: &str| Credentials {
+            level: SigninLevel::Record,
+            access: Some("account".to_string()),
+            subject: Some(subject.to_string()),
+            access_key: Some("server-secret".to_string()),
+            ..Default::default()
+        };
+        signin(
+            &instance,
+            &record("alice@example.com"),
+            Some("test"),
+            Some("test"),
+        )
+        .await
+        .unwrap();
+        let mut res = instance.query("$auth.id").await.unwrap();
+        let id: Option<surrealdb::types::RecordId> = res.take(0).unwrap();
+        assert_eq!(
+            id.unwrap().key,
+            surrealdb::types::RecordIdKey::from("alice")
+        );
+        assert!(
+            signin(
+                &instance,
+                &record("bob@example.com"),
+                Some("test"),
+                Some("test")
+            )
+            .await
+            .is_err()
+        );
+        // The subject is only trusted along with the secret of the server
+        let credentials = Credentials {
+            access_key: Some("guess".to_string()),
+            ..record("alice@example.com")
+        };
+        let err = signin(&instance, &credentials, Some("test"), Some("test")).await;
+        assert!(err.is_err());
+        let credentials = Credentials {
+            access_key: None,
+            ..record("alice@example.com")
+        };
+        let err = signin(&instance, &credentials, Some("test"), Some("test")).await;
+        assert!(
+            err.unwrap_err()
+                .to_string()
+                .contains("access key is required")
+        );
+        // Record signin requires an access method
+        let credentials = Credentials {
+            access: None,
+            ..record("alice@example.com")
+        };
+        let err = signin(&instance, &credentials, Some("test"), Some("test")).await;
+        assert!(err.unwrap_err().to_string().contains("access method"));
+        // Namespace users sign in to the namespace
+        let credentials = Credentials {
+            level: SigninLevel::Namespace,
+            username: Some("reader".to_string()),
+            password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        signin(&instance, &credentials, Some("test"), None)
+            .await
+            .unwrap();
+        let err = signin(&instance, &credentials, None, None).await;
+        assert!(
+            err.unwrap_err()
+                .to_string()
+                .contains("namespace is required")
+        );
+        // The auth level reflects how the credentials authenticate
+        assert_eq!(credentials.auth_level(), "namespace");
+        assert_eq!(record("alice@example.com").auth_level(), "record");
+        assert_eq!(Credentials::default().auth_level(), "none");
+        let credentials = Credentials {
+            token: Some("token".to_string()),
+            ..credentials
+        };
+        assert_eq!(credentials.auth_level(), "token");
+    }
+}
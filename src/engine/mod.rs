@@ -1,16 +1,330 @@
 use crate::utils;
+use crate::utils::JsonEncoding;
 use anyhow::Result;
 use metrics::{counter, histogram};
 use rmcp::model::Content;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use std::{collections::HashMap, time::Duration};
-use surrealdb::types::Value;
+use surrealdb::method::{Query, Transaction, WithStats};
+use surrealdb::types::{ToSql, Value};
 use surrealdb::{Surreal, engine::any::Any};
-use tracing::{debug, error, info};
+use surrealdb_core::syn::lexer::Lexer;
+use surrealdb_core::syn::token::{Delim, Keyword, Operator, StringKind, TokenKind};
+use tracing::{debug, error, info, warn};
 
 /// Type alias for SurrealDB response which supports indexed access in v3
 pub type IndexedResults = surrealdb::IndexedResults;
 
+/// Statements which are not allowed in read-only mode
+///
+/// These mirror the statements which SurrealDB itself will not run within a
+/// read-only transaction. Each entry maps the keyword which begins the statement
+/// to a description which is reported back to the client.
+const MUTATING_STATEMENTS: &[(Keyword, &str)] = &[
+    (Keyword::Create, "CREATE statements"),
+    (Keyword::Update, "UPDATE statements"),
+    (Keyword::Upsert, "UPSERT statements"),
+    (Keyword::Delete, "DELETE statements"),
+    (Keyword::Relate, "RELATE statements"),
+    (Keyword::Insert, "INSERT statements"),
+    (Keyword::Define, "DEFINE statements"),
+    (Keyword::Remove, "REMOVE statements"),
+    (Keyword::Alter, "ALTER statements"),
+    (Keyword::Rebuild, "REBUILD statements"),
+    (Keyword::Access, "ACCESS statements"),
+    (Keyword::Live, "LIVE statements"),
+    (Keyword::Kill, "KILL statements"),
+    (Keyword::Option, "OPTION statements"),
+    (Keyword::Use, "USE statements"),
+];
+
+/// Statements which define or modify the database schema
+const SCHEMA_STATEMENTS: &[Keyword] = &[
+    Keyword::Define,
+    Keyword::Remove,
+    Keyword::Alter,
+    Keyword::Rebuild,
+    Keyword::Access,
+];
+
+/// Function namespaces which are not allowed in read-only mode
+///
+/// Custom and module functions are refused as it is not possible to determine
+/// whether they modify data, while the `api` and `http` functions can modify
+/// data in SurrealDB or in other services.
+const MUTATING_FUNCTIONS: &[(&str, &str)] = &[
+    ("fn", "custom functions"),
+    ("mod", "module functions"),
+    ("silo", "module functions"),
+    ("api", "api functions"),
+    ("http", "http functions"),
+];
+
+/// The maximum number of cursors which can be held open by a session
+const MAX_OPEN_CURSORS: usize = 32;
+
+/// The duration after which an unused cursor expires
+const CURSOR_EXPIRY: Duration = Duration::from_secs(600);
+
+/// Limits on the size of a single page of results returned to the client
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// The maximum number of rows in a single page (0 for unlimited)
+    pub max_rows: usize,
+    /// The maximum number of serialized JSON bytes in a single page (0 for unlimited)
+    pub max_bytes: usize,
+    /// The maximum number of serialized JSON bytes held by the cursors of a session (0 for unlimited)
+    pub max_buffered_bytes: usize,
+}
+
+impl Default for ResultLimits {
+    fn default() -> Self {
+        Self {
+            max_rows: 1000,
+            max_bytes: 1024 * 1024,
+            max_buffered_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// The remaining rows of a truncated result
+struct PendingRows {
+    /// The rows which have not yet been returned
+    rows: VecDeque<serde_json::Value>,
+    /// The number of serialized JSON bytes of the rows
+    bytes: usize,
+    /// When the cursor was last used
+    updated_at: Instant,
+}
+
+/// A per-session store of cursors for fetching the remaining rows of truncated results
+#[derive(Default)]
+pub struct CursorStore {
+    cursors: Mutex<HashMap<String, PendingRows>>,
+}
+
+impl CursorStore {
+    /// Store the remaining rows of a truncated result, returning a new cursor
+    ///
+    /// The least recently used cursors are removed while the store holds too many
+    /// cursors, or more than the maximum number of buffered bytes.
+    fn insert(&self, rows: VecDeque<serde_json::Value>, bytes: usize, max_bytes: usize) -> String {
+        let mut cursors = self.cursors.lock().unwrap_or_else(|e| e.into_inner());
+        // Remove any expired cursors
+        cursors.retain(|_, v| v.updated_at.elapsed() < CURSOR_EXPIRY);
+        // Remove the least recently used cursors while the store is full
+        loop {
+            let buffered: usize = cursors.values().map(|v| v.bytes).sum();
+            let full = cursors.len() >= MAX_OPEN_CURSORS
+                || (max_bytes > 0 && buffered + bytes > max_bytes);
+            let oldest = cursors
+                .iter()
+                .min_by_key(|(_, v)| v.updated_at)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(oldest) if full => cursors.remove(&oldest),
+                _ => break,
+            };
+        }
+        // Store the rows under a new random cursor
+        let cursor = format!("cur_{:x}", rand::random::<u64>());
+        cursors.insert(
+            cursor.clone(),
+            PendingRows {
+                rows,
+                bytes,
+                updated_at: Instant::now(),
+            },
+        );
+        cursor
+    }
+
+    /// Take the remaining rows for a cursor, if it exists and has not expired
+    fn take(&self, cursor: &str) -> Option<VecDeque<serde_json::Value>> {
+        let mut cursors = self.cursors.lock().unwrap_or_else(|e| e.into_inner());
+        cursors
+            .remove(cursor)
+            .filter(|v| v.updated_at.elapsed() < CURSOR_EXPIRY)
+            .map(|v| v.rows)
+    }
+
+    /// Get the number of open cursors
+    pub fn len(&self) -> usize {
+        self.cursors.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Check whether there are no open cursors
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Options controlling how query results are returned to the client
+#[derive(Clone, Default)]
+pub struct OutputOptions {
+    /// The JSON encoding of the results
+    pub encoding: JsonEncoding,
+    /// The limits on the size of a single page of results
+    pub limits: ResultLimits,
+    /// The store for the remaining rows of truncated results
+    pub cursors: Arc<CursorStore>,
+}
+
+/// A page of rows taken from a result
+pub struct Page {
+    /// The rows in this page
+    pub rows: Vec<serde_json::Value>,
+    /// The cursor for fetching the next page, if any rows remain
+    pub next_cursor: Option<String>,
+    /// The number of rows which have not yet been returned
+    pub remaining_rows: usize,
+    /// The number of rows which were discarded as they could not be buffered
+    pub discarded_rows: usize,
+}
+
+impl Page {
+    /// Convert the page into a JSON object
+    pub fn into_json(self) -> serde_json::Value {
+        json!({
+            "result": self.rows,
+            "next_cursor": self.next_cursor,
+            "remaining_rows": self.remaining_rows,
+            "discarded_rows": self.discarded_rows,
+        })
+    }
+}
+
+/// Take a page of rows within the result limits
+///
+/// Rows are taken until either the row limit or the byte limit is reached. Any
+/// remaining rows are stored in the cursor store, and can be retrieved using the
+/// returned cursor. Remaining rows beyond the limit on buffered bytes are
+/// discarded. An error is returned if a single row exceeds the byte limit.
+pub fn paginate(
+    mut rows: VecDeque<serde_json::Value>,
+    options: &OutputOptions,
+) -> Result<Page, String> {
+    let ResultLimits {
+        max_rows,
+        max_bytes,
+        max_buffered_bytes,
+    } = options.limits;
+    // Count the rows which fit within the limits
+    let mut count = 0;
+    let mut bytes = 2;
+    for row in rows.iter() {
+        // Check the row limit
+        if max_rows > 0 && count >= max_rows {
+            break;
+        }
+        // Check the byte limit, including the separator
+        let size = serde_json::to_vec(row).map(|v| v.len()).unwrap_or_default() + 1;
+        if max_bytes > 0 && bytes + size > max_bytes {
+            break;
+        }
+        bytes += size;
+        count += 1;
+    }
+    // Ensure that at least one row can be returned
+    if count == 0 && !rows.is_empty() {
+        return Err(format!(
+            "Result row exceeds the maximum result size of {max_bytes} bytes"
+        ));
+    }
+    // Split off any rows which do not fit
+    let mut remaining = rows.split_off(count);
+    // Count the remaining rows which can be buffered
+    let mut buffered = 0;
+    let mut count = 0;
+    for row in remaining.iter() {
+        let size = serde_json::to_vec(row).map(|v| v.len()).unwrap_or_default();
+        if max_buffered_bytes > 0 && buffered + size > max_buffered_bytes {
+            break;
+        }
+        buffered += size;
+        count += 1;
+    }
+    // Discard any rows which can not be buffered
+    let discarded_rows = remaining.split_off(count).len();
+    if discarded_rows > 0 {
+        counter!("surrealmcp.total_discarded_rows").increment(discarded_rows as u64);
+    }
+    let remaining_rows = remaining.len();
+    // Store the remaining rows under a cursor
+    let next_cursor = match remaining.is_empty() {
+        true => None,
+        false => {
+            // Update truncation metrics
+            counter!("surrealmcp.total_truncated_results").increment(1);
+            // Store the remaining rows
+            Some(
+                options
+                    .cursors
+                    .insert(remaining, buffered, max_buffered_bytes),
+            )
+        }
+    };
+    Ok(Page {
+        rows: rows.into(),
+        next_cursor,
+        remaining_rows,
+        discarded_rows,
+    })
+}
+
+/// Take the next page of rows for a cursor returned with a truncated result
+pub fn next_page(cursor: &str, options: &OutputOptions) -> Result<Page, String> {
+    match options.cursors.take(cursor) {
+        Some(rows) => paginate(rows, options),
+        None => Err(format!("Cursor '{cursor}' does not exist or has expired")),
+    }
+}
+
+/// Apply the result limits to a JSON result
+///
+/// Arrays which exceed the limits are truncated to a single page, returned along
+/// with a cursor for fetching the next page. Other values are returned as-is,
+/// unless they exceed the byte limit, in which case an error is returned.
+fn limit_result(
+    value: serde_json::Value,
+    options: &OutputOptions,
+) -> Result<LimitedResult, String> {
+    match value {
+        // Truncate arrays to a single page
+        serde_json::Value::Array(rows) => {
+            let page = paginate(rows.into(), options)?;
+            match page.next_cursor.is_some() || page.discarded_rows > 0 {
+                true => Ok(LimitedResult::Truncated(page)),
+                false => Ok(LimitedResult::Complete(serde_json::Value::Array(page.rows))),
+            }
+        }
+        // Check the size of other values
+        value => {
+            let max_bytes = options.limits.max_bytes;
+            let size = serde_json::to_vec(&value)
+                .map(|v| v.len())
+                .unwrap_or_default();
+            if max_bytes > 0 && size > max_bytes {
+                return Err(format!(
+                    "Result of {size} bytes exceeds the maximum result size of {max_bytes} bytes"
+                ));
+            }
+            Ok(LimitedResult::Complete(value))
+        }
+    }
+}
+
+/// A result after the result limits have been applied
+enum LimitedResult {
+    /// The whole result fits within the limits
+    Complete(serde_json::Value),
+    /// The result was truncated to the first page
+    Truncated(Page),
+}
+
 /// Response from executing a SurrealDB query
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -29,27 +343,491 @@ pub struct Response {
 
 impl Response {
     /// Convert the response to an MCP Tool Result
-    pub fn into_mcp_result(mut self) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+    ///
+    /// The result of the first statement is returned as pretty-printed JSON text
+    /// for older clients, and as structured content under the `result` key. If
+    /// the result exceeds the result limits, then only the first page of rows is
+    /// returned, along with the `next_cursor` for fetching the next page.
+    pub fn into_mcp_result(
+        mut self,
+        options: &OutputOptions,
+    ) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
         if let Some(res) = self.result.as_mut() {
             // Take the first result from the response (SurrealDB v3)
             let value: surrealdb::types::Value = res.take(0).map_err(|e: surrealdb::Error| {
                 rmcp::ErrorData::internal_error(e.to_string(), None)
             })?;
-
-            let json_value = utils::surreal_to_json(value);
+            // Convert the result to JSON within the result limits
+            let json_value = limit_result(options.encoding.to_json(value), options)
+                .map_err(|e| rmcp::ErrorData::invalid_request(e, None))?;
+            // Structured content must be an object, so wrap the result
+            let (json_value, structured) = match json_value {
+                LimitedResult::Complete(v) => (v.clone(), json!({ "result": v })),
+                LimitedResult::Truncated(page) => {
+                    let page = page.into_json();
+                    (page.clone(), page)
+                }
+            };
             Ok(rmcp::model::CallToolResult {
                 content: vec![Content::text(
                     serde_json::to_string_pretty(&json_value).unwrap_or_default(),
                 )],
                 is_error: None,
                 meta: None,
-                structured_content: None,
+                structured_content: Some(structured),
             })
         } else {
             let error_msg = self.error.unwrap_or_else(|| "Unknown error".to_string());
             Err(rmcp::ErrorData::internal_error(error_msg, None))
         }
     }
+
+    /// Convert the response to an MCP Tool Result containing every statement result
+    ///
+    /// Each statement is returned as an object containing the statement index,
+    /// its status (`OK` or `ERR`), its execution time, and either its result or
+    /// its error. The tool result is marked as an error if any statement failed.
+    /// The statements are also returned as structured content under the
+    /// `statements` key. The result limits are applied to each statement, with
+    /// truncated statements including the `next_cursor` for the next page.
+    pub fn into_mcp_results(
+        self,
+        options: &OutputOptions,
+    ) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+        if let Some(res) = self.result {
+            // Wrap the response so that statement statistics can be retrieved
+            let mut res = WithStats(res);
+            // Collect the result of each statement in order
+            let mut statements = Vec::with_capacity(res.num_statements());
+            let mut failed = false;
+            for statement_index in 0..res.num_statements() {
+                // Take the statement result along with its statistics
+                let Some((stats, result)) = res.take::<Value>(statement_index) else {
+                    continue;
+                };
+                // Format the statement execution time
+                let time = stats
+                    .execution_time
+                    .map(utils::format_duration)
+                    .unwrap_or_default();
+                // Convert the statement result to JSON within the result limits
+                let result = result
+                    .map_err(|e| e.to_string())
+                    .and_then(|v| limit_result(options.encoding.to_json(v), options));
+                // Convert the statement result to JSON
+                let statement = match result {
+                    Ok(LimitedResult::Complete(value)) => json!({
+                        "statement_index": statement_index,
+                        "status": "OK",
+                        "time": time,
+                        "result": value,
+                    }),
+                    Ok(LimitedResult::Truncated(page)) => json!({
+                        "statement_index": statement_index,
+                        "status": "OK",
+                        "time": time,
+                        "result": page.rows,
+                        "next_cursor": page.next_cursor,
+                        "remaining_rows": page.remaining_rows,
+                        "discarded_rows": page.discarded_rows,
+                    }),
+                    Err(e) => {
+                        failed = true;
+                        json!({
+                            "statement_index": statement_index,
+                            "status": "ERR",
+                            "time": time,
+                            "error": e,
+                        })
+                    }
+                };
+                statements.push(statement);
+            }
+            Ok(rmcp::model::CallToolResult {
+                content: vec![Content::text(
+                    serde_json::to_string_pretty(&statements).unwrap_or_default(),
+                )],
+                is_error: Some(failed),
+                meta: None,
+                // Structured content must be an object, so wrap the statements
+                structured_content: Some(json!({ "statements": statements })),
+            })
+        } else {
+            let error_msg = self.error.unwrap_or_else(|| "Unknown error".to_string());
+            Err(rmcp::ErrorData::internal_error(error_msg, None))
+        }
+    }
+}
+
+/// The tokens of a SurrealQL query which has been parsed successfully
+///
+/// Queries are parsed using the SurrealDB parser, so that only valid queries are
+/// inspected, and are then split into tokens by the SurrealDB lexer. Quoted
+/// strings, identifiers, and comments are single tokens, so their contents are
+/// never mistaken for statements or functions.
+struct Tokens<'a> {
+    /// The kind and source text of each token
+    tokens: Vec<(TokenKind, &'a str)>,
+}
+
+impl<'a> Tokens<'a> {
+    /// Parse and split a query into tokens
+    fn parse(query_string: &'a str) -> Result<Self, String> {
+        // Parse the query to check that it is valid
+        surrealdb_core::syn::parse(query_string).map_err(|e| e.to_string())?;
+        // Split the query into tokens
+        let mut lexer = Lexer::new(query_string.as_bytes());
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token();
+            if token.is_eof() {
+                break;
+            }
+            tokens.push((token.kind, lexer.span_str(token.span)));
+        }
+        Ok(Self { tokens })
+    }
+
+    /// Get the kind of the token at the given position, if any
+    fn kind(&self, index: usize) -> Option<TokenKind> {
+        self.tokens.get(index).map(|(kind, _)| *kind)
+    }
+
+    /// Get the positions and keywords of the statements in the query
+    ///
+    /// Statements can be nested within any expression, so every statement keyword
+    /// is treated as the start of a statement, unless it is used as a field name,
+    /// a graph edge, a function name, an object key, or a record table.
+    fn statements(&self) -> impl Iterator<Item = (usize, Keyword)> + '_ {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (kind, _))| match kind {
+                TokenKind::Keyword(keyword) => Some((i, *keyword)),
+                _ => None,
+            })
+            .filter(|(i, _)| {
+                let previous = i.checked_sub(1).and_then(|i| self.kind(i));
+                !matches!(
+                    previous,
+                    Some(TokenKind::Dot | TokenKind::ArrowRight | TokenKind::PathSeperator)
+                ) && !matches!(
+                    self.kind(i + 1),
+                    Some(TokenKind::Colon | TokenKind::LeftChefron)
+                )
+            })
+    }
+
+    /// Get the namespaces of the functions which are called in the query
+    ///
+    /// Scripting functions are reported with the `function` namespace.
+    fn functions(&self) -> impl Iterator<Item = String> + '_ {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(i, (kind, _))| {
+                let previous = i.checked_sub(1).and_then(|i| self.kind(i));
+                match kind {
+                    // Scripting functions are followed by their arguments
+                    TokenKind::Keyword(Keyword::Function) => {
+                        self.kind(i + 1) == Some(TokenKind::OpenDelim(Delim::Paren))
+                    }
+                    // Function namespaces are the first part of a function path
+                    _ => {
+                        previous != Some(TokenKind::PathSeperator)
+                            && self.kind(i + 1) == Some(TokenKind::PathSeperator)
+                    }
+                }
+            })
+            .map(|(_, (_, text))| text.to_lowercase())
+    }
+
+    /// Get the tokens of the statement which starts at the given position
+    ///
+    /// The statement ends at the next semicolon, or at the end of the block,
+    /// subquery, or list which it is contained within.
+    fn statement(&self, start: usize) -> &[(TokenKind, &'a str)] {
+        let mut depth = 0;
+        for (i, (kind, _)) in self.tokens.iter().enumerate().skip(start) {
+            match kind {
+                TokenKind::OpenDelim(_) => depth += 1,
+                TokenKind::CloseDelim(_) if depth == 0 => return &self.tokens[start..i],
+                TokenKind::CloseDelim(_) => depth -= 1,
+                TokenKind::SemiColon if depth == 0 => return &self.tokens[start..i],
+                _ => {}
+            }
+        }
+        &self.tokens[start..]
+    }
+}
+
+/// Get the descriptions of the statements and functions which modify data
+fn mutations(tokens: &Tokens) -> Vec<(Option<Keyword>, &'static str)> {
+    let mut mutations = Vec::new();
+    // Check the query for any mutating statements
+    for (_, keyword) in tokens.statements() {
+        if let Some((_, description)) = MUTATING_STATEMENTS.iter().find(|(k, _)| *k == keyword) {
+            mutations.push((Some(keyword), *description));
+        }
+    }
+    // Check the query for any functions which could modify data
+    for namespace in tokens.functions() {
+        if namespace == "function" {
+            mutations.push((None, "scripting functions"));
+        } else if let Some((_, description)) =
+            MUTATING_FUNCTIONS.iter().find(|(n, _)| *n == namespace)
+        {
+            mutations.push((None, *description));
+        }
+    }
+    mutations
+}
+
+/// Check that a SurrealQL query only reads data
+///
+/// The query is parsed using the SurrealDB parser, and its tokens are inspected
+/// for any statement which could modify data, change the schema, or alter the
+/// session. Custom, scripting, module, `api`, and `http` functions are also
+/// refused, as they can modify data in the database or elsewhere.
+///
+/// # Arguments
+/// * `query_string` - The SurrealQL query to check
+///
+/// # Returns
+/// * `Ok(())` - The query only reads data
+/// * `Err(String)` - Error message describing why the query was refused
+pub fn check_read_only(query_string: &str) -> Result<(), String> {
+    // Parse the query into tokens
+    let tokens = Tokens::parse(query_string)?;
+    // Refuse the first statement or function which could modify data
+    match mutations(&tokens).first() {
+        Some((_, description)) => Err(format!(
+            "Query refused: {description} are not allowed as the server is in read-only mode"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Check whether a query contains statements which destroy data or schema
+///
+/// This function parses the query, and reports any `REMOVE` statements, and any
+/// `DELETE` statements without a `WHERE` clause which could delete more than the
+/// specific records they reference. Queries which fail to parse are not reported,
+/// as they will be refused by the database.
+///
+/// # Arguments
+/// * `query_string` - The SurrealQL query to check
+///
+/// # Returns
+/// * `Some(String)` - A description of the destructive statements
+/// * `None` - The query does not contain any destructive statements
+pub fn check_destructive(query_string: &str) -> Option<String> {
+    // Parse the query into tokens
+    let tokens = Tokens::parse(query_string).ok()?;
+    for (i, keyword) in tokens.statements() {
+        match keyword {
+            // Check the query for any REMOVE statements
+            Keyword::Remove => return Some("REMOVE statements".to_string()),
+            // Check the query for any DELETE statements without a condition
+            Keyword::Delete => {
+                // Get the tokens of the DELETE statement outside of any brackets
+                let statement = tokens.statement(i + 1);
+                let mut depth = 0;
+                let mut clauses = Vec::with_capacity(statement.len());
+                for (kind, text) in statement {
+                    match kind {
+                        TokenKind::OpenDelim(_) => depth += 1,
+                        TokenKind::CloseDelim(_) => depth -= 1,
+                        _ => {}
+                    }
+                    clauses.push((depth, *kind, *text));
+                }
+                // Statements with a WHERE clause only delete matching records
+                if clauses
+                    .iter()
+                    .any(|(d, k, _)| *d == 0 && *k == TokenKind::Keyword(Keyword::Where))
+                {
+                    continue;
+                }
+                // Get the targets of the statement, which precede any other clause
+                let targets: Vec<_> = clauses
+                    .iter()
+                    .skip_while(|(_, k, _)| {
+                        matches!(k, TokenKind::Keyword(Keyword::From | Keyword::Only))
+                    })
+                    .take_while(|(d, k, _)| *d > 0 || !matches!(k, TokenKind::Keyword(_)))
+                    .collect();
+                // Statements which only reference specific records are not destructive
+                let specific = targets
+                    .split(|(d, k, _)| *d == 0 && *k == TokenKind::Comma)
+                    .all(is_single_record);
+                if !specific {
+                    return Some("DELETE statements without a WHERE clause".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    // The query does not destroy data or schema
+    None
+}
+
+/// Check whether the tokens of a statement target reference a single record
+///
+/// A target references a single record if it is a record id string, or a table
+/// followed by a single id, which is an identifier, a number, a string, or an
+/// array or object. Record ranges, such as `person:..` or `person:1..1000`, can
+/// reference any number of records.
+fn is_single_record(target: &[&(i32, TokenKind, &str)]) -> bool {
+    // Record ranges can reference any number of records
+    if target.iter().any(|(_, k, _)| *k == TokenKind::DotDot) {
+        return false;
+    }
+    match target {
+        // A record id string
+        [(_, TokenKind::String(StringKind::RecordId | StringKind::RecordIdDouble), _)] => true,
+        // A table followed by the id of the record
+        [_, (_, TokenKind::Colon, _), id @ ..] => match id {
+            [(_, TokenKind::Identifier | TokenKind::Digits | TokenKind::String(_), _)] => true,
+            [
+                (_, TokenKind::Operator(Operator::Subtract), _),
+                (_, TokenKind::Digits, _),
+            ] => true,
+            // An array or object id, which is closed by its final token
+            [
+                (_, TokenKind::OpenDelim(Delim::Bracket | Delim::Brace), _),
+                inner @ ..,
+                (0, TokenKind::CloseDelim(_), _),
+            ] => inner.iter().all(|(d, _, _)| *d > 0),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check whether a query contains statements which define or modify the schema
+///
+/// Queries which fail to parse are not reported, as they will be refused by the
+/// database.
+pub fn check_schema(query_string: &str) -> bool {
+    // Parse the query into tokens
+    let Ok(tokens) = Tokens::parse(query_string) else {
+        return false;
+    };
+    // Check the query for any schema statements
+    tokens
+        .statements()
+        .any(|(_, keyword)| SCHEMA_STATEMENTS.contains(&keyword))
+}
+
+/// Check whether a query contains statements or functions which modify data
+///
+/// Statements which only define or modify the schema are not reported, so that
+/// a query can be checked separately for data and schema changes. Queries which
+/// fail to parse are reported, as they can not be inspected.
+pub fn check_writes(query_string: &str) -> bool {
+    // Parse the query into tokens
+    let Ok(tokens) = Tokens::parse(query_string) else {
+        return true;
+    };
+    // Check the query for any mutations other than schema statements
+    mutations(&tokens)
+        .iter()
+        .any(|(keyword, _)| !keyword.is_some_and(|k| SCHEMA_STATEMENTS.contains(&k)))
+}
+
+/// Check whether a query contains USE statements which change the namespace or database
+///
+/// Queries which fail to parse are not reported, as they will be refused by the
+/// database.
+pub fn check_use(query_string: &str) -> bool {
+    // Parse the query into tokens
+    let Ok(tokens) = Tokens::parse(query_string) else {
+        return false;
+    };
+    // Check the query for any USE statements
+    tokens
+        .statements()
+        .any(|(_, keyword)| keyword == Keyword::Use)
+}
+
+/// Build a query which explains the execution plan of a single statement
+///
+/// The statement is parsed and re-serialized before appending the `EXPLAIN`
+/// clause, so that trailing semicolons and comments do not affect the query.
+/// As `EXPLAIN FULL` executes the statement, it is only allowed for statements
+/// which do not modify data.
+pub fn explain_query(query_string: &str, full: bool) -> Result<String, String> {
+    // Parse the query into a statement tree
+    let ast = surrealdb_core::syn::parse(query_string).map_err(|e| e.to_string())?;
+    // Only a single statement can be explained
+    if ast.num_statements() != 1 {
+        return Err(format!(
+            "Only a single statement can be explained, but the query contains {}",
+            ast.num_statements()
+        ));
+    }
+    // Refuse to execute statements which modify data
+    if full && check_read_only(query_string).is_err() {
+        return Err(
+            "EXPLAIN FULL executes the statement, so it can only be used with statements which do not modify data"
+                .to_string(),
+        );
+    }
+    // Append the explain clause to the statement
+    let statement = ast.to_sql();
+    let statement = statement.trim_end().trim_end_matches(';');
+    match full {
+        true => Ok(format!("{statement} EXPLAIN FULL")),
+        false => Ok(format!("{statement} EXPLAIN")),
+    }
+}
+
+/// Check that a query is a single LIVE SELECT statement
+pub fn check_live_query(query_string: &str) -> Result<(), String> {
+    // Parse the query into a statement tree
+    let ast = surrealdb_core::syn::parse(query_string).map_err(|e| e.to_string())?;
+    // Only a single LIVE SELECT statement can be started
+    if ast.num_statements() != 1 || !ast.to_sql().starts_with("LIVE SELECT ") {
+        return Err("The query must be a single LIVE SELECT statement".to_string());
+    }
+    Ok(())
+}
+
+/// The RETURN clause appended to statements which are executed as a dry run
+///
+/// This returns the id of each affected record, along with its contents before
+/// and after the statement, and the JSON patch operations between the two.
+pub const DRY_RUN_RETURN: &str = " RETURN ($after ?? $before).id AS id, $before AS before, $after AS after, value::diff($before, $after) AS diff";
+
+/// The database session or open transaction which a query is executed within
+pub enum Executor<'a> {
+    /// Execute the query on a database session
+    Session(&'a Surreal<Any>),
+    /// Execute the query within an open transaction
+    Transaction(&'a Transaction<Any>),
+}
+
+impl<'a> From<&'a Surreal<Any>> for Executor<'a> {
+    fn from(db: &'a Surreal<Any>) -> Self {
+        Executor::Session(db)
+    }
+}
+
+impl<'a> From<&'a Transaction<Any>> for Executor<'a> {
+    fn from(txn: &'a Transaction<Any>) -> Self {
+        Executor::Transaction(txn)
+    }
+}
+
+impl Executor<'_> {
+    /// Build a query on the session or within the transaction
+    fn query<'b>(&'b self, query: &'b str) -> Query<'b, Any> {
+        match self {
+            Executor::Session(db) => db.query(query),
+            Executor::Transaction(txn) => txn.query(query),
+        }
+    }
 }
 
 /// Execute a SurrealQL query against the specified SurrealDB endpoint
@@ -58,23 +836,48 @@ impl Response {
 /// It handles parameter binding, query execution, and result formatting.
 ///
 /// # Arguments
-/// * `db` - The SurrealDB client instance
+/// * `db` - The SurrealDB client instance, or the transaction to execute within
 /// * `query_string` - The SurrealQL query to execute
 /// * `parameters` - Optional parameters to bind to the query
 /// * `query_id` - Unique identifier for tracking this query
 /// * `connection_id` - Connection ID for logging purposes
+/// * `read_only` - Whether to refuse queries which modify data or schema
 ///
 /// # Returns
 /// * `Result<Response, anyhow::Error>` - The query response or an error
-pub async fn execute_query(
-    db: &Surreal<Any>,
+pub async fn execute_query<'a>(
+    db: impl Into<Executor<'a>>,
     query_id: u64,
     query_string: String,
     parameters: Option<HashMap<String, Value>>,
     connection_id: &str,
+    read_only: bool,
+    timeout: Option<Duration>,
 ) -> Response {
     // Start the measurement timer
     let start_time = Instant::now();
+    // Refuse queries which modify data when in read-only mode
+    if read_only && let Err(e) = check_read_only(&query_string) {
+        // Output debugging information
+        warn!(
+            connection_id = %connection_id,
+            query_id,
+            query = %query_string,
+            error = %e,
+            "Query refused in read-only mode"
+        );
+        // Update query metrics
+        counter!("surrealmcp.total_query_errors").increment(1);
+        counter!("surrealmcp.total_read_only_errors").increment(1);
+        // Return the response
+        return Response {
+            query: query_string,
+            result: None,
+            error: Some(e),
+            duration: start_time.elapsed(),
+            query_id,
+        };
+    }
     // Output debugging information
     debug!(
         connection_id = %connection_id,
@@ -83,6 +886,7 @@ pub async fn execute_query(
         "Executing SurrealQL query"
     );
     // Build the query string
+    let db = db.into();
     let mut query = db.query(&query_string);
     // Bind any parameters
     if let Some(params) = parameters {
@@ -90,8 +894,42 @@ pub async fn execute_query(
             query = query.bind((key, value));
         }
     }
-    // Execute the query
-    match query.await {
+    // Execute the query, abandoning it if the timeout elapses
+    let result = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, query).await {
+            Ok(res) => res,
+            Err(_) => {
+                // Get the duration of the query
+                let duration = start_time.elapsed();
+                // Output debugging information
+                warn!(
+                    connection_id = %connection_id,
+                    query_id,
+                    query = %query_string,
+                    duration_ms = duration.as_millis(),
+                    "Query execution timed out"
+                );
+                // Update query metrics
+                counter!("surrealmcp.total_query_errors").increment(1);
+                counter!("surrealmcp.total_query_timeouts").increment(1);
+                histogram!("surrealmcp.query_duration_ms").record(duration.as_millis() as f64);
+                // Return the response
+                return Response {
+                    query: query_string,
+                    result: None,
+                    error: Some(format!(
+                        "Query timed out after {}",
+                        utils::format_duration(timeout)
+                    )),
+                    duration,
+                    query_id,
+                };
+            }
+        },
+        None => query.await,
+    };
+    // Check the query result
+    match result {
         Ok(res) => {
             // Get the duration of the query
             let duration = start_time.elapsed();
@@ -142,6 +980,67 @@ pub async fn execute_query(
     }
 }
 
+/// Execute a statement within a transaction which is always cancelled
+///
+/// This function appends `DRY_RUN_RETURN` to the statement, and executes it within
+/// a new transaction on a separate session of the database connection. The
+/// transaction is cancelled once the statement completes, so that the changes
+/// which the statement would make are returned without being persisted.
+pub async fn execute_dry_run(
+    db: &Surreal<Any>,
+    query_id: u64,
+    query_string: String,
+    parameters: Option<HashMap<String, Value>>,
+    connection_id: &str,
+    read_only: bool,
+    timeout: Option<Duration>,
+) -> Response {
+    // Start the measurement timer
+    let start_time = Instant::now();
+    // Add the RETURN clause to the statement
+    let query_string = format!("{query_string}{DRY_RUN_RETURN}");
+    // Update dry run metrics
+    counter!("surrealmcp.total_dry_runs").increment(1);
+    // Begin a transaction on a separate session
+    let txn = match db.clone().begin().await {
+        Ok(txn) => txn,
+        Err(e) => {
+            // Update query metrics
+            counter!("surrealmcp.total_query_errors").increment(1);
+            // Return the response
+            return Response {
+                query: query_string,
+                result: None,
+                error: Some(format!("Failed to begin dry run transaction: {e}")),
+                duration: start_time.elapsed(),
+                query_id,
+            };
+        }
+    };
+    // Execute the statement within the transaction, refusing it in read-only mode
+    let res = execute_query(
+        &txn,
+        query_id,
+        query_string,
+        parameters,
+        connection_id,
+        read_only,
+        timeout,
+    )
+    .await;
+    // Cancel the transaction, discarding any changes
+    if let Err(e) = txn.cancel().await {
+        warn!(
+            connection_id = %connection_id,
+            query_id,
+            error = %e,
+            "Failed to cancel dry run transaction"
+        );
+    }
+    // Return the response
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,7 +1049,7 @@ mod tests {
     use surrealdb::engine::any::Any;
 
     async fn setup_db() -> Surreal<Any> {
-        db::create_client_connection("mem://", None, None, Some("test"), Some("test"))
+        db::create_client_connection("mem://", &Default::default(), Some("test"), Some("test"))
             .await
             .expect("Failed to connect to in-memory SurrealDB")
     }
@@ -187,9 +1086,10 @@ mod tests {
             SELECT *, id FROM ComplexTypes:['north', 'sector', 1];
         ";
 
-        let response = execute_query(&db, 1, query.to_string(), None, "test_conn").await;
+        let response =
+            execute_query(&db, 1, query.to_string(), None, "test_conn", false, None).await;
         let mcp_result = response
-            .into_mcp_result()
+            .into_mcp_result(&OutputOptions::default())
             .expect("Failed to convert to MCP result");
 
         // Verify results using JSON string contains (robust across RMCP versions)
@@ -217,12 +1117,13 @@ mod tests {
         db.query("REMOVE TABLE IF EXISTS person;").await.unwrap();
 
         // Multi-statement query: First statement is CREATE, second is SELECT
-        // MCP single-result goal: take(0) should return the CREATE result.
+        // First-result mode: take(0) should return the CREATE result.
         let query = "CREATE person:john SET name = 'John'; SELECT * FROM person;";
-        let response = execute_query(&db, 2, query.to_string(), None, "test_conn").await;
+        let response =
+            execute_query(&db, 2, query.to_string(), None, "test_conn", false, None).await;
 
         let mcp_result = response
-            .into_mcp_result()
+            .into_mcp_result(&OutputOptions::default())
             .expect("Failed to convert multi-statement result");
         let content = &mcp_result.content[0];
         if let rmcp::model::RawContent::Text(raw_text) = &content.raw {
@@ -240,4 +1141,424 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_all_statements_multi_statement_logic() {
+        let db = setup_db().await;
+        db.query("REMOVE TABLE IF EXISTS person;").await.unwrap();
+
+        // Multi-statement query: every statement result should be returned
+        let query = "CREATE person:john SET name = 'John'; SELECT * FROM person; RETURN 1 + 1;";
+        let response =
+            execute_query(&db, 3, query.to_string(), None, "test_conn", false, None).await;
+
+        let mcp_result = response
+            .into_mcp_results(&OutputOptions::default())
+            .expect("Failed to convert multi-statement result");
+        assert_eq!(mcp_result.is_error, Some(false));
+        let content = &mcp_result.content[0];
+        let rmcp::model::RawContent::Text(raw_text) = &content.raw else {
+            panic!("Expected text content");
+        };
+        let statements: serde_json::Value = serde_json::from_str(&raw_text.text).unwrap();
+        let statements = statements
+            .as_array()
+            .expect("Expected an array of statements");
+        assert_eq!(statements.len(), 3);
+        for (index, statement) in statements.iter().enumerate() {
+            assert_eq!(statement["statement_index"], index);
+            assert_eq!(statement["status"], "OK");
+            assert!(statement["time"].is_string());
+        }
+        // The SELECT result should no longer be discarded
+        assert_eq!(statements[1]["result"][0]["name"], "John");
+        assert_eq!(statements[2]["result"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_all_statements_with_errors() {
+        let db = setup_db().await;
+
+        // A failing statement should not hide the other statement results
+        let query = "RETURN 1; THROW 'failure'; RETURN 3;";
+        let response =
+            execute_query(&db, 4, query.to_string(), None, "test_conn", false, None).await;
+
+        let mcp_result = response
+            .into_mcp_results(&OutputOptions::default())
+            .expect("Failed to convert multi-statement result");
+        assert_eq!(mcp_result.is_error, Some(true));
+        let rmcp::model::RawContent::Text(raw_text) = &mcp_result.content[0].raw else {
+            panic!("Expected text content");
+        };
+        let statements: serde_json::Value = serde_json::from_str(&raw_text.text).unwrap();
+        assert_eq!(statements[0]["status"], "OK");
+        assert_eq!(statements[0]["result"], 1);
+        assert_eq!(statements[1]["status"], "ERR");
+        assert!(statements[1]["error"].as_str().unwrap().contains("failure"));
+        assert!(statements[1].get("result").is_none());
+        assert_eq!(statements[2]["status"], "OK");
+        assert_eq!(statements[2]["result"], 3);
+    }
+
+    #[test]
+    fn test_check_read_only() {
+        // Read-only queries
+        assert!(check_read_only("SELECT * FROM person").is_ok());
+        assert!(check_read_only("SELECT * FROM person WHERE name = 'DELETE person'").is_ok());
+        assert!(check_read_only("SELECT * FROM person WHERE name = 'Create(x)'").is_ok());
+        assert!(check_read_only("SELECT * FROM ⟨Update(⟩").is_ok());
+        assert!(check_read_only("SELECT ->knows->person FROM person:john").is_ok());
+        assert!(check_read_only("INFO FOR DB; RETURN string::len('abc');").is_ok());
+        assert!(check_read_only("LET $x = 1; SELECT * FROM person WHERE age > $x").is_ok());
+        assert!(check_read_only("SELECT * FROM person EXPLAIN").is_ok());
+        assert!(check_read_only("SELECT * FROM person WHERE meta.delete = true").is_ok());
+        assert!(check_read_only("SELECT * FROM person ORDER BY name").is_ok());
+        assert!(check_read_only("RETURN { update: 1, create: 2 }").is_ok());
+        assert!(check_read_only("RETURN <option<string>> NONE").is_ok());
+        assert!(check_read_only("-- DELETE person\nSELECT * FROM person").is_ok());
+
+        // Mutating queries
+        assert!(check_read_only("CREATE person:john").is_err());
+        assert!(check_read_only("create person:john").is_err());
+        assert!(check_read_only("SELECT * FROM person; DELETE person").is_err());
+        assert!(check_read_only("RETURN (UPDATE person SET age = 1)").is_err());
+        assert!(check_read_only("SELECT * FROM (UPSERT person:a)").is_err());
+        assert!(check_read_only("LET $x = (INSERT INTO person {})").is_err());
+        assert!(check_read_only("RELATE person:a->knows->person:b").is_err());
+        assert!(check_read_only("DEFINE TABLE person").is_err());
+        assert!(check_read_only("REMOVE TABLE person").is_err());
+        assert!(check_read_only("/* comment */ REMOVE TABLE person").is_err());
+        assert!(check_read_only("USE NS other").is_err());
+        assert!(check_read_only("RETURN fn::custom()").is_err());
+        assert!(check_read_only("RETURN http::post('https://example.com', {})").is_err());
+        assert!(check_read_only("RETURN HTTP::put('https://example.com', {})").is_err());
+        assert!(check_read_only("RETURN http::get('https://example.com')").is_err());
+        assert!(check_read_only("RETURN api::invoke('/test')").is_err());
+        assert!(check_read_only("RETURN function() { return 1; }").is_err());
+        assert!(check_read_only("RETURN [1, DELETE person]").is_err());
+        assert!(check_read_only("RETURN { a: DELETE person }").is_err());
+        assert!(check_read_only("IF true THEN DELETE person END").is_err());
+        assert!(check_read_only("SELECT * FROM person WHERE age = DELETE person").is_err());
+        assert!(check_read_only("RETURN |$x| CREATE person").is_err());
+        assert!(check_read_only("LIVE SELECT * FROM person").is_err());
+
+        // Invalid queries
+        assert!(check_read_only("SELECT FROM WHERE").is_err());
+    }
+
+    #[test]
+    fn test_check_schema() {
+        assert!(check_schema("DEFINE TABLE person SCHEMAFULL"));
+        assert!(check_schema(
+            "SELECT * FROM person; REMOVE FIELD age ON person"
+        ));
+        assert!(check_schema("ALTER TABLE person COMMENT 'people'"));
+        assert!(!check_schema(
+            "SELECT * FROM person WHERE name = 'DEFINE TABLE x'"
+        ));
+        assert!(!check_schema("CREATE person:john"));
+        assert!(!check_schema("DEFINE FROM WHERE"));
+    }
+
+    #[test]
+    fn test_check_writes() {
+        assert!(check_writes("CREATE person:john"));
+        assert!(check_writes("DEFINE TABLE person; DELETE person"));
+        assert!(check_writes("RETURN http::post('https://example.com', {})"));
+        assert!(check_writes("SELECT FROM WHERE"));
+        assert!(!check_writes("DEFINE TABLE person; REMOVE TABLE other"));
+        assert!(!check_writes(
+            "SELECT * FROM person WHERE name = 'UPDATE person'"
+        ));
+    }
+
+    #[test]
+    fn test_check_use() {
+        assert!(check_use("USE NS app DB main; SELECT * FROM person"));
+        assert!(check_use("SELECT * FROM person; USE DB other"));
+        assert!(!check_use("SELECT * FROM person WHERE name = 'USE NS x'"));
+        assert!(!check_use("SELECT * FROM ⟨Use(⟩"));
+        assert!(!check_use("CREATE person:john"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_query_read_only() {
+        let db = setup_db().await;
+        // A read-only query should succeed
+        let response = execute_query(
+            &db,
+            3,
+            "RETURN 1".to_string(),
+            None,
+            "test_conn",
+            true,
+            None,
+        )
+        .await;
+        assert!(response.error.is_none());
+        // A mutating query should be refused before execution
+        let response = execute_query(
+            &db,
+            4,
+            "CREATE readonly:test".to_string(),
+            None,
+            "test_conn",
+            true,
+            None,
+        )
+        .await;
+        assert!(response.result.is_none());
+        assert!(response.error.unwrap().contains("read-only"));
+        // Ensure the table was never created
+        let mut res = db.query("SELECT * FROM readonly").await.unwrap();
+        assert!(res.take::<Value>(0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_explain_query() {
+        let db = setup_db().await;
+        db.query("DEFINE INDEX idx_n ON explained FIELDS n; CREATE explained:1 SET n = 1, m = 2;")
+            .await
+            .unwrap();
+        // Trailing semicolons and comments are removed before explaining
+        let query =
+            explain_query("SELECT * FROM explained WHERE n = 1; -- by index", false).unwrap();
+        assert!(query.ends_with(" EXPLAIN"));
+        // The plan should show the index which was used
+        let mut res = db.query(&query).await.unwrap();
+        let plan = utils::surreal_to_json(res.take::<Value>(0).unwrap()).to_string();
+        assert!(plan.contains("idx_n"), "Missing index in plan: {plan}");
+        // A full explanation should show the table scan and its metrics
+        let query = explain_query("SELECT * FROM explained WHERE m = 2", true).unwrap();
+        assert!(query.ends_with(" EXPLAIN FULL"));
+        let mut res = db.query(&query).await.unwrap();
+        let plan = utils::surreal_to_json(res.take::<Value>(0).unwrap()).to_string();
+        assert!(
+            plan.contains("TableScan"),
+            "Missing table scan in plan: {plan}"
+        );
+        // Multiple statements cannot be explained
+        assert!(explain_query("SELECT * FROM a; SELECT * FROM b;", false).is_err());
+        // Statements which modify data are never executed
+        assert!(explain_query("UPDATE explained SET n = 2", true).is_err());
+        assert!(explain_query("UPDATE explained SET n = 2", false).is_ok());
+        // Invalid queries return a parse error
+        assert!(explain_query("SELEC * FROM explained", false).is_err());
+    }
+
+    #[test]
+    fn test_check_live_query() {
+        assert!(check_live_query("LIVE SELECT * FROM person").is_ok());
+        assert!(check_live_query("-- changes\nlive select * from person where age > 25;").is_ok());
+        assert!(check_live_query("LIVE SELECT DIFF FROM person").is_ok());
+        assert!(check_live_query("SELECT * FROM person").is_err());
+        assert!(check_live_query("LIVE SELECT * FROM person; DELETE person;").is_err());
+        assert!(check_live_query("LIVE SELEC * FROM person").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_timeout() {
+        let db = setup_db().await;
+        // A query which exceeds the timeout should be abandoned
+        let timeout = Some(Duration::from_millis(100));
+        let query = "SLEEP 5s; RETURN 1;".to_string();
+        let start = Instant::now();
+        let response = execute_query(&db, 5, query, None, "test_conn", false, timeout).await;
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(response.result.is_none());
+        assert!(response.error.unwrap().contains("timed out after 100ms"));
+        // A query which completes within the timeout should succeed
+        let query = "RETURN 1".to_string();
+        let response = execute_query(&db, 6, query, None, "test_conn", false, timeout).await;
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_check_destructive() {
+        // Statements which destroy data or schema should be reported
+        assert!(check_destructive("REMOVE TABLE person").is_some());
+        assert!(check_destructive("DELETE person").is_some());
+        assert!(check_destructive("DELETE person, person:john").is_some());
+        assert!(check_destructive("DELETE type::table($table)").is_some());
+        assert!(check_destructive("SELECT * FROM person; DELETE FROM person;").is_some());
+        // Record ranges can delete any number of records
+        assert!(check_destructive("DELETE person:..").is_some());
+        assert!(check_destructive("DELETE person:1..1000").is_some());
+        assert!(check_destructive("DELETE person:..=5, person:john").is_some());
+        assert!(check_destructive("DELETE person:[1]..[5]").is_some());
+        // Statements which only delete specific or matching records should not be reported
+        assert!(check_destructive("DELETE person:john").is_none());
+        assert!(check_destructive("DELETE person:john, person:jane").is_none());
+        assert!(check_destructive("DELETE person:1, person:-1, person:⟨a b⟩").is_none());
+        assert!(check_destructive("DELETE person:[1, 2], person:{ a: 1 }").is_none());
+        assert!(check_destructive("DELETE r'person:john' RETURN NONE").is_none());
+        assert!(check_destructive("DELETE FROM person WHERE age < 18").is_none());
+        assert!(check_destructive("DELETE person WHERE id IN (SELECT id FROM person)").is_none());
+        assert!(check_destructive("SELECT * FROM person WHERE name = 'REMOVE TABLE x'").is_none());
+        assert!(check_destructive("UPDATE person SET age = 1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_dry_run() {
+        let db = setup_db().await;
+        let query = "CREATE person:john SET age = 30; CREATE person:jane SET age = 20;";
+        execute_query(&db, 7, query.to_string(), None, "test_conn", false, None).await;
+        // The affected records should be returned with their changes
+        let query = "UPDATE person SET age += 1 WHERE age > 25".to_string();
+        let mut response = execute_dry_run(&db, 8, query, None, "test_conn", false, None).await;
+        assert!(response.error.is_none());
+        let value: Value = response.result.as_mut().unwrap().take(0).unwrap();
+        let rows = utils::surreal_to_json(value);
+        assert_eq!(rows.as_array().unwrap().len(), 1);
+        assert_eq!(rows[0]["id"], "person:john");
+        assert_eq!(rows[0]["before"]["age"], 30);
+        assert_eq!(rows[0]["after"]["age"], 31);
+        assert_eq!(rows[0]["diff"][0]["path"], "/age");
+        // Deleted records should be returned without persisting the deletion
+        let query = "DELETE FROM person".to_string();
+        let mut response = execute_dry_run(&db, 9, query, None, "test_conn", false, None).await;
+        let value: Value = response.result.as_mut().unwrap().take(0).unwrap();
+        assert_eq!(utils::surreal_to_json(value).as_array().unwrap().len(), 2);
+        // The changes should not have been persisted
+        let query = "SELECT VALUE age FROM person ORDER BY age".to_string();
+        let mut response = execute_query(&db, 10, query, None, "test_conn", false, None).await;
+        let value: Value = response.result.as_mut().unwrap().take(0).unwrap();
+        assert_eq!(utils::surreal_to_json(value), json!([20, 30]));
+        // Dry runs should be refused in read-only mode
+        let query = "DELETE person".to_string();
+        let response = execute_dry_run(&db, 11, query, None, "test_conn", true, None).await;
+        assert!(response.error.is_some());
+    }
+
+    #[test]
+    fn test_paginate_with_cursors() {
+        let options = OutputOptions {
+            limits: ResultLimits {
+                max_rows: 2,
+                max_bytes: 0,
+                max_buffered_bytes: 0,
+            },
+            ..Default::default()
+        };
+        let rows: VecDeque<_> = (0..5).map(|i| json!({ "n": i })).collect();
+        // The first page is limited by the row limit
+        let page = paginate(rows, &options).unwrap();
+        assert_eq!(page.rows, vec![json!({ "n": 0 }), json!({ "n": 1 })]);
+        assert_eq!(page.remaining_rows, 3);
+        let cursor = page.next_cursor.expect("Expected a cursor");
+        // The next page continues from the cursor
+        let page = next_page(&cursor, &options).unwrap();
+        assert_eq!(page.rows, vec![json!({ "n": 2 }), json!({ "n": 3 })]);
+        assert_eq!(page.remaining_rows, 1);
+        // A cursor can only be used once
+        assert!(next_page(&cursor, &options).is_err());
+        // The last page has no cursor
+        let page = next_page(&page.next_cursor.unwrap(), &options).unwrap();
+        assert_eq!(page.rows, vec![json!({ "n": 4 })]);
+        assert_eq!(page.next_cursor, None);
+        assert_eq!(page.remaining_rows, 0);
+        assert!(options.cursors.is_empty());
+    }
+
+    #[test]
+    fn test_paginate_byte_limit() {
+        let options = OutputOptions {
+            limits: ResultLimits {
+                max_rows: 0,
+                max_bytes: 32,
+                max_buffered_bytes: 0,
+            },
+            ..Default::default()
+        };
+        // Rows are taken until the byte limit is reached
+        let rows: VecDeque<_> = (0..4).map(|_| json!("abcdefgh")).collect();
+        let page = paginate(rows, &options).unwrap();
+        assert_eq!(page.rows.len(), 2);
+        assert_eq!(page.remaining_rows, 2);
+        assert!(serde_json::to_vec(&page.rows).unwrap().len() <= 32);
+        // A single row which exceeds the byte limit is an error
+        let rows: VecDeque<_> = vec![json!("a".repeat(64))].into();
+        assert!(paginate(rows, &options).is_err());
+        // The oldest cursors are evicted when too many are open
+        for _ in 0..MAX_OPEN_CURSORS + 4 {
+            let rows: VecDeque<_> = (0..4).map(|_| json!("abcdefgh")).collect();
+            paginate(rows, &options).unwrap();
+        }
+        assert_eq!(options.cursors.len(), MAX_OPEN_CURSORS);
+    }
+
+    #[test]
+    fn test_paginate_buffer_limit() {
+        let options = OutputOptions {
+            limits: ResultLimits {
+                max_rows: 1,
+                max_bytes: 0,
+                max_buffered_bytes: 30,
+            },
+            ..Default::default()
+        };
+        // Remaining rows beyond the buffer limit are discarded
+        let rows: VecDeque<_> = (0..6).map(|_| json!("abcdefgh")).collect();
+        let page = paginate(rows, &options).unwrap();
+        assert_eq!(page.rows.len(), 1);
+        assert_eq!(page.remaining_rows, 3);
+        assert_eq!(page.discarded_rows, 2);
+        let cursor = page.next_cursor.expect("Expected a cursor");
+        // Older cursors are evicted to keep the session within the buffer limit
+        let rows: VecDeque<_> = (0..3).map(|_| json!("abcdefgh")).collect();
+        let page = paginate(rows, &options).unwrap();
+        assert_eq!(page.discarded_rows, 0);
+        assert!(next_page(&cursor, &options).is_err());
+        assert_eq!(options.cursors.len(), 1);
+        // Truncated results report the discarded rows
+        let value = json!(vec!["abcdefgh"; 6]);
+        match limit_result(value, &options).unwrap() {
+            LimitedResult::Truncated(page) => assert_eq!(page.discarded_rows, 2),
+            LimitedResult::Complete(_) => panic!("Expected a truncated result"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_truncated_query_results() {
+        let db = setup_db().await;
+        db.query("REMOVE TABLE IF EXISTS item;").await.unwrap();
+        let options = OutputOptions {
+            limits: ResultLimits {
+                max_rows: 3,
+                max_bytes: 0,
+                max_buffered_bytes: 0,
+            },
+            ..Default::default()
+        };
+        // Create more records than fit in a single page
+        let query = "FOR $i IN 0..5 { CREATE type::record('item', $i) SET n = $i }; SELECT n FROM item ORDER BY n;";
+        let response =
+            execute_query(&db, 1, query.to_string(), None, "test_conn", false, None).await;
+        let mcp_result = response
+            .into_mcp_results(&options)
+            .expect("Failed to convert to MCP result");
+        let structured = mcp_result.structured_content.unwrap();
+        let statements = structured["statements"].as_array().unwrap();
+        // The SELECT statement is truncated to the first page
+        let select = &statements[1];
+        assert_eq!(select["status"], "OK");
+        assert_eq!(select["result"].as_array().unwrap().len(), 3);
+        assert_eq!(select["remaining_rows"], 2);
+        // The remaining rows can be fetched with the cursor
+        let cursor = select["next_cursor"].as_str().unwrap();
+        let page = next_page(cursor, &options).unwrap();
+        assert_eq!(page.rows, vec![json!({ "n": 3 }), json!({ "n": 4 })]);
+        // First-result mode returns the page object
+        let query = "SELECT n FROM item ORDER BY n;";
+        let response =
+            execute_query(&db, 2, query.to_string(), None, "test_conn", false, None).await;
+        let mcp_result = response
+            .into_mcp_result(&options)
+            .expect("Failed to convert to MCP result");
+        let structured = mcp_result.structured_content.unwrap();
+        assert_eq!(structured["result"].as_array().unwrap().len(), 3);
+        assert_eq!(structured["remaining_rows"], 2);
+        assert!(structured["next_cursor"].is_string());
+    }
 }
@@ -0,0 +1,277 @@
+use crate::utils::JsonEncoding;
+use futures::StreamExt;
+use metrics::counter;
+use rmcp::model::{
+    Annotated, RawResource, ReadResourceResult, Resource, ResourceContents,
+    ResourceUpdatedNotificationParam,
+};
+use rmcp::{Peer, RoleServer};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use surrealdb::method::QueryStream;
+use surrealdb::types::Value;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// The URI prefix of live query resources
+pub const LIVE_URI_PREFIX: &str = "surrealmcp://live/";
+
+/// The maximum number of notifications buffered for each live query
+const MAX_BUFFERED_NOTIFICATIONS: usize = 100;
+
+/// Get the resource URI of a live query
+pub fn live_uri(id: &str) -> String {
+    format!("{LIVE_URI_PREFIX}{id}")
+}
+
+/// Get the live query id from a resource URI, if it is a live query resource
+pub fn parse_live_uri(uri: &str) -> Option<&str> {
+    uri.strip_prefix(LIVE_URI_PREFIX)
+        .filter(|id| !id.is_empty())
+}
+
+/// The state of a live query which is shared with its notification task
+#[derive(Default)]
+struct LiveState {
+    /// The notifications which have not yet been read
+    notifications: VecDeque<serde_json::Value>,
+    /// The number of notifications discarded since the last read
+    dropped: usize,
+    /// The client to notify of changes, if it has subscribed to the resource
+    peer: Option<Peer<RoleServer>>,
+    /// Whether the live query has ended
+    ended: bool,
+}
+
+/// A running live query
+struct Subscription {
+    /// The LIVE SELECT statement
+    query: String,
+    /// The state shared with the notification task
+    state: Arc<Mutex<LiveState>>,
+    /// The task which receives notifications from SurrealDB
+    task: JoinHandle<()>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // Dropping the stream kills the live query on the database
+        self.task.abort();
+    }
+}
+
+/// The live queries running within a single client session
+pub struct LiveQueries {
+    /// The maximum number of concurrent live queries
+    max: usize,
+    /// The running live queries, keyed by id
+    subscriptions: Mutex<HashMap<String, Subscription>>,
+}
+
+impl Default for LiveQueries {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl LiveQueries {
+    /// Create a new set of live queries with a concurrency limit
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            subscriptions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Lock the running live queries
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Subscription>> {
+        self.subscriptions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Ensure that another live query can be started
+    pub fn check_capacity(&self) -> Result<(), String> {
+        match self.lock().len() < self.max {
+            true => Ok(()),
+            false => Err(format!(
+                "The maximum of {} concurrent live queries has been reached. Use kill_live_query to stop a live query first.",
+                self.max
+            )),
+        }
+    }
+
+    /// Start receiving notifications from a live query stream, returning its id
+    pub fn start(
+        &self,
+        query: String,
+        mut stream: QueryStream<Value>,
+        encoding: JsonEncoding,
+    ) -> Result<String, String> {
+        let mut subscriptions = self.lock();
+        // Check the concurrency limit again while holding the lock
+        if subscriptions.len() >= self.max {
+            return Err(format!(
+                "The maximum of {} concurrent live queries has been reached",
+                self.max
+            ));
+        }
+        // Generate a new live query id
+        let id = format!("{:x}", rand::random::<u64>());
+        let uri = live_uri(&id);
+        // Create the state shared with the notification task
+        let state = Arc::new(Mutex::new(LiveState::default()));
+        let shared = state.clone();
+        // Spawn a task to receive notifications
+        let task = tokio::spawn(async move {
+            while let Some(notification) = stream.next().await {
+                // Convert the notification to JSON
+                let notification = match notification {
+                    Ok(v) => json!({
+                        "action": v.action.to_string(),
+                        "data": encoding.to_json(v.data),
+                    }),
+                    Err(e) => json!({ "error": e.to_string() }),
+                };
+                // Update live query metrics
+                counter!("surrealmcp.total_live_notifications").increment(1);
+                // Buffer the notification, discarding the oldest if full
+                let peer = {
+                    let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+                    if state.notifications.len() >= MAX_BUFFERED_NOTIFICATIONS {
+                        state.notifications.pop_front();
+                        state.dropped += 1;
+                    }
+                    state.notifications.push_back(notification);
+                    state.peer.clone()
+                };
+                // Notify the client if it has subscribed to the resource
+                if let Some(peer) = peer {
+                    let param = ResourceUpdatedNotificationParam { uri: uri.clone() };
+                    if let Err(e) = peer.notify_resource_updated(param).await {
+                        warn!(uri = %uri, error = %e, "Failed to send resource updated notification");
+                    }
+                }
+            }
+            // Output debugging information
+            debug!(uri = %uri, "Live query stream ended");
+            // Mark the live query as ended
+            shared.lock().unwrap_or_else(|e| e.into_inner()).ended = true;
+        });
+        // Store the running live query
+        subscriptions.insert(id.clone(), Subscription { query, state, task });
+        Ok(id)
+    }
+
+    /// Stop a live query, returning whether it existed
+    pub fn stop(&self, id: &str) -> bool {
+        self.lock().remove(id).is_some()
+    }
+
+    /// Stop all live queries
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Get the number of running live queries
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check whether there are no running live queries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send change notifications for a live query to a client, returning whether it existed
+    pub fn subscribe(&self, id: &str, peer: Peer<RoleServer>) -> bool {
+        match self.lock().get(id) {
+            Some(v) => {
+                v.state.lock().unwrap_or_else(|e| e.into_inner()).peer = Some(peer);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop sending change notifications for a live query, returning whether it existed
+    pub fn unsubscribe(&self, id: &str) -> bool {
+        match self.lock().get(id) {
+            Some(v) => {
+                v.state.lock().unwrap_or_else(|e| e.into_inner()).peer = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// List the running live queries as resources
+    pub fn resources(&self) -> Vec<Resource> {
+        self.lock()
+            .iter()
+            .map(|(id, v)| {
+                let raw = RawResource {
+                    size: None,
+                    uri: live_uri(id),
+                    name: format!("Live query {id}"),
+                    title: None,
+                    mime_type: Some("application/json".to_string()),
+                    description: Some(v.query.clone()),
+                    icons: None,
+                    meta: None,
+                };
+                Annotated::new(raw, None)
+            })
+            .collect()
+    }
+
+    /// Read and remove the buffered notifications of a live query
+    pub fn read(&self, id: &str) -> Option<ReadResourceResult> {
+        let subscriptions = self.lock();
+        let subscription = subscriptions.get(id)?;
+        let mut state = subscription.state.lock().unwrap_or_else(|e| e.into_inner());
+        // Take the buffered notifications
+        let notifications: Vec<_> = state.notifications.drain(..).collect();
+        let dropped = std::mem::take(&mut state.dropped);
+        // Create the resource contents
+        let content = json!({
+            "id": id,
+            "query": subscription.query,
+            "active": !state.ended,
+            "notifications": notifications,
+            "dropped": dropped,
+        });
+        Some(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: live_uri(id),
+                mime_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&content).unwrap_or_default(),
+                meta: None,
+            }],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_uri() {
+        assert_eq!(live_uri("abc123"), "surrealmcp://live/abc123");
+        assert_eq!(parse_live_uri("surrealmcp://live/abc123"), Some("abc123"));
+        assert_eq!(parse_live_uri("surrealmcp://live/"), None);
+        assert_eq!(parse_live_uri("surrealmcp://instructions"), None);
+    }
+
+    #[test]
+    fn test_live_queries_capacity() {
+        let live = LiveQueries::new(0);
+        assert!(live.check_capacity().is_err());
+        let live = LiveQueries::default();
+        assert!(live.check_capacity().is_ok());
+        assert!(live.is_empty());
+        assert!(!live.stop("missing"));
+        assert!(live.read("missing").is_none());
+        assert!(live.resources().is_empty());
+    }
+}
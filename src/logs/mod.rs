@@ -45,15 +45,51 @@ pub fn init_logging_and_metrics(stdio: bool) {
     counter!("surrealmcp.total_connection_errors").absolute(0);
     counter!("surrealmcp.total_configuration_errors").absolute(0);
     counter!("surrealmcp.total_rate_limit_errors").absolute(0);
+    counter!("surrealmcp.total_read_only_errors").absolute(0);
+    counter!("surrealmcp.total_disabled_tool_errors").absolute(0);
+    counter!("surrealmcp.total_truncated_results").absolute(0);
+    counter!("surrealmcp.total_query_timeouts").absolute(0);
+    counter!("surrealmcp.total_cancelled_requests").absolute(0);
+    counter!("surrealmcp.total_live_notifications").absolute(0);
+    counter!("surrealmcp.total_transaction_timeouts").absolute(0);
+    counter!("surrealmcp.total_dry_runs").absolute(0);
+    counter!("surrealmcp.total_confirmed_operations").absolute(0);
+    counter!("surrealmcp.total_declined_operations").absolute(0);
+    counter!("surrealmcp.total_unconfirmed_errors").absolute(0);
+    counter!("surrealmcp.total_identity_errors").absolute(0);
+    counter!("surrealmcp.total_expired_token_errors").absolute(0);
+    // Connection pool metrics
+    gauge!("surrealmcp.pool.connections").set(0.0);
+    counter!("surrealmcp.pool.connections_opened").absolute(0);
+    counter!("surrealmcp.pool.connections_closed").absolute(0);
+    counter!("surrealmcp.pool.health_check_failures").absolute(0);
     // Operation-specific error metrics
     counter!("surrealmcp.errors.connect_endpoint").absolute(0);
+    counter!("surrealmcp.errors.connect_profile").absolute(0);
     counter!("surrealmcp.errors.use_namespace").absolute(0);
     counter!("surrealmcp.errors.use_database").absolute(0);
     counter!("surrealmcp.errors.no_connection").absolute(0);
     counter!("surrealmcp.errors.list_namespaces").absolute(0);
     counter!("surrealmcp.errors.list_databases").absolute(0);
+    counter!("surrealmcp.errors.list_tables").absolute(0);
+    counter!("surrealmcp.errors.describe_table").absolute(0);
+    counter!("surrealmcp.errors.list_fields").absolute(0);
+    counter!("surrealmcp.errors.list_indexes").absolute(0);
+    counter!("surrealmcp.errors.list_analyzers").absolute(0);
+    counter!("surrealmcp.errors.next_page").absolute(0);
+    counter!("surrealmcp.errors.explain").absolute(0);
+    counter!("surrealmcp.errors.live_query").absolute(0);
+    counter!("surrealmcp.errors.begin_transaction").absolute(0);
+    counter!("surrealmcp.errors.commit_transaction").absolute(0);
+    counter!("surrealmcp.errors.rollback_transaction").absolute(0);
     // Tool method call counters
     counter!("surrealmcp.tools.query").absolute(0);
+    counter!("surrealmcp.tools.explain").absolute(0);
+    counter!("surrealmcp.tools.live_query").absolute(0);
+    counter!("surrealmcp.tools.kill_live_query").absolute(0);
+    counter!("surrealmcp.tools.begin_transaction").absolute(0);
+    counter!("surrealmcp.tools.commit_transaction").absolute(0);
+    counter!("surrealmcp.tools.rollback_transaction").absolute(0);
     counter!("surrealmcp.tools.select").absolute(0);
     counter!("surrealmcp.tools.insert").absolute(0);
     counter!("surrealmcp.tools.create").absolute(0);
@@ -61,9 +97,18 @@ pub fn init_logging_and_metrics(stdio: bool) {
     counter!("surrealmcp.tools.update").absolute(0);
     counter!("surrealmcp.tools.delete").absolute(0);
     counter!("surrealmcp.tools.relate").absolute(0);
+    counter!("surrealmcp.tools.next_page").absolute(0);
     counter!("surrealmcp.tools.connect_endpoint").absolute(0);
+    counter!("surrealmcp.tools.connect_profile").absolute(0);
+    counter!("surrealmcp.tools.list_profiles").absolute(0);
+    counter!("surrealmcp.tools.list_connections").absolute(0);
     counter!("surrealmcp.tools.list_namespaces").absolute(0);
     counter!("surrealmcp.tools.list_databases").absolute(0);
+    counter!("surrealmcp.tools.list_tables").absolute(0);
+    counter!("surrealmcp.tools.describe_table").absolute(0);
+    counter!("surrealmcp.tools.list_fields").absolute(0);
+    counter!("surrealmcp.tools.list_indexes").absolute(0);
+    counter!("surrealmcp.tools.list_analyzers").absolute(0);
     counter!("surrealmcp.tools.use_namespace").absolute(0);
     counter!("surrealmcp.tools.use_database").absolute(0);
     counter!("surrealmcp.tools.disconnect_endpoint").absolute(0);
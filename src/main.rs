@@ -1,17 +1,29 @@
+pub mod audit;
 pub mod cli;
 pub mod cloud;
+pub mod config;
 pub mod db;
 pub mod engine;
+pub mod live;
 pub mod logs;
+pub mod pool;
 pub mod prompts;
 pub mod resources;
 pub mod server;
 pub mod tools;
+pub mod transaction;
 pub mod utils;
 
+use crate::audit::AuditConfig;
+use crate::config::ConfigFile;
+use crate::db::SigninConfig;
+use crate::engine::ResultLimits;
+use crate::pool::PoolConfig;
 use crate::server::ServerConfig;
+use crate::tools::ConfirmationPolicy;
 use anyhow::Result;
 use clap::Parser;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -32,17 +44,58 @@ async fn main() -> Result<()> {
             db,
             user,
             pass,
+            signin_level,
+            access,
+            access_key,
+            forward_token,
             server_url,
             bind_address,
             socket_path,
             auth_disabled,
+            require_scopes,
             rate_limit_rps,
             rate_limit_burst,
             auth_server,
             auth_audience,
+            auth_issuer,
+            auth_jwks_url,
+            auth_algorithms,
+            auth_jwks_cache_ttl,
+            auth_jwe_key,
+            auth_jwe_key_file,
+            auth_insecure_jwe_headers,
+            auth_introspection_url,
+            auth_introspection_client_id,
+            auth_introspection_client_secret,
+            read_only,
+            json_encoding,
+            max_result_rows,
+            max_result_bytes,
+            max_buffered_result_bytes,
+            query_timeout,
+            max_live_queries,
+            transaction_timeout,
+            pool_max_size,
+            pool_idle_timeout,
+            pool_health_check_interval,
+            confirm_destructive,
+            allow_unconfirmed,
+            audit_log,
+            audit_log_rotation,
+            allow_tools,
+            deny_tools,
+            allow_endpoints,
+            allow_namespaces,
+            allow_databases,
+            config,
             cloud_access_token,
             cloud_refresh_token,
         } => {
+            // Load the configuration file if specified
+            let file = match config {
+                Some(path) => ConfigFile::load(path)?,
+                None => ConfigFile::default(),
+            };
             // Create the server config
             let config = ServerConfig {
                 endpoint,
@@ -50,14 +103,67 @@ async fn main() -> Result<()> {
                 db,
                 user,
                 pass,
+                signin: SigninConfig {
+                    level: signin_level,
+                    access,
+                    access_key,
+                    forward_token,
+                },
                 server_url,
                 bind_address,
                 socket_path,
                 auth_disabled,
+                require_scopes,
                 rate_limit_rps,
                 rate_limit_burst,
                 auth_server,
                 auth_audience,
+                read_only,
+                json_encoding,
+                result_limits: ResultLimits {
+                    max_rows: max_result_rows,
+                    max_bytes: max_result_bytes,
+                    max_buffered_bytes: max_buffered_result_bytes,
+                },
+                query_timeout: (query_timeout > 0).then(|| Duration::from_secs(query_timeout)),
+                max_live_queries,
+                transaction_timeout: (transaction_timeout > 0)
+                    .then(|| Duration::from_secs(transaction_timeout)),
+                pool: (pool_max_size > 0).then(|| PoolConfig {
+                    max_size: pool_max_size,
+                    idle_timeout: Duration::from_secs(pool_idle_timeout),
+                    health_check_interval: Duration::from_secs(pool_health_check_interval.max(1)),
+                }),
+                confirmation: ConfirmationPolicy {
+                    confirm: confirm_destructive,
+                    allow_unconfirmed,
+                },
+                audit: audit_log.map(|directory| AuditConfig {
+                    directory,
+                    rotation: audit_log_rotation,
+                }),
+                tools: file.tools.merge(allow_tools, deny_tools),
+                profiles: file.profiles,
+                connections: file.connections.merge(
+                    allow_endpoints,
+                    allow_namespaces,
+                    allow_databases,
+                ),
+                auth: file
+                    .auth
+                    .merge(
+                        auth_issuer,
+                        auth_jwks_url,
+                        auth_algorithms,
+                        auth_jwks_cache_ttl,
+                    )
+                    .with_jwe_key(auth_jwe_key, auth_jwe_key_file)
+                    .with_insecure_jwe_headers(auth_insecure_jwe_headers)
+                    .with_introspection(
+                        auth_introspection_url,
+                        auth_introspection_client_id,
+                        auth_introspection_client_secret,
+                    ),
                 cloud_access_token,
                 cloud_refresh_token,
             };
@@ -0,0 +1,427 @@
+use crate::config::is_embedded;
+use crate::db::{self, Credentials};
+use anyhow::{Result, anyhow};
+use metrics::{counter, gauge};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use surrealdb::Surreal;
+use surrealdb::engine::any::{self, Any};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+/// The time to wait for a pooled connection to respond to a health check
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The configuration of the shared connection pool
+#[derive(Clone, Copy, Debug)]
+pub struct PoolConfig {
+    /// The maximum number of connections to an endpoint
+    pub max_size: usize,
+    /// The time after which a connection without any sessions is closed
+    pub idle_timeout: Duration,
+    /// How often pooled connections are checked for health and idleness
+    pub health_check_interval: Duration,
+}
+
+/// A lease on a pooled connection, held for as long as a session uses it
+pub struct PoolLease {
+    /// Shared with the pooled connection to count the sessions using it
+    _sessions: Arc<()>,
+}
+
+/// A connection which client sessions are created on
+struct PooledConnection {
+    /// The unauthenticated connection which sessions are cloned from
+    db: Arc<Surreal<Any>>,
+    /// Shared with the lease of each session using this connection
+    sessions: Arc<()>,
+    /// The time the connection was last known to be used by a session
+    used_at: Instant,
+    /// Whether the connection responded to its last health check
+    healthy: bool,
+}
+
+impl PooledConnection {
+    /// Get the number of sessions using this connection
+    fn sessions(&self) -> usize {
+        Arc::strong_count(&self.sessions) - 1
+    }
+}
+
+/// The pooled connections to an endpoint
+#[derive(Default)]
+struct Slots {
+    /// The open connections to the endpoint
+    connections: Vec<PooledConnection>,
+    /// The number of connections to the endpoint which are being opened
+    opening: usize,
+}
+
+/// The pooled connections to an endpoint, and a notification when one is opened
+#[derive(Default)]
+struct Endpoint {
+    /// The connections to the endpoint, which are never locked across an await
+    slots: std::sync::Mutex<Slots>,
+    /// Notified when a connection to the endpoint has been opened or has failed to open
+    opened: Notify,
+}
+
+impl Endpoint {
+    /// Lock the connections to the endpoint
+    fn lock(&self) -> std::sync::MutexGuard<'_, Slots> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The connection chosen for a new session
+enum Choice {
+    /// Create the session on an existing connection
+    Reuse(Arc<Surreal<Any>>, PoolLease),
+    /// Open a new connection in a reserved slot
+    Open,
+    /// Wait for a connection which is being opened
+    Wait,
+}
+
+/// A pool of SurrealDB connections shared between client sessions
+///
+/// Each client session uses a separate SurrealDB session on a pooled connection,
+/// which is authenticated with the credentials of the client session, so the
+/// namespace, database, authentication, transactions, and live queries of each
+/// client session remain independent, while the number of connections to each
+/// endpoint is limited.
+pub struct ConnectionPool {
+    /// The configuration of the pool
+    config: PoolConfig,
+    /// The pooled connections for each endpoint
+    connections: std::sync::Mutex<HashMap<String, Arc<Endpoint>>>,
+}
+
+impl ConnectionPool {
+    /// Create a new connection pool, checking its connections in the background
+    pub fn new(config: PoolConfig) -> Arc<Self> {
+        let pool = Arc::new(Self {
+            config,
+            connections: Default::default(),
+        });
+        // Check the pooled connections until the pool is dropped
+        tokio::spawn(maintain(
+            Arc::downgrade(&pool),
+            config.health_check_interval,
+        ));
+        pool
+    }
+
+    /// Get a new session on a pooled connection to an endpoint
+    ///
+    /// The session is created on an unused connection if there is one, or on a
+    /// new connection if the pool is not full, or otherwise on the connection
+    /// with the fewest sessions, and is then authenticated with the credentials.
+    /// Sessions on embedded engines, such as `memory` or `rocksdb:`, all share a
+    /// single connection, so that they use the same datastore.
+    /// Connections which fail a health check are not used for new sessions. The
+    /// connection is not closed while the returned lease is held.
+    pub async fn acquire(
+        &self,
+        endpoint: &str,
+        credentials: &Credentials,
+        namespace: Option<&str>,
+        database: Option<&str>,
+    ) -> Result<(Surreal<Any>, PoolLease)> {
+        // Get the connections to the endpoint
+        let pooled = self
+            .connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(endpoint.to_string())
+            .or_default()
+            .clone();
+        // Each connection to an embedded engine opens a separate datastore
+        let max_size = match is_embedded(endpoint) {
+            true => 1,
+            false => self.config.max_size,
+        };
+        let (connection, lease) = loop {
+            // Wait for a notification registered before the connections are checked
+            let opened = pooled.opened.notified();
+            match self.choose(&pooled, max_size) {
+                // Check the health of the connection before reusing it
+                Choice::Reuse(connection, lease) => {
+                    if healthy(&connection).await {
+                        break (connection, lease);
+                    }
+                    // Output debugging information
+                    warn!(endpoint = %endpoint, "Pooled connection failed a health check");
+                    counter!("surrealmcp.pool.health_check_failures").increment(1);
+                    // Stop creating sessions on the unhealthy connection
+                    let mut slots = pooled.lock();
+                    if let Some(v) = slots
+                        .connections
+                        .iter_mut()
+                        .find(|v| Arc::ptr_eq(&v.db, &connection))
+                    {
+                        v.healthy = false;
+                    }
+                    // Close the connection once no other sessions use it
+                    drop(lease);
+                    close_unused(&mut slots.connections);
+                }
+                // Open a new connection without holding the lock
+                Choice::Open => {
+                    let connected = any::connect(endpoint).await;
+                    // Release the reserved slot, and wake any waiting sessions
+                    let mut slots = pooled.lock();
+                    slots.opening -= 1;
+                    pooled.opened.notify_waiters();
+                    let connection = Arc::new(connected.map_err(|e| anyhow!(e.to_string()))?);
+                    // Output debugging information
+                    info!(
+                        endpoint = %endpoint,
+                        connections = slots.connections.len() + 1,
+                        "Opened pooled connection"
+                    );
+                    // Update the pool metrics
+                    counter!("surrealmcp.pool.connections_opened").increment(1);
+                    gauge!("surrealmcp.pool.connections").increment(1.0);
+                    // Add the connection to the pool
+                    let sessions = Arc::new(());
+                    slots.connections.push(PooledConnection {
+                        db: connection.clone(),
+                        sessions: sessions.clone(),
+                        used_at: Instant::now(),
+                        healthy: true,
+                    });
+                    break (
+                        connection,
+                        PoolLease {
+                            _sessions: sessions,
+                        },
+                    );
+                }
+                // Wait for a connection to be opened, and then choose again
+                Choice::Wait => opened.await,
+            }
+        };
+        // Create a separate session on the connection
+        let session = (*connection).clone();
+        // Authenticate the session with the credentials of the client
+        db::prepare_session(&session, credentials, namespace, database).await?;
+        Ok((session, lease))
+    }
+
+    /// Choose the connection for a new session, reserving it with a lease
+    fn choose(&self, pooled: &Endpoint, max_size: usize) -> Choice {
+        let mut slots = pooled.lock();
+        // Unhealthy connections do not count towards the maximum size
+        let healthy = slots.connections.iter().filter(|v| v.healthy).count();
+        let full = healthy + slots.opening >= max_size;
+        // Find the healthy connection with the fewest sessions
+        let least_used = slots
+            .connections
+            .iter_mut()
+            .filter(|v| v.healthy)
+            .min_by_key(|v| v.sessions());
+        match least_used {
+            // Reuse an unused connection, or share a connection once the pool is full
+            Some(v) if v.sessions() == 0 || full => {
+                v.used_at = Instant::now();
+                let lease = PoolLease {
+                    _sessions: v.sessions.clone(),
+                };
+                Choice::Reuse(v.db.clone(), lease)
+            }
+            // Wait while the pool is full of connections being opened
+            None if full => Choice::Wait,
+            // Otherwise reserve a slot for a new connection
+            _ => {
+                slots.opening += 1;
+                Choice::Open
+            }
+        }
+    }
+
+    /// Close the pooled connections which are idle or unhealthy
+    ///
+    /// Unhealthy connections which are still used by sessions are kept open
+    /// until those sessions end, and are used again if they recover.
+    async fn check(&self) {
+        // Get the connections for each endpoint
+        let all: Vec<Arc<Endpoint>> = self
+            .connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        for pooled in all {
+            // Close idle connections, and get the connections to check
+            let connections: Vec<Arc<Surreal<Any>>> = {
+                let mut slots = pooled.lock();
+                let idle_timeout = self.config.idle_timeout;
+                slots.connections.retain_mut(|connection| {
+                    // Connections which are in use are not idle
+                    if connection.sessions() > 0 {
+                        connection.used_at = Instant::now();
+                        return true;
+                    }
+                    // Close connections which have not been used within the idle timeout
+                    if connection.used_at.elapsed() < idle_timeout {
+                        return true;
+                    }
+                    debug!("Closing idle pooled connection");
+                    counter!("surrealmcp.pool.connections_closed").increment(1);
+                    gauge!("surrealmcp.pool.connections").decrement(1.0);
+                    false
+                });
+                slots.connections.iter().map(|v| v.db.clone()).collect()
+            };
+            // Check the health of the connections without holding the lock
+            let mut results = Vec::with_capacity(connections.len());
+            for connection in connections {
+                let healthy = healthy(&connection).await;
+                results.push((connection, healthy));
+            }
+            // Record the health of each connection which is still pooled
+            let mut slots = pooled.lock();
+            for (connection, healthy) in results {
+                let Some(v) = slots
+                    .connections
+                    .iter_mut()
+                    .find(|v| Arc::ptr_eq(&v.db, &connection))
+                else {
+                    continue;
+                };
+                if !healthy {
+                    warn!("Pooled connection failed a health check");
+                    counter!("surrealmcp.pool.health_check_failures").increment(1);
+                }
+                v.healthy = healthy;
+            }
+            // Close unhealthy connections which are no longer used
+            close_unused(&mut slots.connections);
+        }
+        // Forget endpoints without any connections
+        self.connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|_, v| {
+                let slots = v.lock();
+                Arc::strong_count(v) > 1 || slots.opening > 0 || !slots.connections.is_empty()
+            });
+    }
+}
+
+/// Close the unhealthy connections which are not used by any sessions
+fn close_unused(connections: &mut Vec<PooledConnection>) {
+    connections.retain(|v| {
+        if v.healthy || v.sessions() > 0 {
+            return true;
+        }
+        warn!("Closing unhealthy pooled connection");
+        gauge!("surrealmcp.pool.connections").decrement(1.0);
+        false
+    });
+}
+
+/// Check the pooled connections periodically until the pool is dropped
+async fn maintain(pool: Weak<ConnectionPool>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    // Skip the first tick, which completes immediately
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        // Stop once the pool has been dropped
+        let Some(pool) = pool.upgrade() else {
+            return;
+        };
+        pool.check().await;
+    }
+}
+
+/// Check whether a connection responds to a health check in time
+async fn healthy(db: &Surreal<Any>) -> bool {
+    matches!(
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, db.health()).await,
+        Ok(Ok(()))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Count the connections in the pool
+    async fn size(pool: &ConnectionPool) -> usize {
+        let all: Vec<Arc<Endpoint>> = pool.connections.lock().unwrap().values().cloned().collect();
+        all.iter().map(|v| v.lock().connections.len()).sum()
+    }
+
+    /// Count the records in the person table of a session
+    async fn count(db: &Surreal<Any>) -> usize {
+        let mut res = db.query("SELECT * FROM person").await.unwrap();
+        let rows: Vec<surrealdb::types::Value> = res.take(0).unwrap_or_default();
+        rows.len()
+    }
+
+    #[tokio::test]
+    async fn test_connection_pool() {
+        let pool = ConnectionPool::new(PoolConfig {
+            max_size: 2,
+            idle_timeout: Duration::ZERO,
+            health_check_interval: Duration::from_secs(60),
+        });
+        let credentials = Credentials::default();
+        let acquire = || pool.acquire("memory", &credentials, Some("test"), Some("test"));
+        // Sessions on an embedded engine share a single connection and datastore
+        let (first, first_lease) = acquire().await.unwrap();
+        first.query("CREATE person:one").await.unwrap();
+        let (second, second_lease) = acquire().await.unwrap();
+        assert_eq!(size(&pool).await, 1);
+        assert_eq!(count(&second).await, 1);
+        // Each session keeps its own namespace and database
+        first.use_ns("other").await.unwrap();
+        assert_eq!(count(&first).await, 0);
+        assert_eq!(count(&second).await, 1);
+        // Sessions with other credentials share the connections to the endpoint
+        let other = Credentials {
+            subject: Some("alice".to_string()),
+            ..Default::default()
+        };
+        let (third, third_lease) = pool
+            .acquire("memory", &other, Some("test"), Some("test"))
+            .await
+            .unwrap();
+        assert_eq!(size(&pool).await, 1);
+        assert_eq!(count(&third).await, 1);
+        // Idle connections are closed
+        drop((first, first_lease, third, third_lease));
+        pool.check().await;
+        assert_eq!(size(&pool).await, 1);
+        drop((second, second_lease));
+        pool.check().await;
+        assert_eq!(size(&pool).await, 0);
+        assert!(pool.connections.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_connection_pool_concurrent() {
+        let pool = ConnectionPool::new(PoolConfig {
+            max_size: 2,
+            idle_timeout: Duration::ZERO,
+            health_check_interval: Duration::from_secs(60),
+        });
+        let credentials = Credentials::default();
+        let acquire = || pool.acquire("memory", &credentials, Some("test"), Some("test"));
+        // Concurrent sessions on an embedded engine never open a second connection
+        let sessions = futures::future::join_all((0..8).map(|_| acquire())).await;
+        assert!(sessions.iter().all(|v| v.is_ok()));
+        assert_eq!(size(&pool).await, 1);
+        // Connections are not closed while sessions use them
+        pool.check().await;
+        assert_eq!(size(&pool).await, 1);
+        drop(sessions);
+        pool.check().await;
+        assert_eq!(size(&pool).await, 0);
+    }
+}
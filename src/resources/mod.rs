@@ -1,4 +1,13 @@
-use rmcp::model::{Annotated, RawResource, ReadResourceResult, Resource, ResourceContents};
+use crate::engine;
+use crate::utils;
+use futures::future::BoxFuture;
+use rmcp::ErrorData as McpError;
+use rmcp::model::{
+    Annotated, RawResource, RawResourceTemplate, ReadResourceResult, Resource, ResourceContents,
+    ResourceTemplate,
+};
+use std::collections::HashMap;
+use surrealdb::types::Value;
 
 // Trait and provider-based resource registry (similar to prompts)
 pub trait ResourceProvider {
@@ -92,6 +101,338 @@ pub fn read_resource(uri: &str) -> Option<ReadResourceResult> {
     ResourceRegistry::find_by_uri(uri).map(|provider| provider.read())
 }
 
+/// The variables extracted from a resource URI which matches a template
+pub type TemplateVars = HashMap<String, String>;
+
+/// Executes a query for a resource on a session for the connected database
+///
+/// Queries are executed by the query engine in the same way as those of the
+/// tools, so that they are subject to the read-only mode, query timeout, and
+/// audit log of the server.
+pub type Query<'a> = dyn Fn(String) -> BoxFuture<'a, engine::Response> + Send + Sync + 'a;
+
+// Trait and provider-based registry for templated resources read from the connected database
+pub trait ResourceTemplateProvider: Send + Sync {
+    /// Get the resource URI template
+    fn uri_template(&self) -> &'static str;
+
+    /// Get the resource template name
+    fn name(&self) -> &'static str;
+
+    /// Get the resource template description
+    fn description(&self) -> &'static str;
+
+    /// Get the resource MIME type
+    fn mime_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    /// Whether the URI contains the `ns` and `db` variables which scope the session
+    fn scoped(&self) -> bool {
+        true
+    }
+
+    /// Get the resource content, using a session for the connected database
+    fn content<'a>(
+        &'a self,
+        query: &'a Query<'a>,
+        vars: &'a TemplateVars,
+    ) -> BoxFuture<'a, Result<String, McpError>>;
+
+    /// Match a resource URI against the template, extracting the template variables
+    fn matches(&self, uri: &str) -> Option<TemplateVars> {
+        match_template(self.uri_template(), uri)
+    }
+
+    /// Get the resource template metadata
+    fn meta(&self) -> ResourceTemplate {
+        let raw = RawResourceTemplate {
+            uri_template: self.uri_template().to_string(),
+            name: self.name().to_string(),
+            title: Some(self.name().to_string()),
+            description: Some(self.description().to_string()),
+            mime_type: Some(self.mime_type().to_string()),
+            icons: None,
+        };
+        Annotated::new(raw, None)
+    }
+
+    /// Get a resource for a specific URI matching the template
+    fn resource(&self, uri: String, name: String) -> Resource {
+        let raw = RawResource {
+            size: None,
+            uri,
+            name,
+            title: None,
+            mime_type: Some(self.mime_type().to_string()),
+            description: Some(self.description().to_string()),
+            icons: None,
+            meta: None,
+        };
+        Annotated::new(raw, None)
+    }
+
+    /// Read the resource for a specific URI matching the template
+    fn read<'a>(
+        &'a self,
+        query: &'a Query<'a>,
+        uri: &'a str,
+        vars: &'a TemplateVars,
+    ) -> BoxFuture<'a, Result<ReadResourceResult, McpError>> {
+        Box::pin(async move {
+            let content = self.content(query, vars).await?;
+            Ok(ReadResourceResult {
+                contents: vec![ResourceContents::TextResourceContents {
+                    uri: uri.to_string(),
+                    mime_type: Some(self.mime_type().to_string()),
+                    text: content,
+                    meta: None,
+                }],
+            })
+        })
+    }
+}
+
+/// Match a URI against a template containing `{name}` path segment variables
+pub fn match_template(template: &str, uri: &str) -> Option<TemplateVars> {
+    let mut vars = TemplateVars::new();
+    let mut template = template.split('/');
+    let mut uri = uri.split('/');
+    loop {
+        match (template.next(), uri.next()) {
+            // Both have ended, so the URI matches
+            (None, None) => return Some(vars),
+            // Variables match any non-empty segment
+            (Some(t), Some(u)) if t.starts_with('{') && t.ends_with('}') => {
+                if u.is_empty() {
+                    return None;
+                }
+                vars.insert(t[1..t.len() - 1].to_string(), percent_decode(u)?);
+            }
+            // Other segments must match exactly
+            (Some(t), Some(u)) if t == u => {}
+            // The URI does not match
+            _ => return None,
+        }
+    }
+}
+
+/// Decode percent-encoded characters in a URI segment
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(b) = input.next() {
+        match b {
+            b'%' => {
+                let hex = [input.next()?, input.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Take the result of a statement from a query response
+fn take(res: &mut engine::Response, index: usize) -> Result<Value, McpError> {
+    match res.result.as_mut() {
+        Some(response) => response
+            .take(index)
+            .map_err(|e: surrealdb::Error| McpError::internal_error(e.to_string(), None)),
+        None => Err(McpError::internal_error(
+            res.error
+                .clone()
+                .unwrap_or_else(|| "Unknown error".to_string()),
+            None,
+        )),
+    }
+}
+
+/// Run an INFO statement and return its result as pretty-printed JSON
+async fn info_json(query: &Query<'_>, statement: String) -> Result<String, McpError> {
+    // Execute the INFO statement
+    let mut res = query(statement).await;
+    // Take the first result from the response
+    let value = take(&mut res, 0)?;
+    // Convert the result to pretty-printed JSON
+    serde_json::to_string_pretty(&utils::surreal_to_json(value))
+        .map_err(|e| McpError::internal_error(e.to_string(), None))
+}
+
+// Record resource template
+pub struct RecordResource;
+
+impl ResourceTemplateProvider for RecordResource {
+    fn uri_template(&self) -> &'static str {
+        "surrealmcp://record/{table}/{id}"
+    }
+
+    fn name(&self) -> &'static str {
+        "SurrealDB Record"
+    }
+
+    fn description(&self) -> &'static str {
+        "A single record, fetched by its table and ID from the database which the session is using"
+    }
+
+    fn scoped(&self) -> bool {
+        false
+    }
+
+    fn content<'a>(
+        &'a self,
+        query: &'a Query<'a>,
+        vars: &'a TemplateVars,
+    ) -> BoxFuture<'a, Result<String, McpError>> {
+        Box::pin(async move {
+            let table = vars.get("table").map_or("", String::as_str);
+            let id = vars.get("id").map_or("", String::as_str);
+            // Parse the record ID from the table and ID
+            let target = utils::parse_target(format!("{table}:{id}"))
+                .map_err(|e| McpError::invalid_params(e, None))?;
+            // Ensure the target was parsed as a record ID, not a table
+            if target == utils::parse_table(&format!("{table}:{id}")) {
+                return Err(McpError::invalid_params(
+                    format!("Invalid record ID '{table}:{id}'"),
+                    None,
+                ));
+            }
+            // Fetch the record
+            let mut res = query(format!("SELECT * FROM ONLY {target};")).await;
+            let value = take(&mut res, 0)?;
+            // Return an error if the record does not exist
+            if matches!(value, Value::None | Value::Null) {
+                return Err(McpError::resource_not_found(
+                    format!("Record {target} does not exist"),
+                    None,
+                ));
+            }
+            // Convert the record to pretty-printed JSON
+            serde_json::to_string_pretty(&utils::surreal_to_json(value))
+                .map_err(|e| McpError::internal_error(e.to_string(), None))
+        })
+    }
+}
+
+// Database schema resource template
+pub struct DatabaseSchemaResource;
+
+impl ResourceTemplateProvider for DatabaseSchemaResource {
+    fn uri_template(&self) -> &'static str {
+        "surrealdb://{ns}/{db}/schema"
+    }
+
+    fn name(&self) -> &'static str {
+        "SurrealDB Database Schema"
+    }
+
+    fn description(&self) -> &'static str {
+        "The schema of a database, including its tables, analyzers, functions, and parameters, from INFO FOR DB"
+    }
+
+    fn content<'a>(
+        &'a self,
+        query: &'a Query<'a>,
+        _vars: &'a TemplateVars,
+    ) -> BoxFuture<'a, Result<String, McpError>> {
+        Box::pin(info_json(query, "INFO FOR DB STRUCTURE;".to_string()))
+    }
+}
+
+// Table schema resource template
+pub struct TableSchemaResource;
+
+impl ResourceTemplateProvider for TableSchemaResource {
+    fn uri_template(&self) -> &'static str {
+        "surrealdb://{ns}/{db}/table/{name}"
+    }
+
+    fn name(&self) -> &'static str {
+        "SurrealDB Table Schema"
+    }
+
+    fn description(&self) -> &'static str {
+        "The schema of a table, including its fields, indexes, and events, from INFO FOR TABLE"
+    }
+
+    fn content<'a>(
+        &'a self,
+        query: &'a Query<'a>,
+        vars: &'a TemplateVars,
+    ) -> BoxFuture<'a, Result<String, McpError>> {
+        // Escape the table name for use in the statement
+        let table = utils::parse_table(vars.get("name").map_or("", String::as_str));
+        Box::pin(info_json(
+            query,
+            format!("INFO FOR TABLE {table} STRUCTURE;"),
+        ))
+    }
+}
+
+/// Registry of all available resource templates
+pub struct ResourceTemplateRegistry;
+
+impl ResourceTemplateRegistry {
+    /// Get all available resource template providers
+    pub fn get_providers() -> Vec<Box<dyn ResourceTemplateProvider>> {
+        vec![
+            Box::new(RecordResource),
+            Box::new(DatabaseSchemaResource),
+            Box::new(TableSchemaResource),
+        ]
+    }
+
+    /// Find the resource template provider which matches a URI
+    pub fn find_by_uri(uri: &str) -> Option<(Box<dyn ResourceTemplateProvider>, TemplateVars)> {
+        Self::get_providers()
+            .into_iter()
+            .find_map(|p| p.matches(uri).map(|vars| (p, vars)))
+    }
+}
+
+/// List all available resource templates
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
+    ResourceTemplateRegistry::get_providers()
+        .into_iter()
+        .map(|p| p.meta())
+        .collect()
+}
+
+/// List the schema resources of the database which a session is currently using
+pub async fn list_schema_resources(query: &Query<'_>) -> Vec<Resource> {
+    // Get the namespace and database of the session
+    let mut res = query(
+        "RETURN { ns: session::ns(), db: session::db() }; INFO FOR DB STRUCTURE;".to_string(),
+    )
+    .await;
+    let session = take(&mut res, 0).map(utils::surreal_to_json);
+    let (Some(ns), Some(name)) = session
+        .as_ref()
+        .map(|v| (v["ns"].as_str(), v["db"].as_str()))
+        .unwrap_or_default()
+    else {
+        return vec![];
+    };
+    // Add the database schema resource
+    let mut resources = vec![DatabaseSchemaResource.resource(
+        format!("surrealdb://{ns}/{name}/schema"),
+        format!("Schema of {ns}/{name}"),
+    )];
+    // Add a table schema resource for each table
+    if let Ok(info) = take(&mut res, 1).map(utils::surreal_to_json)
+        && let Some(tables) = info["tables"].as_array()
+    {
+        for table in tables.iter().filter_map(|t| t["name"].as_str()) {
+            resources.push(TableSchemaResource.resource(
+                format!("surrealdb://{ns}/{name}/table/{table}"),
+                format!("Schema of table {table}"),
+            ));
+        }
+    }
+    resources
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +480,72 @@ mod tests {
         // Compare with the provider's URI since ResourceContents field access depends on the variant
         assert!(read_resource("surrealmcp://instructions").is_some());
     }
+
+    #[test]
+    fn test_match_template() {
+        let vars = match_template(
+            "surrealdb://{ns}/{db}/schema",
+            "surrealdb://app/prod/schema",
+        )
+        .expect("URI should match");
+        assert_eq!(vars["ns"], "app");
+        assert_eq!(vars["db"], "prod");
+        assert!(match_template("surrealdb://{ns}/{db}/schema", "surrealdb://app/schema").is_none());
+        assert!(
+            match_template("surrealdb://{ns}/{db}/schema", "surrealdb://app//schema").is_none()
+        );
+        assert!(
+            match_template(
+                "surrealdb://{ns}/{db}/schema",
+                "surrealdb://app/prod/schema/x"
+            )
+            .is_none()
+        );
+        // Each URI only matches a single template
+        let (provider, vars) =
+            ResourceTemplateRegistry::find_by_uri("surrealdb://app/prod/table/person").unwrap();
+        assert_eq!(
+            provider.uri_template(),
+            "surrealdb://{ns}/{db}/table/{name}"
+        );
+        assert_eq!(vars["name"], "person");
+        assert!(ResourceTemplateRegistry::find_by_uri("surrealmcp://instructions").is_none());
+        // Record URIs are decoded
+        let (provider, vars) =
+            ResourceTemplateRegistry::find_by_uri("surrealmcp://record/person/john%20doe").unwrap();
+        assert_eq!(provider.uri_template(), "surrealmcp://record/{table}/{id}");
+        assert_eq!(vars["id"], "john doe");
+        let (provider, _) =
+            ResourceTemplateRegistry::find_by_uri("surrealmcp://record/person/schema").unwrap();
+        assert!(!provider.scoped());
+        assert!(ResourceTemplateRegistry::find_by_uri("surrealmcp://record/person/%zz").is_none());
+        // Schema URIs of a namespace named record are not read as records
+        let (provider, vars) =
+            ResourceTemplateRegistry::find_by_uri("surrealdb://record/prod/schema").unwrap();
+        assert_eq!(provider.uri_template(), "surrealdb://{ns}/{db}/schema");
+        assert_eq!(vars["ns"], "record");
+        assert_eq!(vars["db"], "prod");
+        let (provider, vars) =
+            ResourceTemplateRegistry::find_by_uri("surrealdb://record/prod/table/person").unwrap();
+        assert_eq!(
+            provider.uri_template(),
+            "surrealdb://{ns}/{db}/table/{name}"
+        );
+        assert_eq!(vars["ns"], "record");
+    }
+
+    #[test]
+    fn test_list_resource_templates() {
+        let templates = list_resource_templates();
+        assert_eq!(templates.len(), 3);
+        assert_eq!(
+            templates[0].uri_template,
+            "surrealmcp://record/{table}/{id}"
+        );
+        assert_eq!(templates[1].uri_template, "surrealdb://{ns}/{db}/schema");
+        assert_eq!(
+            templates[2].uri_template,
+            "surrealdb://{ns}/{db}/table/{name}"
+        );
+    }
 }
@@ -1,4 +1,5 @@
-use axum::http::Request;
+use axum::body::Body;
+use axum::http::{Method, Request};
 use axum::middleware::Next;
 use axum::{
     http::StatusCode,
@@ -6,19 +7,29 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use http_body_util::LengthLimitError;
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use metrics::counter;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, Nonce, UnboundKey};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::error::Error as _;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::config::AuthConfig;
+use crate::server::scopes;
+
 /// WWW-Authenticate value for HTTP 401 responses
 const WWW_AUTHENTICATE_VALUE: &str =
     "Bearer resource_metadata='/.well-known/oauth-protected-resource'";
 
+/// Resource metadata location for HTTP 403 insufficient scope responses
+const RESOURCE_METADATA: &str = "/.well-known/oauth-protected-resource";
+
 /// Expected issuer for SurrealDB auth tokens
 const EXPECTED_ISSUER: &str = "https://auth.surrealdb.com/";
 
@@ -31,6 +42,33 @@ const JWKS_ENDPOINT: &str = "https://auth.surrealdb.com/.well-known/jwks.json";
 /// JWKS cache duration (1 hour)
 const JWKS_CACHE_DURATION: Duration = Duration::from_secs(3600);
 
+/// The maximum time an introspected token is cached for
+const INTROSPECTION_CACHE_DURATION: Duration = Duration::from_secs(60);
+
+/// The time a token rejected by introspection is cached for
+const INTROSPECTION_FAILURE_CACHE_DURATION: Duration = Duration::from_secs(10);
+
+/// The maximum number of introspected tokens which are cached
+const MAX_INTROSPECTION_CACHE_SIZE: usize = 10_000;
+
+/// The maximum size of a request body which is read to check its required scopes
+const MAX_SCOPED_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+/// OpenID Connect discovery document path, relative to the issuer
+const OIDC_DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+
+/// Algorithms allowed for JWT signatures by default
+const DEFAULT_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
 /// JWKS (JSON Web Key Set) structure
 #[derive(Debug, Serialize, Deserialize)]
 struct Jwks {
@@ -60,6 +98,15 @@ struct JwksKey {
     curve: Option<String>,
 }
 
+/// OpenID Connect discovery document structure
+#[derive(Debug, Deserialize)]
+struct OidcConfiguration {
+    /// The issuer of tokens
+    issuer: String,
+    /// The URL of the JSON Web Key Set
+    jwks_uri: String,
+}
+
 /// Cached JWKS with expiration
 #[derive(Debug, Clone)]
 struct CachedJwks {
@@ -69,7 +116,7 @@ struct CachedJwks {
 
 impl CachedJwks {
     /// Create a new JWKS cache
-    fn new(keys: Vec<JwksKey>) -> Self {
+    fn new(keys: Vec<JwksKey>, ttl: Duration) -> Self {
         // Create a new hash map to store the JWKS
         let mut store = HashMap::new();
         // Insert the JWKS into the hash map
@@ -79,7 +126,7 @@ impl CachedJwks {
         // Create a new cached JWKS
         Self {
             keys: store,
-            expires_at: SystemTime::now() + JWKS_CACHE_DURATION,
+            expires_at: SystemTime::now() + ttl,
         }
     }
 
@@ -94,6 +141,15 @@ impl CachedJwks {
     }
 }
 
+/// Where a JWKS manager fetches public keys from
+#[derive(Debug, Clone)]
+enum JwksSource {
+    /// A JWKS endpoint URL
+    Url(String),
+    /// The JWKS endpoint advertised in the OpenID Connect discovery document of an issuer
+    Discovery(String),
+}
+
 /// JWKS manager for fetching and caching public keys
 #[derive(Debug, Clone)]
 pub struct JwksManager {
@@ -101,25 +157,89 @@ pub struct JwksManager {
     client: reqwest::Client,
     /// Temporary cache for JWKS
     cache: Arc<RwLock<Option<CachedJwks>>>,
+    /// Where the JWKS is fetched from
+    source: JwksSource,
+    /// How long fetched keys are cached for
+    cache_ttl: Duration,
 }
 
 impl JwksManager {
-    /// Create a new JWKS manager
+    /// Create a new JWKS manager for the SurrealDB auth service
     pub fn new() -> Self {
+        Self::with_url(JWKS_ENDPOINT, JWKS_CACHE_DURATION)
+    }
+
+    /// Create a new JWKS manager which fetches keys from a JWKS endpoint
+    pub fn with_url(url: impl Into<String>, cache_ttl: Duration) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache: Arc::new(RwLock::new(None)),
+            source: JwksSource::Url(url.into()),
+            cache_ttl,
+        }
+    }
+
+    /// Create a new JWKS manager which discovers the JWKS endpoint of an issuer
+    /// using its OpenID Connect discovery document
+    pub fn with_discovery(issuer: impl Into<String>, cache_ttl: Duration) -> Self {
         Self {
             client: reqwest::Client::new(),
             cache: Arc::new(RwLock::new(None)),
+            source: JwksSource::Discovery(issuer.into()),
+            cache_ttl,
+        }
+    }
+
+    /// Get the URL of the JWKS endpoint
+    async fn jwks_url(&self) -> Result<String, String> {
+        let issuer = match &self.source {
+            JwksSource::Url(url) => return Ok(url.clone()),
+            JwksSource::Discovery(issuer) => issuer,
+        };
+        // Get the discovery document location
+        let url = format!("{}{OIDC_DISCOVERY_PATH}", issuer.trim_end_matches('/'));
+        // Output debugging information
+        debug!("Fetching OpenID Connect configuration from {url}");
+        // Fetch the discovery document
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch OpenID Connect configuration: {e}"))?;
+        // Check if the response is successful
+        if !response.status().is_success() {
+            return Err(format!(
+                "OpenID Connect discovery endpoint returned error status: {}",
+                response.status()
+            ));
+        }
+        // Parse the response as JSON
+        let config: OidcConfiguration = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse OpenID Connect configuration: {e}"))?;
+        // The discovery document must be for the same issuer
+        if config.issuer != *issuer {
+            return Err(format!(
+                "OpenID Connect configuration is for a different issuer: expected {issuer}, got {}",
+                config.issuer
+            ));
         }
+        // Return the JWKS endpoint
+        Ok(config.jwks_uri)
     }
 
     /// Fetch JWKS from the authentication endpoint
     async fn fetch_jwks(&self) -> Result<Jwks, String> {
+        // Get the JWKS endpoint
+        let url = self.jwks_url().await?;
         // Output debugging information
-        debug!("Fetching JWKS from {JWKS_ENDPOINT}");
+        debug!("Fetching JWKS from {url}");
         // Fetch the JWKS from the endpoint
         let response = self
             .client
-            .get(JWKS_ENDPOINT)
+            .get(&url)
             .send()
             .await
             .map_err(|e| format!("Failed to fetch JWKS: {e}"))?;
@@ -176,7 +296,7 @@ impl JwksManager {
         }
 
         // Create a new JWKS cache
-        let cached_jwks = CachedJwks::new(jwks.keys);
+        let cached_jwks = CachedJwks::new(jwks.keys, self.cache_ttl);
         // Update the temporary cache
         *cache = Some(cached_jwks.clone());
         // Output debugging information
@@ -224,11 +344,155 @@ impl JwksManager {
     }
 }
 
+/// A 256-bit key for decrypting JWE tokens using direct encryption
+#[derive(Clone)]
+pub struct JweKey([u8; 32]);
+
+impl JweKey {
+    /// Parse a base64url-encoded 256-bit key
+    pub fn parse(encoded: &str) -> Result<Self, String> {
+        // Decode the key into bytes
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded.trim().trim_end_matches('='))
+            .map_err(|e| format!("Failed to decode JWE key: {e}"))?;
+        // The key must be 256 bits long for A256GCM
+        let key = bytes
+            .try_into()
+            .map_err(|v: Vec<u8>| format!("Invalid JWE key: expected 32 bytes, got {}", v.len()))?;
+        Ok(Self(key))
+    }
+
+    /// Decrypt the ciphertext of a compact JWE token
+    fn decrypt(&self, parts: &[&str]) -> Result<Vec<u8>, String> {
+        // Direct encryption uses an empty encrypted key
+        if !parts[1].is_empty() {
+            return Err(
+                "Invalid JWE token: encrypted key must be empty for direct encryption".to_string(),
+            );
+        }
+        // Decode the initialization vector, ciphertext, and authentication tag
+        let decode = |name: &str, part: &str| {
+            URL_SAFE_NO_PAD
+                .decode(part)
+                .map_err(|e| format!("Failed to decode JWE {name}: {e}"))
+        };
+        let iv = decode("initialization vector", parts[2])?;
+        let mut ciphertext = decode("ciphertext", parts[3])?;
+        ciphertext.extend(decode("authentication tag", parts[4])?);
+        // Create the nonce and decryption key
+        let nonce = Nonce::try_assume_unique_for_key(&iv)
+            .map_err(|_| "Invalid JWE initialization vector".to_string())?;
+        let key =
+            UnboundKey::new(&AES_256_GCM, &self.0).map_err(|_| "Invalid JWE key".to_string())?;
+        // Decrypt the ciphertext, authenticating the protected header
+        let plaintext = LessSafeKey::new(key)
+            .open_in_place(nonce, Aad::from(parts[0].as_bytes()), &mut ciphertext)
+            .map_err(|_| "Failed to decrypt JWE token".to_string())?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+/// Response from an OAuth 2.0 token introspection endpoint (RFC 7662)
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct IntrospectionResponse {
+    /// Whether the token is currently active
+    active: bool,
+    /// The issuer of the token
+    iss: Option<String>,
+    /// The audience of the token
+    aud: Option<Audience>,
+    /// The expiration time of the token
+    exp: Option<u64>,
+    /// The issued at time of the token
+    iat: Option<u64>,
+    /// The subject of the token
+    sub: Option<String>,
+    /// The space-separated scopes granted to the token
+    scope: Option<String>,
+}
+
+/// The validation result of an introspected token, and when it expires from the cache
+type IntrospectionResult = (Result<TokenClaims, String>, SystemTime);
+
+/// Client for validating opaque tokens using token introspection (RFC 7662)
+#[derive(Clone)]
+pub struct TokenIntrospector {
+    /// HTTP client for introspection requests
+    client: reqwest::Client,
+    /// The introspection endpoint URL
+    url: String,
+    /// The client ID to authenticate with
+    client_id: Option<String>,
+    /// The client secret to authenticate with
+    client_secret: Option<String>,
+    /// Temporary cache of introspected tokens and their validation results
+    cache: Arc<RwLock<HashMap<String, IntrospectionResult>>>,
+}
+
+impl TokenIntrospector {
+    /// Create a new token introspection client
+    pub fn new(
+        url: impl Into<String>,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            client_id,
+            client_secret,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Introspect a token, returning the response from the introspection endpoint
+    async fn introspect(&self, token: &str) -> Result<IntrospectionResponse, String> {
+        // Output debugging information
+        debug!("Introspecting token using {}", self.url);
+        // Create the introspection request
+        let mut request = self
+            .client
+            .post(&self.url)
+            .form(&[("token", token), ("token_type_hint", "access_token")]);
+        // Authenticate the request with the client credentials
+        if let Some(client_id) = &self.client_id {
+            request = request.basic_auth(client_id, self.client_secret.as_ref());
+        }
+        // Send the introspection request
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to introspect token: {e}"))?;
+        // Check if the response is successful
+        if !response.status().is_success() {
+            return Err(format!(
+                "Introspection endpoint returned error status: {}",
+                response.status()
+            ));
+        }
+        // Parse the response as JSON
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse introspection response: {e}"))
+    }
+}
+
+/// An identity provider whose tokens are trusted
+#[derive(Debug, Clone)]
+pub struct TrustedIssuer {
+    /// Expected issuer for authentication tokens
+    pub issuer: String,
+    /// JWKS manager for fetching and caching the public keys of the issuer
+    pub jwks_manager: Option<JwksManager>,
+}
+
 /// Token validation configuration
 #[derive(Clone)]
 pub struct TokenValidationConfig {
-    /// Expected issuer for authentication tokens
-    pub expected_issuer: String,
+    /// Trusted issuers for authentication tokens
+    pub issuers: Vec<TrustedIssuer>,
     /// Expected audience for authentication tokens
     pub expected_audience: String,
     /// Public key for JWT validation
@@ -239,24 +503,116 @@ pub struct TokenValidationConfig {
     pub validate_issued_at: bool,
     /// Clock skew in seconds
     pub clock_skew_seconds: u64,
-    /// JWKS manager for fetching and caching public keys
-    pub jwks_manager: Option<JwksManager>,
+    /// Algorithms allowed for JWT signatures
+    pub algorithms: Vec<Algorithm>,
+    /// Key for decrypting JWE tokens
+    pub jwe_key: Option<JweKey>,
+    /// Whether JWE tokens are accepted by validating only their header when no key is set
+    pub insecure_jwe_headers: bool,
+    /// Token introspection endpoint for validating opaque tokens
+    pub introspection: Option<TokenIntrospector>,
+    /// Whether tool calls and resource reads require MCP scopes
+    pub require_scopes: bool,
 }
 
 impl Default for TokenValidationConfig {
     fn default() -> Self {
         Self {
-            expected_issuer: EXPECTED_ISSUER.to_string(),
+            issuers: vec![TrustedIssuer {
+                issuer: EXPECTED_ISSUER.to_string(),
+                jwks_manager: Some(JwksManager::new()),
+            }],
             expected_audience: EXPECTED_AUDIENCE.to_string(),
             jwt_public_key: None,
             validate_expiration: true,
             validate_issued_at: true,
             clock_skew_seconds: 300, // 5 minutes
-            jwks_manager: Some(JwksManager::new()),
+            algorithms: DEFAULT_ALGORITHMS.to_vec(),
+            jwe_key: None,
+            insecure_jwe_headers: false,
+            introspection: None,
+            require_scopes: false,
         }
     }
 }
 
+impl TokenValidationConfig {
+    /// Create a token validation configuration for the configured identity providers
+    ///
+    /// Tokens from the SurrealDB auth service are trusted if no issuers are
+    /// configured. The public keys of each issuer are fetched from its JWKS URL,
+    /// or from the JWKS endpoint in its OpenID Connect discovery document.
+    pub fn from_auth_config(config: &AuthConfig) -> anyhow::Result<Self> {
+        let defaults = Self::default();
+        // Get the time to cache public keys for
+        let cache_ttl = config
+            .jwks_cache_ttl
+            .map(Duration::from_secs)
+            .unwrap_or(JWKS_CACHE_DURATION);
+        // Create a JWKS manager for each trusted issuer
+        let issuers = match config.issuers.is_empty() {
+            true => vec![TrustedIssuer {
+                issuer: EXPECTED_ISSUER.to_string(),
+                jwks_manager: Some(JwksManager::with_url(JWKS_ENDPOINT, cache_ttl)),
+            }],
+            false => config
+                .issuers
+                .iter()
+                .map(|v| TrustedIssuer {
+                    issuer: v.issuer.clone(),
+                    jwks_manager: Some(match &v.jwks_url {
+                        Some(url) => JwksManager::with_url(url, cache_ttl),
+                        None => JwksManager::with_discovery(&v.issuer, cache_ttl),
+                    }),
+                })
+                .collect(),
+        };
+        // Parse the allowed signature algorithms
+        let algorithms = match config.algorithms.is_empty() {
+            true => defaults.algorithms.clone(),
+            false => config
+                .algorithms
+                .iter()
+                .map(|v| {
+                    v.parse::<Algorithm>()
+                        .map_err(|_| anyhow::anyhow!("Unsupported JWT algorithm: {v}"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        };
+        // Load the key for decrypting JWE tokens
+        let jwe_key = match (&config.jwe_key, &config.jwe_key_file) {
+            (Some(key), _) => Some(JweKey::parse(key).map_err(|e| anyhow::anyhow!(e))?),
+            (None, Some(path)) => {
+                let key = std::fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to read JWE key file {path}: {e}"))?;
+                Some(JweKey::parse(&key).map_err(|e| anyhow::anyhow!("{e} in {path}"))?)
+            }
+            (None, None) => None,
+        };
+        // Create the token introspection client
+        let introspection = config.introspection_url.as_ref().map(|url| {
+            TokenIntrospector::new(
+                url,
+                config.introspection_client_id.clone(),
+                config.introspection_client_secret.clone(),
+            )
+        });
+        Ok(Self {
+            issuers,
+            algorithms,
+            jwe_key,
+            insecure_jwe_headers: config.insecure_jwe_headers,
+            introspection,
+            ..defaults
+        })
+    }
+
+    /// Get the trusted issuer with the given issuer identifier
+    fn issuer(&self, issuer: &str) -> Option<&TrustedIssuer> {
+        self.issuers.iter().find(|v| v.issuer == issuer)
+    }
+}
+
 /// JWE header structure
 #[derive(Debug, Serialize, Deserialize)]
 struct JweHeader {
@@ -265,7 +621,11 @@ struct JweHeader {
     /// The encryption algorithm used to encrypt the token
     enc: String,
     /// The issuer of the token
-    iss: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<String>,
+    /// The compression algorithm applied before encryption
+    #[serde(skip_serializing_if = "Option::is_none")]
+    zip: Option<String>,
 }
 
 /// JWT header structure
@@ -280,25 +640,104 @@ struct JwtHeader {
     typ: Option<String>,
 }
 
+/// The audience of a token, which may be a single value or a list
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    /// A single audience
+    One(String),
+    /// A list of audiences
+    Many(Vec<String>),
+}
+
+impl Audience {
+    /// Check whether the audience includes the given value
+    fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::One(v) => v == audience,
+            Audience::Many(v) => v.iter().any(|v| v == audience),
+        }
+    }
+}
+
 /// Token claims structure for both JWE and JWT tokens
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TokenClaims {
     /// The issuer of the token
     iss: String,
     /// The audience of the token
-    aud: Option<String>,
+    aud: Option<Audience>,
     /// The expiration time of the token
     exp: Option<u64>,
     /// The issued at time of the token
     iat: Option<u64>,
     /// The subject of the token
     sub: Option<String>,
+    /// The space-separated scopes granted to the token
+    #[serde(default)]
+    scope: Option<String>,
+    /// The permissions granted to the token
+    #[serde(default)]
+    permissions: Option<Vec<String>>,
 }
 
-/// Validate a JWE token header from SurrealDB auth service
+/// The authenticated identity of the client, taken from a validated bearer token
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity {
+    /// The subject of the token
+    pub subject: Option<String>,
+    /// The issuer of the token
+    pub issuer: String,
+    /// The scopes granted to the token
+    pub scopes: Vec<String>,
+    /// The time after which the token is no longer accepted, in seconds since the Unix epoch
+    pub expires_at: Option<u64>,
+}
+
+impl Identity {
+    /// Create an identity from validated token claims
+    fn from_claims(claims: TokenClaims, config: &TokenValidationConfig) -> Self {
+        // Combine the scopes and permissions granted to the token
+        let mut scopes: Vec<String> = claims
+            .scope
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .chain(claims.permissions.unwrap_or_default())
+            .collect();
+        scopes.sort();
+        scopes.dedup();
+        Self {
+            subject: claims.sub,
+            issuer: claims.iss,
+            scopes,
+            // Only enforce the expiration time if it is validated, allowing for clock skew
+            expires_at: claims
+                .exp
+                .filter(|_| config.validate_expiration)
+                .map(|exp| exp + config.clock_skew_seconds),
+        }
+    }
+
+    /// Check whether the token has expired
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|v| v.as_secs() > expires_at)
+                .unwrap_or_default(),
+            None => false,
+        }
+    }
+}
+
+/// Validate a JWE token from SurrealDB auth service
 ///
 /// This function validates the JWE token header structure and issuer.
 /// For SurrealDB tokens using "dir" algorithm with A256GCM encryption.
+/// If a JWE key is configured, the token is decrypted, and its claims are
+/// validated in the same way as JWT tokens. Otherwise only the header is
+/// validated.
 async fn validate_jwe_token(
     token: &str,
     config: &TokenValidationConfig,
@@ -331,31 +770,141 @@ async fn validate_jwe_token(
             header.enc
         ));
     }
+    // Validate the compression
+    if let Some(zip) = &header.zip {
+        return Err(format!("Unsupported JWE compression algorithm: {zip}"));
+    }
     // Validate the issuer from header
-    if header.iss != config.expected_issuer {
+    if let Some(iss) = &header.iss
+        && config.issuer(iss).is_none()
+    {
+        return Err(format!("Invalid issuer: {iss} is not trusted"));
+    }
+    // Only validate the header if no key is configured and this is explicitly allowed
+    let Some(key) = &config.jwe_key else {
+        if !config.insecure_jwe_headers {
+            return Err(
+                "JWE tokens can not be validated as no decryption key is configured".to_string(),
+            );
+        }
+        // The header must specify the issuer
+        let iss = header
+            .iss
+            .ok_or_else(|| "JWE token header missing issuer (iss)".to_string())?;
+        // Create the default claims
+        let claims = TokenClaims {
+            iss,
+            aud: None,
+            exp: None,
+            iat: None,
+            sub: None,
+            scope: None,
+            permissions: None,
+        };
+        // Output debugging information
+        debug!(
+            token = %token,
+            issuer = %claims.iss,
+            "JWE token header validated successfully"
+        );
+        // Return the claims
+        return Ok(claims);
+    };
+    // Decrypt the token payload
+    let payload = key.decrypt(&parts)?;
+    // Parse the payload contents
+    let claims: TokenClaims =
+        serde_json::from_slice(&payload).map_err(|e| format!("Failed to parse JWE claims: {e}"))?;
+    // Validate the issuer from the claims
+    if config.issuer(&claims.iss).is_none() {
+        return Err(format!("Invalid issuer: {} is not trusted", claims.iss));
+    }
+    // The issuer in the header must match the claims
+    if let Some(iss) = &header.iss
+        && *iss != claims.iss
+    {
         return Err(format!(
-            "Invalid issuer: expected {}, got {}",
-            config.expected_issuer, header.iss
+            "Invalid issuer: header issuer {iss} does not match claims issuer {}",
+            claims.iss
         ));
     }
-    // Create the default claims
-    let claims = TokenClaims {
-        iss: header.iss,
-        aud: None,
-        exp: None,
-        iat: None,
-        sub: None,
-    };
+    // Validate the remaining claims
+    validate_claims(&claims, config, true)?;
     // Output debugging information
     debug!(
         token = %token,
         issuer = %claims.iss,
-        "JWE token validated successfully"
+        audience = ?claims.aud,
+        subject = ?claims.sub,
+        expiration = ?claims.exp,
+        issued_at = ?claims.iat,
+        "JWE token decrypted and validated successfully"
     );
     // Return the claims
     Ok(claims)
 }
 
+/// Validate the audience, subject, expiration, and issued at claims of a token
+///
+/// These are the same checks which are applied to JWT tokens. If `required` is
+/// set, the claims must be present, otherwise only present claims are checked.
+fn validate_claims(
+    claims: &TokenClaims,
+    config: &TokenValidationConfig,
+    required: bool,
+) -> Result<(), String> {
+    // Check the required claims are present
+    if required {
+        let missing = [
+            ("aud", claims.aud.is_none()),
+            ("exp", claims.exp.is_none()),
+            ("iat", claims.iat.is_none()),
+            ("sub", claims.sub.is_none()),
+        ];
+        if let Some((name, _)) = missing.iter().find(|(_, missing)| *missing) {
+            return Err(format!("Token missing required claim '{name}'"));
+        }
+    }
+    // Validate the audience
+    if let Some(aud) = &claims.aud
+        && !aud.contains(&config.expected_audience)
+    {
+        return Err(format!(
+            "Token 'aud' invalid: expected {}, got {aud:?}",
+            config.expected_audience
+        ));
+    }
+    // Get the current time
+    let current_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Failed to get current time: {e}"))?
+        .as_secs();
+    // Validate expiration time
+    if config.validate_expiration
+        && let Some(exp) = claims.exp
+    {
+        // Check for expiration, allowing for clock skew
+        if current_time > exp + config.clock_skew_seconds {
+            return Err(format!(
+                "Token 'exp' invalid: expired at {exp}, current time {current_time}",
+            ));
+        }
+    }
+    // Validate issued at time
+    if config.validate_issued_at
+        && let Some(iat) = claims.iat
+    {
+        // Check for issued at, allowing for clock skew
+        if iat > current_time + config.clock_skew_seconds {
+            return Err(format!(
+                "Token 'iat' invalid: issued at {iat}, current time {current_time}",
+            ));
+        }
+    }
+    // All claims are valid
+    Ok(())
+}
+
 /// Validate a standard JWT token using JWKS
 ///
 /// This function validates JWT tokens using the jsonwebtoken crate and JWKS.
@@ -368,16 +917,25 @@ async fn validate_jwt_token(
     debug!(token = %token, "Validating JWT token");
     // Decode the header to check the algorithm and key ID
     let header = decode_header(token).map_err(|e| format!("Failed to decode JWT header: {e}"))?;
+    // Check the signature algorithm is allowed
+    if !config.algorithms.contains(&header.alg) {
+        return Err(format!("JWT algorithm {:?} is not allowed", header.alg));
+    }
+    // Find the trusted issuer of the token, before the signature is verified
+    let issuer = unverified_issuer(token)?;
+    let issuer = config
+        .issuer(&issuer)
+        .ok_or_else(|| format!("Invalid issuer: {issuer} is not trusted"))?;
     // Create validation configuration
     let mut validation = Validation::new(header.alg);
     validation.set_audience(&[&config.expected_audience]);
-    validation.set_issuer(&[&config.expected_issuer]);
+    validation.set_issuer(&[&issuer.issuer]);
     validation.set_required_spec_claims(&["iss", "aud", "exp", "iat", "sub"]);
     validation.leeway = config.clock_skew_seconds;
     validation.validate_aud = true;
     validation.validate_exp = true;
     // Get the decoding key
-    let key = if let Some(jwks_manager) = &config.jwks_manager {
+    let key = if let Some(jwks_manager) = &issuer.jwks_manager {
         // Get the key ID from the header
         let kid = header
             .kid
@@ -392,7 +950,12 @@ async fn validate_jwt_token(
     } else if let Some(public_key) = &config.jwt_public_key {
         // Fallback to static public key
         match header.alg {
-            Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => {
+            Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512 => {
                 // Decode the RSA public key
                 DecodingKey::from_rsa_pem(public_key.as_bytes())
                     .map_err(|e| format!("Failed to create RSA decoding key: {e}"))?
@@ -412,38 +975,8 @@ async fn validate_jwt_token(
     // Decode the authentication token
     let token_data = decode::<TokenClaims>(token, &key, &validation)
         .map_err(|e| format!("Failed to validate JWT token: {e}"))?;
-    // Validate expiration time
-    if config.validate_expiration
-        && let Some(exp) = token_data.claims.exp
-    {
-        // Get the current time
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| format!("Failed to get current time: {e}"))?
-            .as_secs();
-        // Check for expiration, allowing for clock skew
-        if current_time > exp + config.clock_skew_seconds {
-            return Err(format!(
-                "Token 'exp' invalid: expired at {exp}, current time {current_time}",
-            ));
-        }
-    }
-    // Validate issued at time
-    if config.validate_issued_at
-        && let Some(iat) = token_data.claims.iat
-    {
-        // Get the current time
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| format!("Failed to get current time: {e}"))?
-            .as_secs();
-        // Check for issued at, allowing for clock skew
-        if iat > current_time + config.clock_skew_seconds {
-            return Err(format!(
-                "Token 'iat' invalid: issued at {iat}, current time {current_time}",
-            ));
-        }
-    }
+    // Validate expiration and issued at times
+    validate_claims(&token_data.claims, config, true)?;
     // Output debugging information
     debug!(
         token = %token,
@@ -452,17 +985,126 @@ async fn validate_jwt_token(
         subject = ?token_data.claims.sub,
         expiration = ?token_data.claims.exp,
         issued_at = ?token_data.claims.iat,
+        scope = ?token_data.claims.scope,
+        permissions = ?token_data.claims.permissions,
         "JWT token validated successfully"
     );
     // Return the token claims
     Ok(token_data.claims)
 }
 
-/// Validate a bearer token (supports both JWE and JWT formats)
+/// Get the issuer claim of a JWT token without verifying its signature
+///
+/// The issuer is only used to find the keys to verify the token with, and is
+/// validated again once the signature has been verified.
+fn unverified_issuer(token: &str) -> Result<String, String> {
+    #[derive(Deserialize)]
+    struct Claims {
+        iss: String,
+    }
+    // Get the payload of the token
+    let payload = token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| "Invalid JWT token format".to_string())?;
+    // Decode the payload into bytes
+    let bytes = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|e| format!("Failed to decode JWT payload: {e}"))?;
+    // Parse the issuer from the payload
+    let claims: Claims =
+        serde_json::from_slice(&bytes).map_err(|e| format!("Token 'iss' invalid: {e}"))?;
+    Ok(claims.iss)
+}
+
+/// Validate an opaque token using token introspection (RFC 7662)
+///
+/// The token must be active, and the issuer, audience, subject, expiration, and
+/// issued at claims in the introspection response are validated in the same way
+/// as those of JWT tokens. Active tokens are cached for up to a minute, or until
+/// they expire, and rejected tokens are cached briefly, so that repeated invalid
+/// tokens do not each cause an introspection request.
+async fn validate_introspected_token(
+    token: &str,
+    introspection: &TokenIntrospector,
+    config: &TokenValidationConfig,
+) -> Result<TokenClaims, String> {
+    // Check the cache for a previously introspected token
+    if let Some((result, expires_at)) = introspection.cache.read().await.get(token)
+        && SystemTime::now() < *expires_at
+    {
+        return result.clone();
+    }
+    // Introspect and validate the token
+    let result = introspect_claims(token, introspection, config).await;
+    // Cache valid tokens until they expire, for at most a minute
+    let expires_at = match &result {
+        Ok(claims) => {
+            let expires_at = SystemTime::now() + INTROSPECTION_CACHE_DURATION;
+            match claims.exp {
+                Some(exp) => expires_at.min(UNIX_EPOCH + Duration::from_secs(exp)),
+                None => expires_at,
+            }
+        }
+        Err(_) => SystemTime::now() + INTROSPECTION_FAILURE_CACHE_DURATION,
+    };
+    // Remove expired tokens, and only cache tokens while the cache is not full
+    let mut cache = introspection.cache.write().await;
+    cache.retain(|_, (_, v)| SystemTime::now() < *v);
+    if cache.len() < MAX_INTROSPECTION_CACHE_SIZE {
+        cache.insert(token.to_string(), (result.clone(), expires_at));
+    }
+    // Return the result
+    result
+}
+
+/// Introspect an opaque token and validate the claims in the response
+async fn introspect_claims(
+    token: &str,
+    introspection: &TokenIntrospector,
+    config: &TokenValidationConfig,
+) -> Result<TokenClaims, String> {
+    // Introspect the token
+    let res = introspection.introspect(token).await?;
+    // The token must be active
+    if !res.active {
+        return Err("Token is not active".to_string());
+    }
+    // Validate the issuer if specified
+    if let Some(iss) = &res.iss
+        && config.issuer(iss).is_none()
+    {
+        return Err(format!("Invalid issuer: {iss} is not trusted"));
+    }
+    // Create the claims from the introspection response
+    let claims = TokenClaims {
+        iss: res.iss.unwrap_or_else(|| introspection.url.clone()),
+        aud: res.aud,
+        exp: res.exp,
+        iat: res.iat,
+        sub: res.sub,
+        scope: res.scope,
+        permissions: None,
+    };
+    // Validate the remaining claims
+    validate_claims(&claims, config, true)?;
+    // Output debugging information
+    debug!(
+        issuer = %claims.iss,
+        subject = ?claims.sub,
+        expiration = ?claims.exp,
+        "Token introspected successfully"
+    );
+    // Return the claims
+    Ok(claims)
+}
+
+/// Validate a bearer token (supports JWE, JWT, and opaque formats)
 ///
 /// This function determines the token type based on the number of parts:
 /// - 5 parts: JWE token (JSON Web Encryption)
 /// - 3 parts: JWT token (JSON Web Token)
+/// - otherwise: opaque token, if token introspection is configured
 ///
 /// It then delegates to the appropriate validation function.
 async fn validate_bearer_token(
@@ -479,9 +1121,12 @@ async fn validate_bearer_token(
     match token.split('.').count() {
         5 => validate_jwe_token(token, config).await,
         3 => validate_jwt_token(token, config).await,
-        l => Err(format!(
-            "Invalid token format: expected 3 or 5 parts, got {l}"
-        )),
+        l => match &config.introspection {
+            Some(introspection) => validate_introspected_token(token, introspection, config).await,
+            None => Err(format!(
+                "Invalid token format: expected 3 or 5 parts, got {l}"
+            )),
+        },
     }
 }
 
@@ -491,13 +1136,17 @@ async fn validate_bearer_token(
 /// 1. Allows access to /.well-known/ and /health endpoints without authentication
 /// 2. Extracts the Bearer token from the Authorization header
 /// 3. Validates the token structure, issuer, and claims (where available)
-/// 4. Stores the validated token in the context extensions for use by subsequent services
+/// 4. Stores the validated token and identity in the context extensions for use by subsequent services
 /// 5. Returns 401 Unauthorized with proper WWW-Authenticate header if validation fails
+/// 6. Returns 403 Forbidden with an `insufficient_scope` challenge if scopes are required,
+///    and the token is not granted the scope required by a tool call or resource read
 ///
 /// Security considerations:
 /// - Validates token structure and issuer
 /// - For JWT tokens: validates audience, expiration, and issued at using JWKS
-/// - For JWE tokens: validates header structure and issuer only
+/// - For JWE tokens: decrypts the token and validates its claims if a key is configured,
+///   otherwise validates header structure and issuer only
+/// - For opaque tokens: validates the token using token introspection if configured
 /// - Supports both JWE and JWT token formats
 /// - Logs validation failures for monitoring
 /// - Uses proper HTTP status codes and headers
@@ -531,8 +1180,39 @@ pub async fn require_bearer_auth(
                     issued_at = ?claims.iat,
                     "Bearer token validated successfully"
                 );
+                // Get the authenticated identity from the claims
+                let identity = Identity::from_claims(claims, &config);
+                // Check the token is granted the scopes required by the request
+                if config.require_scopes && req.method() == Method::POST {
+                    // Read the JSON-RPC request body
+                    let (parts, body) = req.into_parts();
+                    let body = match axum::body::to_bytes(body, MAX_SCOPED_BODY_SIZE).await {
+                        Ok(body) => body,
+                        // Refuse request bodies which are larger than the limit
+                        Err(e) if e.source().is_some_and(|e| e.is::<LengthLimitError>()) => {
+                            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+                        }
+                        Err(_) => return Err(StatusCode::BAD_REQUEST),
+                    };
+                    // Refuse the request if a required scope is not granted
+                    if let Some(scope) = scopes::missing_scope(&body, &identity.scopes) {
+                        warn!(
+                            subject = identity.subject.as_deref().unwrap_or("unknown"),
+                            scope = scope,
+                            "Bearer token is missing a required scope"
+                        );
+                        // Update insufficient scope metrics
+                        counter!("surrealmcp.total_insufficient_scope_errors").increment(1);
+                        // Return a 403 response
+                        return Ok(insufficient_scope(scope));
+                    }
+                    // Restore the request body
+                    req = Request::from_parts(parts, Body::from(body));
+                }
                 // Store the token on the request context
                 req.extensions_mut().insert(token);
+                // Store the authenticated identity on the request context
+                req.extensions_mut().insert(identity);
                 // Continue to the next middleware
                 return Ok(next.run(req).await);
             }
@@ -552,6 +1232,14 @@ pub async fn require_bearer_auth(
     Ok(res)
 }
 
+/// Create a 403 response challenging the client to obtain a token with a scope
+fn insufficient_scope(scope: &str) -> Response {
+    let challenge = format!(
+        r#"Bearer error="insufficient_scope", scope="{scope}", resource_metadata="{RESOURCE_METADATA}", error_description="The token is not granted the {scope} scope""#
+    );
+    (StatusCode::FORBIDDEN, [(WWW_AUTHENTICATE, challenge)]).into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -568,19 +1256,28 @@ mod tests {
         let header = JweHeader {
             alg: "dir".to_string(),
             enc: "A256GCM".to_string(),
-            iss: issuer.to_string(),
+            iss: Some(issuer.to_string()),
+            zip: None,
         };
         let header_json = serde_json::to_string(&header).expect("Failed to serialize JWE header");
         let header_b64 = URL_SAFE_NO_PAD.encode(header_json.as_bytes());
         format!("{header_b64}.key.iv.payload.tag")
     }
 
+    /// Helper function to create a config which accepts JWE tokens by their header
+    fn header_only_config() -> TokenValidationConfig {
+        TokenValidationConfig {
+            insecure_jwe_headers: true,
+            ..Default::default()
+        }
+    }
+
     #[tokio::test]
     async fn test_validate_surrealdb_jwe_token() {
         // Example JWE token from SurrealDB auth service
         let token = make_test_jwe_token(EXPECTED_ISSUER);
 
-        let result = validate_jwe_token(&token, &TokenValidationConfig::default()).await;
+        let result = validate_jwe_token(&token, &header_only_config()).await;
         assert!(
             result.is_ok(),
             "Token validation should succeed: {result:?}"
@@ -608,7 +1305,7 @@ mod tests {
     async fn test_validate_jwe_header_structure() {
         let token = make_test_jwe_token(EXPECTED_ISSUER);
 
-        let result = validate_jwe_token(&token, &TokenValidationConfig::default()).await;
+        let result = validate_jwe_token(&token, &header_only_config()).await;
         assert!(result.is_ok(), "JWE token validation should succeed");
 
         let claims = result.unwrap();
@@ -619,41 +1316,313 @@ mod tests {
     async fn test_jwe_token_without_decryption_key() {
         let token = make_test_jwe_token(EXPECTED_ISSUER);
 
-        // Should fall back to header-only validation when no decryption key is available
+        // Should be rejected when no decryption key is available
         let result = validate_jwe_token(&token, &TokenValidationConfig::default()).await;
+        assert!(
+            result
+                .unwrap_err()
+                .contains("no decryption key is configured")
+        );
+
+        // Should fall back to header-only validation only when explicitly allowed
+        let result = validate_jwe_token(&token, &header_only_config()).await;
         assert!(result.is_ok());
         assert!(result.unwrap().iss == EXPECTED_ISSUER);
     }
 
+    /// Helper function to encrypt test claims as a JWE token using direct encryption
+    fn make_encrypted_jwe_token(key: &[u8; 32], claims: serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(
+            serde_json::json!({ "alg": "dir", "enc": "A256GCM", "iss": claims["iss"] }).to_string(),
+        );
+        let iv = [7u8; 12];
+        let key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).unwrap());
+        let mut payload = claims.to_string().into_bytes();
+        let tag = key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(iv),
+                Aad::from(header.as_bytes()),
+                &mut payload,
+            )
+            .unwrap();
+        format!(
+            "{header}..{}.{}.{}",
+            URL_SAFE_NO_PAD.encode(iv),
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(tag.as_ref()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_jwe_token_with_decryption_key() {
+        let key = [42u8; 32];
+        let config = TokenValidationConfig {
+            jwe_key: Some(JweKey::parse(&URL_SAFE_NO_PAD.encode(key)).unwrap()),
+            ..Default::default()
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let claims = |aud: &str, exp: u64| {
+            serde_json::json!({
+                "iss": EXPECTED_ISSUER,
+                "aud": [aud],
+                "sub": "user@example.com",
+                "iat": now - 60,
+                "exp": exp,
+                "scope": "openid db:read",
+            })
+        };
+        // Valid tokens are decrypted and their claims returned
+        let token = make_encrypted_jwe_token(&key, claims(EXPECTED_AUDIENCE, now + 3600));
+        let result = validate_jwe_token(&token, &config).await.unwrap();
+        assert_eq!(result.sub.as_deref(), Some("user@example.com"));
+        assert_eq!(result.scope.as_deref(), Some("openid db:read"));
+        // Expired tokens are rejected
+        let token = make_encrypted_jwe_token(&key, claims(EXPECTED_AUDIENCE, now - 3600));
+        let result = validate_jwe_token(&token, &config).await;
+        assert!(result.unwrap_err().contains("'exp' invalid"));
+        // Tokens for another audience are rejected
+        let token = make_encrypted_jwe_token(&key, claims("https://other.example.com/", now + 60));
+        let result = validate_jwe_token(&token, &config).await;
+        assert!(result.unwrap_err().contains("'aud' invalid"));
+        // Tokens missing a subject are rejected
+        let mut missing = claims(EXPECTED_AUDIENCE, now + 60);
+        missing.as_object_mut().unwrap().remove("sub");
+        let token = make_encrypted_jwe_token(&key, missing);
+        let result = validate_jwe_token(&token, &config).await;
+        assert!(result.unwrap_err().contains("'sub'"));
+        // Tokens encrypted with another key are rejected
+        let token = make_encrypted_jwe_token(&[1u8; 32], claims(EXPECTED_AUDIENCE, now + 60));
+        let result = validate_jwe_token(&token, &config).await;
+        assert!(result.unwrap_err().contains("Failed to decrypt"));
+        // Tokens with a forged header are rejected
+        let token = make_encrypted_jwe_token(&key, claims(EXPECTED_AUDIENCE, now + 60));
+        let header = format!(r#"{{"enc":"A256GCM","alg":"dir","iss":"{EXPECTED_ISSUER}"}}"#);
+        let forged = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header),
+            token.split_once('.').unwrap().1
+        );
+        assert!(validate_jwe_token(&forged, &config).await.is_err());
+        // Header-only tokens are rejected when a key is configured
+        let token = make_test_jwe_token(EXPECTED_ISSUER);
+        assert!(validate_jwe_token(&token, &config).await.is_err());
+        // Keys must be 256 bits long
+        assert!(JweKey::parse(&URL_SAFE_NO_PAD.encode([0u8; 16])).is_err());
+        assert!(JweKey::parse("not a key!").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_introspection() {
+        // Serve an introspection endpoint which accepts a single token
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/introspect", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = requests.clone();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let app = Router::new().route(
+            "/introspect",
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap, form: axum::Form<HashMap<String, String>>| {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        assert!(headers.contains_key(AUTHORIZATION));
+                        let response = match form.get("token").map(String::as_str) {
+                            Some("opaque-token") => serde_json::json!({
+                                "active": true,
+                                "iss": EXPECTED_ISSUER,
+                                "aud": EXPECTED_AUDIENCE,
+                                "sub": "user@example.com",
+                                "scope": "db:read",
+                                "exp": now + 3600,
+                                "iat": now,
+                            }),
+                            Some("missing-subject") => serde_json::json!({
+                                "active": true,
+                                "aud": EXPECTED_AUDIENCE,
+                                "exp": now + 3600,
+                                "iat": now,
+                            }),
+                            Some("other-audience") => serde_json::json!({
+                                "active": true,
+                                "aud": "https://other.example.com/",
+                                "sub": "user@example.com",
+                                "exp": now + 3600,
+                                "iat": now,
+                            }),
+                            _ => serde_json::json!({ "active": false }),
+                        };
+                        axum::Json(response)
+                    }
+                },
+            ),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let config = TokenValidationConfig {
+            introspection: Some(TokenIntrospector::new(
+                &url,
+                Some("surrealmcp".to_string()),
+                Some("secret".to_string()),
+            )),
+            ..Default::default()
+        };
+        // Active tokens are accepted
+        let claims = validate_bearer_token("opaque-token", &config)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub.as_deref(), Some("user@example.com"));
+        assert_eq!(claims.scope.as_deref(), Some("db:read"));
+        // Introspected tokens are cached
+        validate_bearer_token("opaque-token", &config)
+            .await
+            .unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+        // Inactive tokens are rejected, and the rejection is cached
+        let result = validate_bearer_token("revoked-token", &config).await;
+        assert!(result.unwrap_err().contains("not active"));
+        let result = validate_bearer_token("revoked-token", &config).await;
+        assert!(result.unwrap_err().contains("not active"));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+        // Tokens without the required claims are rejected
+        let result = validate_bearer_token("missing-subject", &config).await;
+        assert!(result.unwrap_err().contains("missing required claim 'sub'"));
+        // Tokens for another audience are rejected
+        let result = validate_bearer_token("other-audience", &config).await;
+        assert!(result.unwrap_err().contains("'aud' invalid"));
+        // Opaque tokens are rejected without an introspection endpoint
+        let result = validate_bearer_token("opaque-token", &TokenValidationConfig::default()).await;
+        assert!(result.unwrap_err().contains("Invalid token format"));
+    }
+
     #[test]
     fn test_token_validation_config_default() {
         let config = TokenValidationConfig::default();
-        assert_eq!(config.expected_issuer, EXPECTED_ISSUER);
+        assert_eq!(config.issuers.len(), 1);
+        assert_eq!(config.issuers[0].issuer, EXPECTED_ISSUER);
         assert_eq!(config.expected_audience, EXPECTED_AUDIENCE);
         assert!(config.validate_expiration);
         assert!(config.validate_issued_at);
         assert_eq!(config.clock_skew_seconds, 300);
-        assert!(config.jwks_manager.is_some());
+        assert!(config.issuers[0].jwks_manager.is_some());
+        assert!(!config.algorithms.contains(&Algorithm::HS256));
     }
 
     #[test]
     fn test_custom_token_validation_config() {
         let config = TokenValidationConfig {
-            expected_issuer: "https://custom.issuer.com/".to_string(),
+            issuers: vec![TrustedIssuer {
+                issuer: "https://custom.issuer.com/".to_string(),
+                jwks_manager: None,
+            }],
             expected_audience: "https://custom.audience.com/".to_string(),
             jwt_public_key: None,
             validate_expiration: false,
             validate_issued_at: false,
             clock_skew_seconds: 600,
-            jwks_manager: None,
+            algorithms: vec![Algorithm::RS256],
+            jwe_key: None,
+            insecure_jwe_headers: false,
+            introspection: None,
+            require_scopes: true,
         };
 
-        assert_eq!(config.expected_issuer, "https://custom.issuer.com/");
+        assert_eq!(config.issuers[0].issuer, "https://custom.issuer.com/");
         assert_eq!(config.expected_audience, "https://custom.audience.com/");
         assert!(!config.validate_expiration);
         assert!(!config.validate_issued_at);
         assert_eq!(config.clock_skew_seconds, 600);
-        assert!(config.jwks_manager.is_none());
+        assert!(config.issuers[0].jwks_manager.is_none());
+        assert_eq!(config.algorithms, vec![Algorithm::RS256]);
+        assert!(config.require_scopes);
+    }
+
+    #[test]
+    fn test_token_validation_config_from_auth_config() {
+        // Tokens from SurrealDB Cloud are trusted by default
+        let config = TokenValidationConfig::from_auth_config(&AuthConfig::default()).unwrap();
+        assert_eq!(config.issuers.len(), 1);
+        assert_eq!(config.issuers[0].issuer, EXPECTED_ISSUER);
+        assert_eq!(config.algorithms, DEFAULT_ALGORITHMS);
+        // Configured issuers replace SurrealDB Cloud
+        let auth = AuthConfig::default().merge(
+            vec![
+                "https://keycloak.example.com/realms/main".into(),
+                "https://auth.example.com/".into(),
+            ],
+            None,
+            vec!["RS256".into(), "ES256".into()],
+            Some(60),
+        );
+        let config = TokenValidationConfig::from_auth_config(&auth).unwrap();
+        assert_eq!(config.issuers.len(), 2);
+        assert!(config.issuer(EXPECTED_ISSUER).is_none());
+        let issuer = config.issuer("https://auth.example.com/").unwrap();
+        let jwks = issuer.jwks_manager.as_ref().unwrap();
+        assert!(matches!(jwks.source, JwksSource::Discovery(_)));
+        assert_eq!(jwks.cache_ttl, Duration::from_secs(60));
+        assert_eq!(config.algorithms, vec![Algorithm::RS256, Algorithm::ES256]);
+        // Unknown algorithms are rejected
+        let auth = AuthConfig::default().merge(vec![], None, vec!["RS257".into()], None);
+        assert!(TokenValidationConfig::from_auth_config(&auth).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_untrusted_issuer() {
+        // JWE tokens from an untrusted issuer are rejected
+        let token = make_test_jwe_token("https://evil.example.com/");
+        let result = validate_jwe_token(&token, &TokenValidationConfig::default()).await;
+        assert!(result.unwrap_err().contains("is not trusted"));
+        // JWT tokens from an untrusted issuer are rejected before fetching keys
+        let encode = |v: serde_json::Value| URL_SAFE_NO_PAD.encode(v.to_string());
+        let header = encode(serde_json::json!({ "alg": "RS256", "kid": "key" }));
+        let payload = encode(serde_json::json!({ "iss": "https://evil.example.com/" }));
+        let token = format!("{header}.{payload}.signature");
+        let result = validate_jwt_token(&token, &TokenValidationConfig::default()).await;
+        assert!(result.unwrap_err().contains("is not trusted"));
+        // JWT tokens using a disallowed algorithm are rejected
+        let header = encode(serde_json::json!({ "alg": "HS256" }));
+        let payload = encode(serde_json::json!({ "iss": EXPECTED_ISSUER }));
+        let token = format!("{header}.{payload}.signature");
+        let result = validate_jwt_token(&token, &TokenValidationConfig::default()).await;
+        assert!(result.unwrap_err().contains("is not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_jwks_discovery() {
+        // Serve an OpenID Connect discovery document and JWKS
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}/realms/main", listener.local_addr().unwrap());
+        let configuration = serde_json::json!({
+            "issuer": issuer,
+            "jwks_uri": format!("{issuer}/protocol/openid-connect/certs"),
+        });
+        let jwks = serde_json::json!({
+            "keys": [{ "kty": "RSA", "kid": "key", "n": "AQAB", "e": "AQAB" }],
+        });
+        let app = Router::new()
+            .route(
+                "/realms/main/.well-known/openid-configuration",
+                get(move || async move { axum::Json(configuration) }),
+            )
+            .route(
+                "/realms/main/protocol/openid-connect/certs",
+                get(move || async move { axum::Json(jwks) }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        // The JWKS endpoint is discovered from the issuer
+        let manager = JwksManager::with_discovery(&issuer, JWKS_CACHE_DURATION);
+        let jwks = manager.fetch_jwks().await.unwrap();
+        assert_eq!(jwks.keys.len(), 1);
+        assert!(manager.get_decoding_key("key").await.is_ok());
+        // The discovery document must be for the same issuer
+        let manager = JwksManager::with_discovery(format!("{issuer}/"), JWKS_CACHE_DURATION);
+        assert!(manager.fetch_jwks().await.is_err());
     }
 
     #[tokio::test]
@@ -662,7 +1631,7 @@ mod tests {
             Router::new()
                 .route("/test", get(|| async { "OK" }))
                 .layer(axum::middleware::from_fn(|req, next| {
-                    let config = TokenValidationConfig::default();
+                    let config = header_only_config();
                     require_bearer_auth(config, req, next)
                 }));
 
@@ -678,6 +1647,113 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[test]
+    fn test_identity_from_claims() {
+        let claims = || TokenClaims {
+            iss: EXPECTED_ISSUER.to_string(),
+            aud: Some(Audience::One(EXPECTED_AUDIENCE.to_string())),
+            exp: Some(1_000),
+            iat: Some(0),
+            sub: Some("auth0|123".to_string()),
+            scope: Some("openid  db:read db:write".to_string()),
+            permissions: Some(vec!["db:read".to_string(), "cloud:read".to_string()]),
+        };
+        let config = TokenValidationConfig::default();
+        let identity = Identity::from_claims(claims(), &config);
+        assert_eq!(identity.subject.as_deref(), Some("auth0|123"));
+        assert_eq!(
+            identity.scopes,
+            vec!["cloud:read", "db:read", "db:write", "openid"]
+        );
+        assert_eq!(identity.expires_at, Some(1_300));
+        assert!(identity.is_expired());
+        // Tokens do not expire if expiration is not validated
+        let config = TokenValidationConfig {
+            validate_expiration: false,
+            ..config
+        };
+        let identity = Identity::from_claims(claims(), &config);
+        assert_eq!(identity.expires_at, None);
+        assert!(!identity.is_expired());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_stores_identity() {
+        let app = Router::new()
+            .route(
+                "/test",
+                get(|req: Request<Body>| async move {
+                    req.extensions().get::<Identity>().unwrap().issuer.clone()
+                }),
+            )
+            .layer(axum::middleware::from_fn(|req, next| {
+                let config = header_only_config();
+                require_bearer_auth(config, req, next)
+            }));
+
+        let token = make_test_jwe_token(EXPECTED_ISSUER);
+
+        let request = Request::builder()
+            .uri("/test")
+            .header("Authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, EXPECTED_ISSUER);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_insufficient_scope() {
+        let app = Router::new()
+            .route("/mcp", axum::routing::post(|| async { "OK" }))
+            .layer(axum::middleware::from_fn(|req, next| {
+                let config = TokenValidationConfig {
+                    require_scopes: true,
+                    ..header_only_config()
+                };
+                require_bearer_auth(config, req, next)
+            }));
+
+        let token = make_test_jwe_token(EXPECTED_ISSUER);
+        let request = |body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/mcp")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // Requests which do not require a scope are allowed
+        let body = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" });
+        let response = app.clone().oneshot(request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Tool calls without the required scope are forbidden
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "delete", "arguments": { "targets": ["person"] } },
+        });
+        let response = app.clone().oneshot(request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let challenge = response.headers().get(WWW_AUTHENTICATE).unwrap();
+        let challenge = challenge.to_str().unwrap();
+        assert!(challenge.contains(r#"error="insufficient_scope""#));
+        assert!(challenge.contains(r#"scope="db:write""#));
+
+        // Request bodies which are too large to check are refused
+        let body = serde_json::json!({ "padding": "x".repeat(MAX_SCOPED_BODY_SIZE) });
+        let response = app.oneshot(request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
     #[tokio::test]
     async fn test_middleware_with_invalid_token() {
         let app =
@@ -754,7 +1830,7 @@ mod tests {
             }],
         };
 
-        let cached_jwks = CachedJwks::new(jwks.keys);
+        let cached_jwks = CachedJwks::new(jwks.keys, JWKS_CACHE_DURATION);
         assert!(!cached_jwks.is_expired());
 
         // Test that we can get the key
@@ -817,7 +1893,7 @@ mod tests {
             custom_config.expected_audience,
             "https://custom.audience.com/"
         );
-        assert_eq!(custom_config.expected_issuer, EXPECTED_ISSUER);
+        assert_eq!(custom_config.issuers[0].issuer, EXPECTED_ISSUER);
         assert!(custom_config.validate_expiration);
         assert!(custom_config.validate_issued_at);
     }
@@ -1,6 +1,8 @@
 mod auth;
 mod http;
 mod limit;
+mod scopes;
 mod start;
 
+pub use auth::Identity;
 pub use start::{ServerConfig, start_server};
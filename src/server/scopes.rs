@@ -0,0 +1,191 @@
+use crate::engine;
+use serde_json::{Map, Value};
+
+/// Scope required to read data and schema from the database
+pub const DB_READ: &str = "db:read";
+
+/// Scope required to create, modify, or delete data in the database
+pub const DB_WRITE: &str = "db:write";
+
+/// Scope required to define, alter, or remove the database schema
+pub const DB_SCHEMA: &str = "db:schema";
+
+/// Scope required to view SurrealDB Cloud organizations and instances
+pub const CLOUD_READ: &str = "cloud:read";
+
+/// Scope required to create, pause, and resume SurrealDB Cloud instances
+pub const CLOUD_ADMIN: &str = "cloud:admin";
+
+/// The MCP scopes which can be granted to a bearer token
+pub const SUPPORTED_SCOPES: &[&str] = &[DB_READ, DB_WRITE, DB_SCHEMA, CLOUD_READ, CLOUD_ADMIN];
+
+/// Get the scopes required to call a tool with the given arguments
+///
+/// The `query` tool requires `db:schema` for queries which define or modify the
+/// schema, `db:write` for queries which modify data, and `db:read` for queries
+/// which only read data. Queries containing both schema and data changes require
+/// both scopes. Unknown tools do not require a scope, as the call will fail.
+pub fn tool_scopes(tool: &str, arguments: Option<&Map<String, Value>>) -> Vec<&'static str> {
+    let scope = match tool {
+        // Arbitrary queries depend on the statements they contain
+        "query" => {
+            let query = arguments
+                .and_then(|v| v.get("query"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let mut scopes = Vec::new();
+            if engine::check_schema(query) {
+                scopes.push(DB_SCHEMA);
+            }
+            if engine::check_writes(query) {
+                scopes.push(DB_WRITE);
+            }
+            if scopes.is_empty() {
+                scopes.push(DB_READ);
+            }
+            return scopes;
+        }
+        // Tools which modify data
+        "insert" | "create" | "upsert" | "update" | "delete" | "relate" => Some(DB_WRITE),
+        // Tools which read data or schema, or manage the session
+        "select"
+        | "explain"
+        | "live_query"
+        | "kill_live_query"
+        | "next_page"
+        | "begin_transaction"
+        | "commit_transaction"
+        | "rollback_transaction"
+        | "list_namespaces"
+        | "list_databases"
+        | "list_tables"
+        | "describe_table"
+        | "list_fields"
+        | "list_indexes"
+        | "list_analyzers"
+        | "connect_endpoint"
+        | "connect_profile"
+        | "list_profiles"
+        | "list_connections"
+        | "use_namespace"
+        | "use_database"
+        | "disconnect_endpoint" => Some(DB_READ),
+        // Tools which view cloud resources
+        "list_cloud_organizations" | "list_cloud_instances" | "get_cloud_instance_status" => {
+            Some(CLOUD_READ)
+        }
+        // Tools which manage cloud resources
+        "create_cloud_instance" | "pause_cloud_instance" | "resume_cloud_instance" => {
+            Some(CLOUD_ADMIN)
+        }
+        _ => None,
+    };
+    scope.into_iter().collect()
+}
+
+/// Get the scopes required by a single JSON-RPC message
+fn message_scopes(message: &Value) -> Vec<&'static str> {
+    let params = message.get("params").and_then(|v| v.as_object());
+    match message.get("method").and_then(|v| v.as_str()) {
+        // Tool calls depend on the tool and its arguments
+        Some("tools/call") => {
+            let Some(name) = params.and_then(|v| v.get("name")).and_then(|v| v.as_str()) else {
+                return Vec::new();
+            };
+            let arguments = params
+                .and_then(|v| v.get("arguments"))
+                .and_then(|v| v.as_object());
+            tool_scopes(name, arguments)
+        }
+        // Reading schema, record, and live query resources
+        Some("resources/read" | "resources/subscribe") => vec![DB_READ],
+        _ => Vec::new(),
+    }
+}
+
+/// Find the first scope required by a JSON-RPC request body which is not granted
+///
+/// The body may contain a single message or a batch of messages. Bodies which
+/// are not valid JSON do not require a scope, as they will be refused by the
+/// MCP service.
+pub fn missing_scope(body: &[u8], granted: &[String]) -> Option<&'static str> {
+    // Parse the request body
+    let body: Value = serde_json::from_slice(body).ok()?;
+    // Get the messages in the request body
+    let messages = match &body {
+        Value::Array(v) => v.iter().collect(),
+        v => vec![v],
+    };
+    // Check the scopes required by each message
+    messages
+        .into_iter()
+        .flat_map(message_scopes)
+        .find(|scope| !granted.iter().any(|v| v == scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_scopes() {
+        let args = |v: Value| v.as_object().unwrap().clone();
+        assert_eq!(tool_scopes("select", None), vec![DB_READ]);
+        assert_eq!(tool_scopes("delete", None), vec![DB_WRITE]);
+        assert_eq!(tool_scopes("list_cloud_instances", None), vec![CLOUD_READ]);
+        assert_eq!(tool_scopes("pause_cloud_instance", None), vec![CLOUD_ADMIN]);
+        assert!(tool_scopes("unknown", None).is_empty());
+        // Queries require the scopes for the statements they contain
+        let query = |q: &str| tool_scopes("query", Some(&args(json!({ "query": q }))));
+        assert_eq!(query("SELECT * FROM person"), vec![DB_READ]);
+        assert_eq!(query("CREATE person:john"), vec![DB_WRITE]);
+        assert_eq!(query("DEFINE TABLE person"), vec![DB_SCHEMA]);
+        assert_eq!(query("SELECT FROM WHERE"), vec![DB_WRITE]);
+        // Queries which change both schema and data require both scopes
+        assert_eq!(
+            query("DEFINE TABLE person; DELETE person"),
+            vec![DB_SCHEMA, DB_WRITE]
+        );
+    }
+
+    #[test]
+    fn test_missing_scope() {
+        let call = |name: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": {} },
+            })
+        };
+        let body = |v: Value| serde_json::to_vec(&v).unwrap();
+        let granted = vec!["openid".to_string(), DB_READ.to_string()];
+        assert_eq!(missing_scope(&body(call("select")), &granted), None);
+        assert_eq!(
+            missing_scope(&body(call("create")), &granted),
+            Some(DB_WRITE)
+        );
+        // Batches require the scopes of every message
+        let batch = json!([call("select"), call("create_cloud_instance")]);
+        assert_eq!(missing_scope(&body(batch), &granted), Some(CLOUD_ADMIN));
+        // Other methods do not require a scope
+        let list = json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" });
+        assert_eq!(missing_scope(&body(list), &[]), None);
+        let read = json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/read" });
+        assert_eq!(missing_scope(&body(read), &[]), Some(DB_READ));
+        assert_eq!(missing_scope(b"not json", &[]), None);
+        // Queries which change both schema and data are refused without either scope
+        let mixed = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "query",
+                "arguments": { "query": "DEFINE TABLE person; DELETE person" },
+            },
+        });
+        let schema = vec![DB_SCHEMA.to_string()];
+        assert_eq!(missing_scope(&body(mixed), &schema), Some(DB_WRITE));
+    }
+}
@@ -6,6 +6,7 @@ use rmcp::transport::{
     streamable_http_server::{session::local::LocalSessionManager, tower::StreamableHttpService},
 };
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -16,12 +17,18 @@ use tokio_util::sync::CancellationToken;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{debug, error, info, warn};
 
+use crate::audit::{AuditConfig, AuditLog};
+use crate::config::{AuthConfig, ConnectionsConfig, ProfileConfig, ToolsConfig};
+use crate::db::SigninConfig;
+use crate::engine::ResultLimits;
 use crate::logs::init_logging_and_metrics;
+use crate::pool::{ConnectionPool, PoolConfig};
 use crate::server::auth::{TokenValidationConfig, require_bearer_auth};
 use crate::server::http::health;
 use crate::server::limit::create_rate_limit_layer;
-use crate::tools::SurrealService;
-use crate::utils::{format_duration, generate_connection_id};
+use crate::server::scopes::SUPPORTED_SCOPES;
+use crate::tools::{ConfirmationPolicy, SurrealService};
+use crate::utils::{JsonEncoding, format_duration, generate_connection_id};
 
 /// Configuration for server startup
 #[derive(Clone)]
@@ -31,14 +38,29 @@ pub struct ServerConfig {
     pub db: Option<String>,
     pub user: Option<String>,
     pub pass: Option<String>,
+    pub signin: SigninConfig,
     pub server_url: String,
     pub bind_address: Option<String>,
     pub socket_path: Option<String>,
     pub auth_disabled: bool,
+    pub require_scopes: bool,
     pub rate_limit_rps: u32,
     pub rate_limit_burst: u32,
     pub auth_server: String,
     pub auth_audience: String,
+    pub read_only: bool,
+    pub json_encoding: JsonEncoding,
+    pub result_limits: ResultLimits,
+    pub query_timeout: Option<Duration>,
+    pub max_live_queries: usize,
+    pub transaction_timeout: Option<Duration>,
+    pub pool: Option<PoolConfig>,
+    pub confirmation: ConfirmationPolicy,
+    pub audit: Option<AuditConfig>,
+    pub tools: ToolsConfig,
+    pub connections: ConnectionsConfig,
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    pub auth: AuthConfig,
     pub cloud_access_token: Option<String>,
     pub cloud_refresh_token: Option<String>,
 }
@@ -72,12 +94,35 @@ pub async fn start_server(config: ServerConfig) -> Result<()> {
         bind_address = config.bind_address.as_deref().unwrap_or("N/A"),
         socket_path = config.socket_path.as_deref().unwrap_or("N/A"),
         auth_disabled = config.auth_disabled,
+        require_scopes = config.require_scopes,
         rate_limit_rps = config.rate_limit_rps,
         rate_limit_burst = config.rate_limit_burst,
         auth_server = config.auth_server,
         auth_audience = config.auth_audience,
+        signin_level = ?config.signin.level,
+        forward_token = config.signin.forward_token,
+        read_only = config.read_only,
+        json_encoding = ?config.json_encoding,
+        max_result_rows = config.result_limits.max_rows,
+        max_result_bytes = config.result_limits.max_bytes,
+        query_timeout = ?config.query_timeout,
+        max_live_queries = config.max_live_queries,
+        transaction_timeout = ?config.transaction_timeout,
+        pool_max_size = config.pool.as_ref().map(|v| v.max_size),
+        confirm_destructive = ?config.confirmation.confirm,
+        allow_unconfirmed = config.confirmation.allow_unconfirmed,
+        audit_log = config.audit.as_ref().map(|v| v.directory.as_str()),
+        allow_tools = ?config.tools.allow,
+        deny_tools = ?config.tools.deny,
+        allow_endpoints = ?config.connections.allow_endpoints,
+        allow_namespaces = ?config.connections.allow_namespaces,
+        allow_databases = ?config.connections.allow_databases,
+        profiles = ?config.profiles.keys().collect::<Vec<_>>(),
+        auth_issuers = ?config.auth.issuers.iter().map(|v| &v.issuer).collect::<Vec<_>>(),
         "Server configuration loaded"
     );
+    // Ensure the tool lists only reference existing tools
+    SurrealService::check_tools_config(&config.tools)?;
     match (config.bind_address.is_some(), config.socket_path.is_some()) {
         // We are running as a STDIO server
         (false, false) => start_stdio_server(config, token).await,
@@ -92,6 +137,24 @@ pub async fn start_server(config: ServerConfig) -> Result<()> {
     }
 }
 
+/// Open the audit log if one is configured
+fn open_audit_log(config: Option<AuditConfig>) -> Result<Option<Arc<AuditLog>>> {
+    match config {
+        Some(config) => {
+            // Open the audit log directory
+            let audit = AuditLog::open(&config)?;
+            // Output debugging information
+            info!(
+                directory = %config.directory,
+                rotation = ?config.rotation,
+                "Audit log opened"
+            );
+            Ok(Some(Arc::new(audit)))
+        }
+        None => Ok(None),
+    }
+}
+
 /// Start the MCP server in stdio mode
 async fn start_stdio_server(config: ServerConfig, token: CancellationToken) -> Result<()> {
     // Extract configuration values
@@ -101,12 +164,26 @@ async fn start_stdio_server(config: ServerConfig, token: CancellationToken) -> R
         db,
         user,
         pass,
+        signin,
+        read_only,
+        json_encoding,
+        result_limits,
+        query_timeout,
+        max_live_queries,
+        transaction_timeout,
+        confirmation,
+        audit,
+        tools,
+        connections,
+        profiles,
         cloud_access_token,
         cloud_refresh_token,
         ..
     } = config;
     // Initialize structured logging and metrics
     init_logging_and_metrics(true);
+    // Open the audit log if configured
+    let audit = open_audit_log(audit)?;
     // Output debugging information
     info!("Starting MCP server in stdio mode");
     // Generate a connection ID for this connection
@@ -121,7 +198,19 @@ async fn start_stdio_server(config: ServerConfig, token: CancellationToken) -> R
         pass,
         cloud_access_token,
         cloud_refresh_token,
-    );
+    )
+    .with_read_only(read_only)
+    .with_json_encoding(json_encoding)
+    .with_result_limits(result_limits)
+    .with_query_timeout(query_timeout)
+    .with_max_live_queries(max_live_queries)
+    .with_transaction_timeout(transaction_timeout)
+    .with_confirmation_policy(confirmation)
+    .with_audit_log(audit)
+    .with_signin(signin)
+    .with_connections_config(connections)
+    .with_profiles(Arc::new(profiles))
+    .with_tools_config(&tools);
     // Initialize the connection using startup configuration
     if let Err(e) = service.initialize_connection().await {
         error!(
@@ -175,7 +264,19 @@ async fn start_unix_server(config: ServerConfig, token: CancellationToken) -> Re
         db,
         user,
         pass,
+        signin,
         socket_path,
+        read_only,
+        json_encoding,
+        result_limits,
+        query_timeout,
+        max_live_queries,
+        transaction_timeout,
+        confirmation,
+        audit,
+        tools,
+        connections,
+        profiles,
         cloud_access_token,
         cloud_refresh_token,
         ..
@@ -188,6 +289,10 @@ async fn start_unix_server(config: ServerConfig, token: CancellationToken) -> Re
     );
     // Initialize structured logging and metrics
     init_logging_and_metrics(false);
+    // Open the audit log if configured
+    let audit = open_audit_log(audit)?;
+    // Share the connection profiles between client sessions
+    let profiles = Arc::new(profiles);
     // Remove existing socket file if it exists
     if socket_path.exists() {
         fs::remove_file(socket_path).await?;
@@ -242,6 +347,11 @@ async fn start_unix_server(config: ServerConfig, token: CancellationToken) -> Re
         let pass = pass.clone();
         let cloud_access_token = cloud_access_token.clone();
         let cloud_refresh_token = cloud_refresh_token.clone();
+        let tools = tools.clone();
+        let audit = audit.clone();
+        let signin = signin.clone();
+        let connections = connections.clone();
+        let profiles = profiles.clone();
         // Spawn a new async task to handle this client connection
         let connection_token = token.clone();
         tokio::spawn(async move {
@@ -259,7 +369,19 @@ async fn start_unix_server(config: ServerConfig, token: CancellationToken) -> Re
                 pass,
                 cloud_access_token,
                 cloud_refresh_token,
-            );
+            )
+            .with_read_only(read_only)
+            .with_json_encoding(json_encoding)
+            .with_result_limits(result_limits)
+            .with_query_timeout(query_timeout)
+            .with_max_live_queries(max_live_queries)
+            .with_transaction_timeout(transaction_timeout)
+            .with_confirmation_policy(confirmation)
+            .with_audit_log(audit)
+            .with_signin(signin)
+            .with_connections_config(connections)
+            .with_profiles(profiles)
+            .with_tools_config(&tools);
             // Initialize the connection using startup configuration only if endpoint is specified
             if let Err(e) = service.initialize_connection().await {
                 error!(
@@ -326,13 +448,28 @@ async fn start_http_server(config: ServerConfig, token: CancellationToken) -> Re
         db,
         user,
         pass,
+        signin,
         server_url,
         bind_address,
         auth_disabled,
+        require_scopes,
         rate_limit_rps,
         rate_limit_burst,
         auth_server,
         auth_audience,
+        read_only,
+        json_encoding,
+        result_limits,
+        query_timeout,
+        max_live_queries,
+        transaction_timeout,
+        confirmation,
+        audit,
+        tools,
+        connections,
+        profiles,
+        auth,
+        pool,
         cloud_access_token,
         cloud_refresh_token,
         ..
@@ -341,6 +478,17 @@ async fn start_http_server(config: ServerConfig, token: CancellationToken) -> Re
     let bind_address = bind_address.as_deref().unwrap();
     // Initialize structured logging and metrics
     init_logging_and_metrics(false);
+    // Open the audit log if configured
+    let audit = open_audit_log(audit)?;
+    // Share the connection profiles between client sessions
+    let profiles = Arc::new(profiles);
+    // Remote clients can only use embedded engines if they are explicitly allowed
+    let connections = ConnectionsConfig {
+        deny_embedded: true,
+        ..connections
+    };
+    // Share a pool of connections to the configured endpoint between client sessions
+    let pool = pool.filter(|_| endpoint.is_some()).map(ConnectionPool::new);
     // Output debugging information
     info!(
         server_url = %server_url,
@@ -353,12 +501,17 @@ async fn start_http_server(config: ServerConfig, token: CancellationToken) -> Re
     let listener = TcpListener::bind(&bind_address)
         .await
         .map_err(|e| anyhow!("Failed to bind to address {bind_address}: {e}"))?;
+    // List the OpenID and MCP scopes which clients can request
+    let scopes_supported: Vec<&str> = ["openid", "profile", "email", "offline_access"]
+        .into_iter()
+        .chain(SUPPORTED_SCOPES.iter().copied())
+        .collect();
     // List servers for authentication discovery
     let protected_resource = Json(json!({
         "resource": server_url,
         "bearer_methods_supported": ["header"],
         "authorization_servers": [auth_server],
-        "scopes_supported": ["openid", "profile", "email", "offline_access"],
+        "scopes_supported": scopes_supported,
         "audience": [
             "https://surrealdb.us.auth0.com/userinfo",
             "https://surrealdb.us.auth0.com/api/v2/",
@@ -392,7 +545,20 @@ async fn start_http_server(config: ServerConfig, token: CancellationToken) -> Re
                 pass.clone(),
                 cloud_access_token.clone(),
                 cloud_refresh_token.clone(),
-            ))
+            )
+            .with_read_only(read_only)
+            .with_json_encoding(json_encoding)
+            .with_result_limits(result_limits)
+            .with_query_timeout(query_timeout)
+            .with_max_live_queries(max_live_queries)
+            .with_transaction_timeout(transaction_timeout)
+            .with_confirmation_policy(confirmation)
+            .with_audit_log(audit.clone())
+            .with_signin(signin.clone())
+            .with_connections_config(connections.clone())
+            .with_profiles(profiles.clone())
+            .with_connection_pool(pool.clone())
+            .with_tools_config(&tools))
         },
         session_manager,
         StreamableHttpServerConfig {
@@ -445,21 +611,27 @@ async fn start_http_server(config: ServerConfig, token: CancellationToken) -> Re
         .nest_service("/.well-known", well_known_service)
         .nest_service("/mcp", mcp_service)
         .route("/health", get(health))
-        .layer(trace_layer)
-        .layer(rate_limit_layer);
+        .layer(trace_layer);
     // Add bearer authentication middleware if specified
     if !auth_disabled {
         // Set the token validation config
         let token_config = TokenValidationConfig {
             expected_audience: auth_audience.clone(),
-            ..Default::default()
+            require_scopes,
+            ..TokenValidationConfig::from_auth_config(&auth)?
         };
+        // Warn if JWE tokens are accepted without being decrypted
+        if token_config.jwe_key.is_none() && token_config.insecure_jwe_headers {
+            warn!("No JWE key configured, so only the headers of JWE tokens will be validated");
+        }
         // Add bearer authentication middleware
         router = router.layer(axum::middleware::from_fn(move |req, next| {
             let config = token_config.clone();
             require_bearer_auth(config, req, next)
         }));
     }
+    // Rate limit requests before they are authenticated
+    let router = router.layer(rate_limit_layer);
     // Serve the Axum router over HTTP
     axum::serve(listener, router)
         .with_graceful_shutdown(async move {
@@ -490,14 +662,29 @@ mod tests {
             db: None,
             user: None,
             pass: None,
+            signin: SigninConfig::default(),
             server_url: "https://mcp.surrealdb.com".to_string(),
             bind_address: Some("127.0.0.1:0".to_string()),
             socket_path: None,
             auth_disabled: true,
+            require_scopes: false,
             rate_limit_rps: 100,
             rate_limit_burst: 200,
             auth_server: "https://auth.surrealdb.com".to_string(),
             auth_audience: "https://custom.audience.com/".to_string(),
+            read_only: false,
+            json_encoding: JsonEncoding::default(),
+            result_limits: ResultLimits::default(),
+            query_timeout: None,
+            max_live_queries: 10,
+            transaction_timeout: None,
+            pool: None,
+            confirmation: ConfirmationPolicy::default(),
+            audit: None,
+            tools: ToolsConfig::default(),
+            connections: ConnectionsConfig::default(),
+            profiles: BTreeMap::new(),
+            auth: AuthConfig::default(),
             cloud_access_token: None,
             cloud_refresh_token: None,
         };
@@ -1,34 +1,229 @@
 use crate::utils;
 use anyhow::Result;
+use futures::future::BoxFuture;
 use http::request::Parts;
 use metrics::counter;
+use rmcp::Peer;
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler,
     handler::server::router::tool::ToolRouter,
+    handler::server::tool::{ToolCallContext, schema_for_type},
     handler::server::wrapper::Parameters,
     model::{CallToolResult, Content, ServerCapabilities, ServerInfo},
-    service::RequestContext,
-    tool, tool_handler, tool_router,
+    service::{ElicitationError, ElicitationMode, RequestContext},
+    tool, tool_router,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use surrealdb::{Surreal, engine::any::Any, types::Value};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 
-use crate::cloud::Client;
-use crate::db;
+use crate::audit::{self, AuditEntry, AuditLog, CallContext};
+use crate::cloud::{Client, CloudInstance, CloudInstanceBackup, CloudOrganization};
+use crate::config::{ConnectionsConfig, ProfileConfig, ToolsConfig};
+use crate::db::{self, SigninConfig};
 use crate::engine;
+use crate::engine::{CursorStore, OutputOptions, ResultLimits};
+use crate::live::{self, LiveQueries};
+use crate::pool::{ConnectionPool, PoolLease};
 use crate::prompts;
 use crate::resources;
-use crate::utils::{convert_json_to_surreal, parse_target, parse_targets};
+use crate::server::Identity;
+use crate::transaction::{SessionTransaction, TransactionResult};
+use crate::utils::{JsonEncoding, parse_target, parse_targets};
 
 // Global metrics
 static QUERY_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Tools which modify data or cloud resources, or run statements refused in read-only mode
+const MUTATING_TOOLS: &[&str] = &[
+    "insert",
+    "create",
+    "upsert",
+    "update",
+    "delete",
+    "relate",
+    "live_query",
+    "create_cloud_instance",
+    "pause_cloud_instance",
+    "resume_cloud_instance",
+];
+
+/// The time to wait for the client user to confirm a destructive operation
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The name of the connection opened by connect_endpoint without a name
+const DEFAULT_CONNECTION: &str = "default";
+
+/// The maximum number of named connections which a session can hold open
+const MAX_NAMED_CONNECTIONS: usize = 8;
+
+/// The time to wait for a connection to respond to a health check
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// When destructive operations require confirmation from the client user
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfirmDestructive {
+    /// Destructive operations run without confirmation
+    #[default]
+    Never,
+    /// Destructive operations are confirmed using MCP elicitation
+    Elicit,
+}
+
+/// The policy for confirming destructive operations with the client user
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConfirmationPolicy {
+    /// When destructive operations require confirmation
+    pub confirm: ConfirmDestructive,
+    /// Whether to run destructive operations if the client does not support elicitation
+    pub allow_unconfirmed: bool,
+}
+
+/// The confirmation requested from the client user for a destructive operation
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct DestructiveConfirmation {
+    #[schemars(description = "Whether to run the destructive operation.")]
+    pub confirm: bool,
+}
+
+rmcp::elicit_safe!(DestructiveConfirmation);
+
+/// The endpoint, namespace, and database which a session is currently using
+#[derive(Clone, Debug, Default)]
+pub struct SessionTarget {
+    /// The connected SurrealDB endpoint
+    pub endpoint: Option<String>,
+    /// The namespace currently in use
+    pub namespace: Option<String>,
+    /// The database currently in use
+    pub database: Option<String>,
+    /// The level at which the connection is authenticated
+    pub auth: Option<&'static str>,
+}
+
+/// A connection which a session holds open under a name
+#[derive(Clone)]
+pub struct NamedConnection {
+    /// The SurrealDB client of the connection
+    pub db: Surreal<Any>,
+    /// The endpoint, namespace, and database of the connection
+    pub target: SessionTarget,
+}
+
+/// A named item returned by the list tools
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct NameResult {
+    pub name: String,
+}
+
+/// The result of the list_namespaces tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ListNamespacesResult {
+    pub namespaces: Vec<NameResult>,
+    pub count: usize,
+}
+
+/// The result of the list_databases tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ListDatabasesResult {
+    pub databases: Vec<NameResult>,
+    pub count: usize,
+}
+
+/// A connection profile returned by the list_profiles tool, without its credentials
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ProfileResult {
+    pub name: String,
+    pub description: Option<String>,
+    pub endpoint: String,
+    pub namespace: Option<String>,
+    pub database: Option<String>,
+}
+
+/// The result of the list_profiles tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ListProfilesResult {
+    pub profiles: Vec<ProfileResult>,
+    pub count: usize,
+}
+
+/// An open connection returned by the list_connections tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ConnectionResult {
+    pub name: String,
+    pub default: bool,
+    pub endpoint: Option<String>,
+    pub namespace: Option<String>,
+    pub database: Option<String>,
+    pub auth: Option<String>,
+    pub healthy: bool,
+}
+
+/// The result of the list_connections tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ListConnectionsResult {
+    pub connections: Vec<ConnectionResult>,
+    pub count: usize,
+}
+
+/// The result of the list_cloud_organizations tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ListCloudOrganizationsResult {
+    pub organizations: Vec<CloudOrganization>,
+    pub count: usize,
+}
+
+/// The result of the list_cloud_instances tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ListCloudInstancesResult {
+    pub instances: Vec<CloudInstance>,
+    pub count: usize,
+}
+
+/// The result of the cloud tools which modify an instance
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct CloudInstanceResult {
+    pub message: String,
+    pub instance: CloudInstance,
+}
+
+/// The result of the get_cloud_instance_status tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct CloudInstanceStatusResult {
+    pub instance_id: String,
+    pub phase: String,
+    pub db_backups: Vec<CloudInstanceBackup>,
+    pub backup_count: usize,
+}
+
+/// The result of the explain tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct ExplainResult {
+    /// The statement which was explained
+    pub query: String,
+    /// The execution plan of the statement
+    pub plan: serde_json::Value,
+    /// The time taken to explain the statement
+    pub duration: String,
+}
+
+/// The result of the live_query tool
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct LiveQueryResult {
+    /// The id of the live query
+    pub id: String,
+    /// The URI of the resource containing the live query notifications
+    pub uri: String,
+    /// The LIVE SELECT statement
+    pub query: String,
+}
+
 #[derive(Serialize, Deserialize)]
 struct ListNamespaces {
     namespaces: std::collections::HashMap<String, String>,
@@ -39,12 +234,41 @@ struct ListDatabases {
     databases: std::collections::HashMap<String, String>,
 }
 
+#[derive(Serialize, Deserialize)]
+struct DatabaseStructure {
+    tables: Vec<serde_json::Value>,
+    analyzers: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TableStructure {
+    fields: Vec<serde_json::Value>,
+    indexes: Vec<serde_json::Value>,
+    events: Vec<serde_json::Value>,
+}
+
 #[derive(Deserialize, schemars::JsonSchema)]
 pub struct QueryParams {
     #[schemars(description = "The SurrealQL query string")]
     pub query: String,
     #[schemars(description = "Optional parameters to bind to the query")]
     pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Whether to return only the result of the first statement, instead of the results of all statements (default: false)"
+    )]
+    pub first_result_only: Option<bool>,
+    #[schemars(
+        description = "Optional timeout for the query in seconds, which can shorten but not extend the server timeout (0 for no timeout, if the server has none)"
+    )]
+    pub timeout: Option<u64>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -65,6 +289,14 @@ pub struct SelectParams {
     pub start_clause: Option<String>,
     #[schemars(description = "Optional parameters to bind to the query.")]
     pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -77,6 +309,14 @@ pub struct InsertParams {
     pub relation: Option<bool>,
     #[schemars(description = "Array of JSON objects to be inserted as the record content.")]
     pub values: Vec<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -85,6 +325,14 @@ pub struct CreateParams {
     pub target: String,
     #[schemars(description = "The JSON data to be inserted as the record content.")]
     pub data: serde_json::Map<String, serde_json::Value>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -103,6 +351,18 @@ pub struct UpsertParams {
     pub where_clause: Option<String>,
     #[schemars(description = "Optional parameters to bind to the query.")]
     pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional flag to preview the affected records and their changes without persisting them."
+    )]
+    pub dry_run: Option<bool>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -121,6 +381,18 @@ pub struct UpdateParams {
     pub where_clause: Option<String>,
     #[schemars(description = "Optional parameters to bind to the query.")]
     pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional flag to preview the affected records and their changes without persisting them."
+    )]
+    pub dry_run: Option<bool>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -131,6 +403,18 @@ pub struct DeleteParams {
     pub where_clause: Option<String>,
     #[schemars(description = "Optional parameters to bind to the query.")]
     pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional flag to preview the affected records and their changes without persisting them."
+    )]
+    pub dry_run: Option<bool>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -145,6 +429,18 @@ pub struct RelateParams {
     pub content_data: Option<serde_json::Map<String, serde_json::Value>>,
     #[schemars(description = "Optional parameters to bind to the query.")]
     pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+    #[schemars(
+        description = "Optional flag to preview the affected records and their changes without persisting them."
+    )]
+    pub dry_run: Option<bool>,
+    #[schemars(
+        description = "Optional name of the connection to use, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -184,6 +480,70 @@ pub struct ConnectParams {
     pub username: Option<String>,
     #[schemars(description = "Password for authentication.")]
     pub password: Option<String>,
+    #[schemars(
+        description = "Optional name to open the connection under, alongside the default connection. Pass this name as the connection parameter of other tools to use it."
+    )]
+    pub connection: Option<String>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct ConnectProfileParams {
+    #[schemars(description = "The name of the connection profile, as returned by list_profiles.")]
+    pub name: String,
+    #[schemars(
+        description = "Optional name to open the connection under, alongside the default connection. Pass this name as the connection parameter of other tools to use it."
+    )]
+    pub connection: Option<String>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct DisconnectParams {
+    #[schemars(
+        description = "Optional name of the connection to close, as returned by list_connections (default: the default connection)."
+    )]
+    pub connection: Option<String>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct ExplainParams {
+    #[schemars(description = "The SurrealQL statement to explain")]
+    pub query: String,
+    #[schemars(description = "Optional parameters to bind to the statement")]
+    pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Whether to execute the statement and include row counts and timings in the plan (default: false)"
+    )]
+    pub full: Option<bool>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct LiveQueryParams {
+    #[schemars(description = "The LIVE SELECT statement to start")]
+    pub query: String,
+    #[schemars(description = "Optional parameters to bind to the statement")]
+    pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[schemars(
+        description = "Optional JSON encoding of parameters and results, either 'simple' or 'typed'."
+    )]
+    pub encoding: Option<JsonEncoding>,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct KillLiveQueryParams {
+    #[schemars(description = "The id of the live query to stop")]
+    pub id: String,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct NextPageParams {
+    #[schemars(description = "The cursor returned as next_cursor with a truncated result")]
+    pub cursor: String,
+}
+
+#[derive(Deserialize, schemars::JsonSchema)]
+pub struct TableParams {
+    #[schemars(description = "The name of the table to inspect.")]
+    pub table: String,
 }
 
 #[derive(Deserialize, schemars::JsonSchema)]
@@ -198,6 +558,66 @@ pub struct UseDatabaseParams {
     pub database: String,
 }
 
+/// Convert a tool result into a JSON value for use as structured content
+fn to_structured<T: Serialize>(value: T) -> Result<serde_json::Value, McpError> {
+    serde_json::to_value(value).map_err(|e| McpError::internal_error(e.to_string(), None))
+}
+
+/// Get the sorted names of the parameters bound to a query
+fn parameter_names(parameters: Option<&HashMap<String, Value>>) -> Vec<String> {
+    let mut names: Vec<String> = parameters
+        .into_iter()
+        .flat_map(|v| v.keys().cloned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Describe the destructive operation which a tool call would perform, if any
+///
+/// Destructive operations are deleting all records from a table, removing schema
+/// with a `REMOVE` statement, and pausing or creating SurrealDB Cloud instances.
+fn destructive_operation(
+    tool: &str,
+    arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Option<String> {
+    // Get a string argument of the tool call
+    let argument = |name: &str| arguments?.get(name)?.as_str();
+    match tool {
+        "query" => engine::check_destructive(argument("query")?)
+            .map(|v| format!("run a query containing {v}")),
+        "delete" => {
+            // Previews and conditional deletes are not destructive
+            if arguments?.get("dry_run").and_then(|v| v.as_bool()) == Some(true)
+                || argument("where_clause").is_some_and(|v| !v.trim().is_empty())
+            {
+                return None;
+            }
+            // Get the targets of the delete statement
+            let targets: Vec<String> = arguments?
+                .get("targets")?
+                .as_array()?
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+            let names = targets.join(", ");
+            let targets = parse_targets(targets).ok()?;
+            // Check whether the statement deletes whole tables
+            engine::check_destructive(&format!("DELETE {targets}"))
+                .map(|_| format!("delete all records from {names}"))
+        }
+        "pause_cloud_instance" => Some(format!(
+            "pause the SurrealDB Cloud instance '{}'",
+            argument("instance_id").unwrap_or_default()
+        )),
+        "create_cloud_instance" => Some(format!(
+            "create the SurrealDB Cloud instance '{}'",
+            argument("name").unwrap_or_default()
+        )),
+        _ => None,
+    }
+}
+
 #[derive(Clone)]
 pub struct SurrealService {
     /// The SurrealDB client instance to use for database operations
@@ -220,6 +640,44 @@ pub struct SurrealService {
     pub tool_router: ToolRouter<Self>,
     /// Cloud client for SurrealDB Cloud operations
     pub cloud_client: Arc<Client>,
+    /// Whether queries which modify data or schema are refused
+    pub read_only: bool,
+    /// The default JSON encoding of tool parameters and results
+    pub json_encoding: JsonEncoding,
+    /// Tools which have been removed from the tool router
+    pub disabled_tools: HashSet<String>,
+    /// The limits on the size of a single page of results
+    pub result_limits: ResultLimits,
+    /// The remaining rows of truncated results for this session
+    pub cursors: Arc<CursorStore>,
+    /// The default timeout for queries (no timeout if not set)
+    pub query_timeout: Option<Duration>,
+    /// The live queries running within this session
+    pub live_queries: Arc<LiveQueries>,
+    /// The transaction which is open within this session
+    pub transaction: Arc<SessionTransaction>,
+    /// The policy for confirming destructive operations
+    pub confirmation: ConfirmationPolicy,
+    /// The endpoint, namespace, and database currently in use
+    pub target: Arc<std::sync::Mutex<SessionTarget>>,
+    /// The audit log of executed queries (disabled if not set)
+    pub audit: Option<Arc<AuditLog>>,
+    /// The authenticated identity of the client, if any
+    pub identity: Arc<RwLock<Option<Identity>>>,
+    /// The bearer token the client authenticated with, if any
+    pub token: Arc<RwLock<Option<String>>>,
+    /// How connections authenticate with SurrealDB
+    pub signin: SigninConfig,
+    /// The endpoints, namespaces, and databases which can be used
+    pub connections: ConnectionsConfig,
+    /// The named connection profiles which can be connected to
+    pub profiles: Arc<BTreeMap<String, ProfileConfig>>,
+    /// The connections held open alongside the default connection
+    pub named_connections: Arc<Mutex<BTreeMap<String, NamedConnection>>>,
+    /// The pool of connections to the configured endpoint shared between sessions
+    pub pool: Option<Arc<ConnectionPool>>,
+    /// The lease on the pooled connection used by the default connection, if any
+    pub pool_lease: Arc<std::sync::Mutex<Option<PoolLease>>>,
 }
 
 #[tool_router]
@@ -274,7 +732,180 @@ impl SurrealService {
             connected_at: Instant::now(),
             tool_router: Self::tool_router(),
             cloud_client,
+            read_only: false,
+            json_encoding: JsonEncoding::default(),
+            disabled_tools: HashSet::new(),
+            result_limits: ResultLimits::default(),
+            cursors: Arc::new(CursorStore::default()),
+            query_timeout: None,
+            live_queries: Arc::new(LiveQueries::default()),
+            transaction: Arc::new(SessionTransaction::default()),
+            confirmation: ConfirmationPolicy::default(),
+            target: Arc::new(std::sync::Mutex::new(SessionTarget::default())),
+            audit: None,
+            identity: Arc::new(RwLock::new(None)),
+            token: Arc::new(RwLock::new(None)),
+            signin: SigninConfig::default(),
+            connections: ConnectionsConfig::default(),
+            profiles: Arc::new(BTreeMap::new()),
+            named_connections: Arc::new(Mutex::new(BTreeMap::new())),
+            pool: None,
+            pool_lease: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    /// Configure whether this session is restricted to read-only operations
+    ///
+    /// When enabled, tools which modify data or cloud resources are removed from
+    /// the tool router, and any query which modifies data or schema is refused.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        if read_only {
+            for name in MUTATING_TOOLS {
+                self.disable_tool(name);
+            }
+        }
+        self.read_only = read_only;
+        self
+    }
+
+    /// Configure the default JSON encoding of tool parameters and results
+    ///
+    /// This can be overridden on each tool call using the `encoding` parameter.
+    pub fn with_json_encoding(mut self, json_encoding: JsonEncoding) -> Self {
+        self.json_encoding = json_encoding;
+        self
+    }
+
+    /// Configure the limits on the size of a single page of results
+    ///
+    /// Results which exceed these limits are truncated, and a cursor is returned
+    /// which can be passed to the `next_page` tool to fetch the remaining rows.
+    pub fn with_result_limits(mut self, result_limits: ResultLimits) -> Self {
+        self.result_limits = result_limits;
+        self
+    }
+
+    /// Configure the default timeout for queries
+    ///
+    /// Queries which do not complete within the timeout are abandoned, releasing
+    /// the database connection. This can be shortened on the `query` tool using
+    /// the `timeout` parameter, but not extended or disabled.
+    pub fn with_query_timeout(mut self, query_timeout: Option<Duration>) -> Self {
+        self.query_timeout = query_timeout;
+        self
+    }
+
+    /// Configure the maximum number of concurrent live queries in this session
+    pub fn with_max_live_queries(mut self, max_live_queries: usize) -> Self {
+        self.live_queries = Arc::new(LiveQueries::new(max_live_queries));
+        self
+    }
+
+    /// Configure the time after which an idle transaction is rolled back
+    ///
+    /// Transactions which are not used within the timeout are considered to be
+    /// abandoned, and are rolled back automatically (never if not set).
+    pub fn with_transaction_timeout(mut self, transaction_timeout: Option<Duration>) -> Self {
+        self.transaction = Arc::new(SessionTransaction::new(transaction_timeout));
+        self
+    }
+
+    /// Configure the policy for confirming destructive operations
+    ///
+    /// Destructive operations include deleting all records from a table, removing
+    /// schema, and pausing or creating SurrealDB Cloud instances.
+    pub fn with_confirmation_policy(mut self, confirmation: ConfirmationPolicy) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// Configure the audit log which every executed query is recorded in
+    pub fn with_audit_log(mut self, audit: Option<Arc<AuditLog>>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Configure the endpoints, namespaces, and databases which can be used
+    pub fn with_connections_config(mut self, connections: ConnectionsConfig) -> Self {
+        self.connections = connections;
+        self
+    }
+
+    /// Configure the named connection profiles which can be connected to
+    pub fn with_profiles(mut self, profiles: Arc<BTreeMap<String, ProfileConfig>>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Configure the pool of connections shared between client sessions
+    ///
+    /// When set, the connection to the configured endpoint is a separate session
+    /// on a pooled connection, instead of a new connection for each session.
+    pub fn with_connection_pool(mut self, pool: Option<Arc<ConnectionPool>>) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Configure how connections authenticate with SurrealDB
+    pub fn with_signin(mut self, signin: SigninConfig) -> Self {
+        self.signin = signin;
+        self
+    }
+
+    /// Get the authenticated identity of the client, if any
+    pub async fn identity(&self) -> Option<Identity> {
+        self.identity.read().await.clone()
+    }
+
+    /// Get the options for returning results with the given JSON encoding
+    fn output_options(&self, encoding: JsonEncoding) -> OutputOptions {
+        OutputOptions {
+            encoding,
+            limits: self.result_limits,
+            cursors: self.cursors.clone(),
+        }
+    }
+
+    /// Configure which tools are exposed by this session
+    ///
+    /// Tools which are not enabled by the allow- and deny-lists are removed from
+    /// the tool router, and any attempt to call them is refused with an error.
+    pub fn with_tools_config(mut self, tools: &ToolsConfig) -> Self {
+        // Collect the names of all currently registered tools
+        let names: Vec<String> = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        // Remove any tools which are not enabled
+        for name in names {
+            if !tools.is_enabled(&name) {
+                self.disable_tool(&name);
+            }
+        }
+        self
+    }
+
+    /// Ensure that the allow- and deny-lists only reference existing tools
+    pub fn check_tools_config(tools: &ToolsConfig) -> Result<(), anyhow::Error> {
+        // Get the router containing all available tools
+        let router = Self::tool_router();
+        // Check each referenced tool name
+        for name in tools.names() {
+            if !router.has_route(name) {
+                return Err(anyhow::anyhow!(
+                    "Unknown tool '{name}' in tool allow- or deny-list"
+                ));
+            }
         }
+        Ok(())
+    }
+
+    /// Remove a tool from the tool router, and mark it as disabled
+    fn disable_tool(&mut self, name: &str) {
+        self.tool_router.remove_route(name);
+        self.disabled_tools.insert(name.to_string());
     }
 
     /// Execute a raw SurrealQL query against the database.
@@ -298,7 +929,9 @@ For security, you can use parameterized queries to prevent SQL injection by prov
 parameters that will be safely bound to the query. Use $param_name syntax in your query 
 and provide the parameters in the parameters field.
 
-The query results are returned as text, or an error occurs if the query execution fails.
+The results of all statements are returned as an array of objects, each containing the
+statement_index, status (OK or ERR), time, and either the result or the error of that
+statement. Set first_result_only to true to return only the result of the first statement.
 
 Examples:
 - SELECT * FROM person
@@ -317,7 +950,13 @@ Parameterized query examples:
         let QueryParams {
             query: query_string,
             parameters,
+            first_result_only,
+            timeout,
+            encoding,
+            connection,
         } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.query").increment(1);
         // Output debugging information
@@ -326,7 +965,8 @@ Parameterized query examples:
         let parameters = if let Some(params) = parameters {
             let mut converted = HashMap::new();
             for (key, val) in params {
-                let surreal_val = convert_json_to_surreal(val, &key)
+                let surreal_val = encoding
+                    .to_surreal(val, &key)
                     .map_err(|e| McpError::internal_error(e, None))?;
                 converted.insert(key, surreal_val);
             }
@@ -334,58 +974,539 @@ Parameterized query examples:
         } else {
             None
         };
+        // Get the timeout for this call, which can not exceed the server timeout
+        let timeout = match (timeout, self.query_timeout) {
+            (Some(0), Some(_)) => {
+                return Err(McpError::invalid_params(
+                    "The query timeout can not be disabled as the server has a query timeout",
+                    None,
+                ));
+            }
+            (Some(0), None) => None,
+            (Some(secs), Some(max)) => Some(Duration::from_secs(secs).min(max)),
+            (Some(secs), None) => Some(Duration::from_secs(secs)),
+            (None, max) => max,
+        };
         // Use the internal query function
-        self.query_internal(query_string, parameters)
-            .await?
-            .into_mcp_result()
+        let response = self
+            .query_with_timeout(connection.as_deref(), query_string, parameters, timeout)
+            .await?;
+        // Return the first result, or the results of all statements
+        if first_result_only.unwrap_or(false) {
+            response.into_mcp_result(&self.output_options(encoding))
+        } else {
+            response.into_mcp_results(&self.output_options(encoding))
+        }
     }
 
-    /// Execute a SurrealDB SELECT statement to retrieve records from the database.
+    /// Explain the execution plan of a SurrealQL statement.
     ///
-    /// This function executes a SurrealDB SELECT statement to query records from
-    /// the specified tables or retrieve specific records by ID. Each item in the from
-    /// parameter can be either a table name to select all records from that table, or a
-    /// specific record ID in the format "table:id" to select a single record.
-    /// The query results are returned as text, or an error occurs if the query
-    /// execution fails.
+    /// This function runs the statement with SurrealDB's `EXPLAIN` clause, or the
+    /// `EXPLAIN FULL` clause when `full` is set, and returns the execution plan as
+    /// structured JSON along with the time taken to produce the plan.
+    ///
+    /// # Arguments
+    /// * `query` - The SurrealQL statement to explain
+    /// * `parameters` - Optional parameters to bind to the statement
+    /// * `full` - Whether to execute the statement and include its metrics
     #[tool(description = r#"
-Execute a SurrealDB SELECT statement to retrieve records from the database.
+Explain the execution plan of a single SurrealQL statement, to find out why it is slow.
 
-This function executes a SurrealDB SELECT statement to query records from the specified 
-tables or record IDs. Each item in the what parameter is parsed to determine if it's a 
-table name or a record ID. You can optionally add various clauses to filter, group, sort, 
-and paginate the results.
+The plan shows the operators which would be used to execute the statement, including which
+indexes are used (IndexScan), which tables are scanned in full (TableScan), and the iterators
+and filters which are applied. Set full to true to execute the statement and include the
+number of rows and the elapsed time of each operator. Statements which modify data can not
+be explained with full set to true. The time taken to produce the plan is also returned.
 
 Examples:
-- select(["person"])  # All records from person table
-- select(["person:john"])  # Specific record
-- select(["person", "article"])  # All records from both tables
-- select(["person:john", "article:123"])  # Specific records
-- select(["person"], Some("age > 25"), None, None, Some("name ASC"), Some("10"), None)  # Filtered and sorted
-- select(["person"], Some("age > $min_age"), None, Some("city"), Some("age DESC"), Some("10"), Some("20"), Some({"min_age": 25}))  # With parameters
-- select(["article"], Some("published = true"), Some("author"), None, Some("created_at DESC"), Some("5"), None)  # With split and pagination
-- select(["person"], Some("age > $min_age AND name CONTAINS $name_filter"), None, None, None, Some("10"), None, Some({ "min_age": 25, "name_filter": "John" }))  # Complex parameterized query
-"#)]
-    pub async fn select(
+- SELECT * FROM person WHERE email = 'john@example.com'
+- SELECT * FROM article WHERE author = $author ORDER BY created_at DESC LIMIT 10
+"#,
+        output_schema = schema_for_type::<ExplainResult>()
+    )]
+    pub async fn explain(
         &self,
-        params: Parameters<SelectParams>,
+        params: Parameters<ExplainParams>,
     ) -> Result<CallToolResult, McpError> {
-        let SelectParams {
-            targets,
-            where_clause,
-            split_clause,
-            group_clause,
-            order_clause,
-            limit_clause,
-            start_clause,
+        let ExplainParams {
+            query: query_string,
             parameters,
+            full,
         } = params.0;
         // Increment tool usage counter
-        counter!("surrealmcp.tools.select").increment(1);
+        counter!("surrealmcp.tools.explain").increment(1);
         // Output debugging information
-        debug!(targets = ?targets, "Selecting records");
-        // Build the initial query string
-        let mut query = "SELECT * FROM ".to_string();
+        debug!(query_string = %query_string, "Explaining SurrealQL query");
+        // Build the query which explains the statement
+        let query = engine::explain_query(&query_string, full.unwrap_or(false))
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        // Convert tool parameters to SurrealQL parameters
+        let parameters = if let Some(params) = parameters {
+            let mut converted = HashMap::new();
+            for (key, val) in params {
+                let surreal_val = self
+                    .json_encoding
+                    .to_surreal(val, &key)
+                    .map_err(|e| McpError::internal_error(e, None))?;
+                converted.insert(key, surreal_val);
+            }
+            Some(converted)
+        } else {
+            None
+        };
+        // Execute the query which explains the statement
+        let mut response = self.query_internal(query.clone(), parameters).await?;
+        // Get the duration of the query
+        let duration = utils::format_duration(response.duration);
+        // Match the result of the query
+        match response.result.as_mut() {
+            Some(res) => {
+                // Take the execution plan from the response
+                let plan: Value = res
+                    .take(0)
+                    .map_err(|e: surrealdb::Error| McpError::internal_error(e.to_string(), None))?;
+                // Create the result JSON
+                let result = to_structured(ExplainResult {
+                    query,
+                    plan: self.json_encoding.to_json(plan),
+                    duration,
+                })?;
+                // Return the result
+                Ok(CallToolResult {
+                    content: vec![Content::text(
+                        serde_json::to_string_pretty(&result).unwrap_or_default(),
+                    )],
+                    is_error: None,
+                    meta: None,
+                    structured_content: Some(result),
+                })
+            }
+            None => {
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.explain").increment(1);
+                // Return error message
+                let err = response
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                Err(McpError::internal_error(err, None))
+            }
+        }
+    }
+
+    /// Start a live query which streams changes to records.
+    ///
+    /// This function starts a SurrealDB `LIVE SELECT` statement, and exposes its
+    /// change notifications as a subscribable resource at `surrealmcp://live/{id}`.
+    /// The number of concurrent live queries in each session is limited.
+    ///
+    /// # Arguments
+    /// * `query` - The LIVE SELECT statement to start
+    /// * `parameters` - Optional parameters to bind to the statement
+    /// * `encoding` - Optional JSON encoding of parameters and notifications
+    #[tool(description = r#"
+Start a live query which streams changes to records as they are created, updated, or deleted.
+
+The query must be a single LIVE SELECT statement. The changes are exposed as a resource at the
+returned uri (surrealmcp://live/{id}). Subscribe to the resource to receive a
+notifications/resources/updated notification whenever a change occurs, and read the resource to
+retrieve the changes since it was last read. Each change contains the action (CREATE, UPDATE,
+DELETE, or KILLED) and the record data. Use kill_live_query to stop the live query when it is
+no longer needed, as the number of concurrent live queries is limited.
+
+Examples:
+- LIVE SELECT * FROM person
+- LIVE SELECT * FROM person WHERE age > $min_age
+- LIVE SELECT DIFF FROM article
+"#,
+        output_schema = schema_for_type::<LiveQueryResult>()
+    )]
+    pub async fn live_query(
+        &self,
+        params: Parameters<LiveQueryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let LiveQueryParams {
+            query: query_string,
+            parameters,
+            encoding,
+        } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.live_query").increment(1);
+        // Output debugging information
+        debug!(query_string = %query_string, "Starting live query");
+        // Ensure the query is a single LIVE SELECT statement
+        engine::check_live_query(&query_string).map_err(|e| McpError::invalid_params(e, None))?;
+        // Ensure another live query can be started
+        self.live_queries
+            .check_capacity()
+            .map_err(|e| McpError::invalid_request(e, None))?;
+        // Convert tool parameters to SurrealQL parameters
+        let parameters = if let Some(params) = parameters {
+            let mut converted = HashMap::new();
+            for (key, val) in params {
+                let surreal_val = encoding
+                    .to_surreal(val, &key)
+                    .map_err(|e| McpError::internal_error(e, None))?;
+                converted.insert(key, surreal_val);
+            }
+            Some(converted)
+        } else {
+            None
+        };
+        // Execute the LIVE SELECT statement
+        let mut response = self
+            .query_internal(query_string.clone(), parameters)
+            .await?;
+        // Get the stream of live query notifications
+        let stream = match response.result.as_mut() {
+            Some(res) => res
+                .stream::<Value>(0)
+                .map_err(|e| McpError::internal_error(e.to_string(), None)),
+            None => Err(McpError::internal_error(
+                response
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string()),
+                None,
+            )),
+        };
+        // Start receiving live query notifications
+        let id = stream.and_then(|stream| {
+            self.live_queries
+                .start(query_string.clone(), stream, encoding)
+                .map_err(|e| McpError::invalid_request(e, None))
+        });
+        let id = match id {
+            Ok(id) => id,
+            Err(e) => {
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.live_query").increment(1);
+                // Return error message
+                return Err(e);
+            }
+        };
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            live_query_id = %id,
+            "Successfully started live query"
+        );
+        // Create the result JSON
+        let result = to_structured(LiveQueryResult {
+            uri: live::live_uri(&id),
+            id,
+            query: query_string,
+        })?;
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// Stop a live query started with the live_query tool.
+    ///
+    /// This function kills the live query on the database, and removes the
+    /// corresponding `surrealmcp://live/{id}` resource.
+    ///
+    /// # Arguments
+    /// * `id` - The id of the live query to stop
+    #[tool(description = r#"
+Stop a live query which was started with the live_query tool, and remove its resource.
+"#)]
+    pub async fn kill_live_query(
+        &self,
+        params: Parameters<KillLiveQueryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let KillLiveQueryParams { id } = params.0;
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.kill_live_query").increment(1);
+        // Output debugging information
+        debug!(live_query_id = %id, "Stopping live query");
+        // Stop the live query
+        if !self.live_queries.stop(&id) {
+            return Err(McpError::invalid_params(
+                format!("Live query '{id}' does not exist"),
+                None,
+            ));
+        }
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            live_query_id = %id,
+            "Successfully stopped live query"
+        );
+        // Return success message
+        Ok(CallToolResult {
+            content: vec![Content::text(format!(
+                "Successfully stopped live query '{id}'"
+            ))],
+            is_error: None,
+            meta: None,
+            structured_content: None,
+        })
+    }
+
+    /// Begin a transaction which spans subsequent tool calls.
+    ///
+    /// This function opens a transaction on the database. All queries executed by
+    /// subsequent tool calls in this session run within the transaction until it is
+    /// committed with `commit_transaction` or discarded with `rollback_transaction`.
+    /// Transactions which are not used within the transaction timeout are rolled
+    /// back automatically.
+    #[tool(description = r#"
+Begin a transaction which spans subsequent tool calls.
+
+All queries executed by subsequent tool calls (query, select, insert, create, upsert, update,
+delete, relate) run within the transaction, and see the changes made earlier in the
+transaction. Changes are only visible to other sessions once the transaction is committed with
+commit_transaction. Use rollback_transaction to discard all changes, for example if a later
+step fails. Only one transaction can be open at a time, and transactions which are not used
+for a while are rolled back automatically.
+
+Do not use BEGIN, COMMIT, or CANCEL statements in queries while a transaction is open.
+"#,
+        output_schema = schema_for_type::<TransactionResult>()
+    )]
+    pub async fn begin_transaction(
+        &self,
+        _params: Parameters<CloudParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.begin_transaction").increment(1);
+        // Output debugging information
+        debug!(connection_id = %self.connection_id, "Beginning transaction");
+        // Get a session for the connected database
+        let db = self.session().await?;
+        // Begin the transaction
+        let result = self.transaction.begin(&db).await.map_err(|e| {
+            // Update the transaction errors metric
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.begin_transaction").increment(1);
+            // Return error message
+            McpError::invalid_request(e, None)
+        })?;
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            transaction_id = %result.id,
+            "Successfully began transaction"
+        );
+        // Create the result JSON
+        let result = to_structured(result)?;
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// Commit the open transaction.
+    ///
+    /// This function commits all changes made within the open transaction, making
+    /// them visible to other sessions. If the commit fails, all of the changes made
+    /// within the transaction are discarded.
+    #[tool(description = r#"
+Commit the transaction which was opened with begin_transaction.
+
+All changes made within the transaction are applied atomically. If the commit fails, none of
+the changes are applied.
+"#,
+        output_schema = schema_for_type::<TransactionResult>()
+    )]
+    pub async fn commit_transaction(
+        &self,
+        _params: Parameters<CloudParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.commit_transaction").increment(1);
+        // Output debugging information
+        debug!(connection_id = %self.connection_id, "Committing transaction");
+        // Commit the transaction
+        let result = self.transaction.commit().await.map_err(|e| {
+            // Update the transaction errors metric
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.commit_transaction").increment(1);
+            // Return error message
+            McpError::invalid_request(e, None)
+        })?;
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            transaction_id = %result.id,
+            statements = result.statements,
+            "Successfully committed transaction"
+        );
+        // Create the result JSON
+        let result = to_structured(result)?;
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// Roll back the open transaction.
+    ///
+    /// This function discards all changes made within the open transaction.
+    #[tool(description = r#"
+Roll back the transaction which was opened with begin_transaction, discarding all of its changes.
+"#,
+        output_schema = schema_for_type::<TransactionResult>()
+    )]
+    pub async fn rollback_transaction(
+        &self,
+        _params: Parameters<CloudParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.rollback_transaction").increment(1);
+        // Output debugging information
+        debug!(connection_id = %self.connection_id, "Rolling back transaction");
+        // Roll back the transaction
+        let result = self.transaction.rollback().await.map_err(|e| {
+            // Update the transaction errors metric
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.rollback_transaction").increment(1);
+            // Return error message
+            McpError::invalid_request(e, None)
+        })?;
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            transaction_id = %result.id,
+            statements = result.statements,
+            "Successfully rolled back transaction"
+        );
+        // Create the result JSON
+        let result = to_structured(result)?;
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// Fetch the next page of rows from a truncated result.
+    ///
+    /// This function returns the next page of rows for a cursor which was returned
+    /// as `next_cursor` with a truncated result. Each cursor can only be used once,
+    /// and expires if it is not used within 10 minutes.
+    ///
+    /// # Arguments
+    /// * `cursor` - The cursor returned with the truncated result
+    #[tool(description = r#"
+Fetch the next page of rows from a truncated result.
+
+Results which exceed the server's row or size limits are truncated to a single page, and
+returned along with a next_cursor and the number of remaining_rows. Pass the next_cursor
+to this tool to fetch the next page. Each cursor can only be used once, and a new
+next_cursor is returned while rows remain. Cursors expire after 10 minutes.
+"#)]
+    pub async fn next_page(
+        &self,
+        params: Parameters<NextPageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let NextPageParams { cursor } = params.0;
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.next_page").increment(1);
+        // Output debugging information
+        debug!(cursor = %cursor, "Fetching next page of results");
+        // Take the next page of rows for the cursor
+        match engine::next_page(&cursor, &self.output_options(self.json_encoding)) {
+            Ok(page) => {
+                // Convert the page into a JSON object
+                let result = page.into_json();
+                // Return the result
+                Ok(CallToolResult {
+                    content: vec![Content::text(
+                        serde_json::to_string_pretty(&result).unwrap_or_default(),
+                    )],
+                    is_error: None,
+                    meta: None,
+                    structured_content: Some(result),
+                })
+            }
+            Err(e) => {
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.next_page").increment(1);
+                // Return error message
+                Err(McpError::invalid_params(
+                    e,
+                    Some(serde_json::json!({ "cursor": cursor })),
+                ))
+            }
+        }
+    }
+
+    /// Execute a SurrealDB SELECT statement to retrieve records from the database.
+    ///
+    /// This function executes a SurrealDB SELECT statement to query records from
+    /// the specified tables or retrieve specific records by ID. Each item in the from
+    /// parameter can be either a table name to select all records from that table, or a
+    /// specific record ID in the format "table:id" to select a single record.
+    /// The query results are returned as text, or an error occurs if the query
+    /// execution fails.
+    #[tool(description = r#"
+Execute a SurrealDB SELECT statement to retrieve records from the database.
+
+This function executes a SurrealDB SELECT statement to query records from the specified 
+tables or record IDs. Each item in the what parameter is parsed to determine if it's a 
+table name or a record ID. You can optionally add various clauses to filter, group, sort, 
+and paginate the results.
+
+Examples:
+- select(["person"])  # All records from person table
+- select(["person:john"])  # Specific record
+- select(["person", "article"])  # All records from both tables
+- select(["person:john", "article:123"])  # Specific records
+- select(["person"], Some("age > 25"), None, None, Some("name ASC"), Some("10"), None)  # Filtered and sorted
+- select(["person"], Some("age > $min_age"), None, Some("city"), Some("age DESC"), Some("10"), Some("20"), Some({"min_age": 25}))  # With parameters
+- select(["article"], Some("published = true"), Some("author"), None, Some("created_at DESC"), Some("5"), None)  # With split and pagination
+- select(["person"], Some("age > $min_age AND name CONTAINS $name_filter"), None, None, None, Some("10"), None, Some({ "min_age": 25, "name_filter": "John" }))  # Complex parameterized query
+"#)]
+    pub async fn select(
+        &self,
+        params: Parameters<SelectParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let SelectParams {
+            targets,
+            where_clause,
+            split_clause,
+            group_clause,
+            order_clause,
+            limit_clause,
+            start_clause,
+            parameters,
+            encoding,
+            connection,
+        } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.select").increment(1);
+        // Output debugging information
+        debug!(targets = ?targets, "Selecting records");
+        // Build the initial query string
+        let mut query = "SELECT * FROM ".to_string();
         // Process the tables and Record IDs
         query.push_str(&parse_targets(targets).map_err(|e| McpError::internal_error(e, None))?);
 
@@ -437,7 +1558,8 @@ Examples:
         // Add user-provided parameters if any
         if let Some(variables) = parameters {
             for (key, val) in variables {
-                let val = convert_json_to_surreal(val, &key)
+                let val = encoding
+                    .to_surreal(val, &key)
                     .map_err(|e| McpError::internal_error(e, None))?;
                 params.insert(key, val);
             }
@@ -445,9 +1567,9 @@ Examples:
         // Output debugging information
         trace!("Selecting records with query: {query}");
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
     /// Insert new records into the specified tables or with specific record IDs.
@@ -481,7 +1603,11 @@ Examples:
             values,
             ignore,
             relation,
+            encoding,
+            connection,
         } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.insert").increment(1);
         // Output debugging information
@@ -506,15 +1632,16 @@ Examples:
         // Add the record data
         let values_array: Vec<serde_json::Value> =
             values.into_iter().map(serde_json::Value::Object).collect();
-        let data = convert_json_to_surreal(serde_json::Value::Array(values_array), "data")
+        let data = encoding
+            .to_surreal(serde_json::Value::Array(values_array), "data")
             .map_err(|e| McpError::internal_error(e, None))?;
         params.insert("data".to_string(), data);
         // Output debugging information
         trace!("Inserting records with query: {query}");
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
     /// Create a new record in the specified table with the provided data.
@@ -536,7 +1663,14 @@ This is useful for creating users, articles, products, or any other entity in yo
         &self,
         params: Parameters<CreateParams>,
     ) -> Result<CallToolResult, McpError> {
-        let CreateParams { target, data } = params.0;
+        let CreateParams {
+            target,
+            data,
+            encoding,
+            connection,
+        } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.create").increment(1);
         // Output debugging information
@@ -550,15 +1684,16 @@ This is useful for creating users, articles, products, or any other entity in yo
         // Create parameters with native SurrealDB types
         let mut params = HashMap::new();
         // Add the record data
-        let data =
-            convert_json_to_surreal(data, "data").map_err(|e| McpError::internal_error(e, None))?;
+        let data = encoding
+            .to_surreal(data, "data")
+            .map_err(|e| McpError::internal_error(e, None))?;
         params.insert("data".to_string(), data);
         // Output debugging information
         trace!("Creating records with query: {query}");
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
     /// Execute a SurrealDB UPSERT statement to create or update records in the database.
@@ -574,6 +1709,9 @@ This function executes a SurrealDB UPSERT statement to create new records or upd
 existing ones. The UPSERT statement combines the functionality of CREATE and UPDATE, 
 inserting a new record if it doesn't exist, or updating an existing record if it does.
 
+Set dry_run to true to preview the affected records, with their contents before and after the
+change and the differences between them, without persisting any changes.
+
 Examples:
 - upsert(["person:john"], {"name": "John", "age": 30})  # Creates or updates specific record
 - upsert(["person"], {"name": "Jane", "age": 25}, Some("age > 18"))  # Upserts with condition
@@ -591,7 +1729,12 @@ Examples:
             content_data,
             where_clause,
             parameters,
+            encoding,
+            dry_run,
+            connection,
         } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.upsert").increment(1);
         // Output debugging information
@@ -609,7 +1752,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -618,7 +1762,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -627,7 +1772,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -636,7 +1782,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -657,17 +1804,25 @@ Examples:
         // Add user-provided parameters if any
         if let Some(variables) = parameters {
             for (key, val) in variables {
-                let val = convert_json_to_surreal(val, &key)
+                let val = encoding
+                    .to_surreal(val, &key)
                     .map_err(|e| McpError::internal_error(e, None))?;
                 params.insert(key, val);
             }
         }
         // Output debugging information
         trace!("Upserting records with query: {query}");
+        // Preview the changes without persisting them if requested
+        if dry_run.unwrap_or(false) {
+            return self
+                .dry_run_internal(connection.as_deref(), query, Some(params))
+                .await?
+                .into_mcp_result(&self.output_options(encoding));
+        }
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
     /// Execute a SurrealDB UPDATE statement to modify records in the database.
@@ -684,6 +1839,9 @@ This function executes a SurrealDB UPDATE statement to modify the content of rec
 in the database. The what parameter accepts an array where each item can be either a 
 table name or a specific record ID, similar to the select function.
 
+Set dry_run to true to preview the affected records, with their contents before and after the
+change and the differences between them, without persisting any changes.
+
 Examples:
 - update(["person"], {"age": 31})  # Updates all records in person table
 - update(["person:john"], {"age": 31})  # Updates specific record
@@ -704,7 +1862,12 @@ Examples:
             replace_data,
             where_clause,
             parameters,
+            encoding,
+            dry_run,
+            connection,
         } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.update").increment(1);
         // Output debugging information
@@ -722,7 +1885,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -731,7 +1895,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -740,7 +1905,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -749,7 +1915,8 @@ Examples:
                 // Add the data input as a parameter
                 params.insert(
                     "data".to_string(),
-                    convert_json_to_surreal(v, "data")
+                    encoding
+                        .to_surreal(v, "data")
                         .map_err(|e| McpError::internal_error(e, None))?,
                 );
             }
@@ -770,17 +1937,25 @@ Examples:
         // Add user-provided parameters if any
         if let Some(variables) = parameters {
             for (key, val) in variables {
-                let val = convert_json_to_surreal(val, &key)
+                let val = encoding
+                    .to_surreal(val, &key)
                     .map_err(|e| McpError::internal_error(e, None))?;
                 params.insert(key, val);
             }
         }
         // Output debugging information
         trace!("Updating records with query: {query}");
+        // Preview the changes without persisting them if requested
+        if dry_run.unwrap_or(false) {
+            return self
+                .dry_run_internal(connection.as_deref(), query, Some(params))
+                .await?
+                .into_mcp_result(&self.output_options(encoding));
+        }
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
     /// Execute a SurrealDB DELETE statement to remove records from the database.
@@ -798,6 +1973,9 @@ This function executes a SurrealDB DELETE statement to remove records from the
 specified tables or specific record IDs. The what parameter accepts an array where 
 each item can be either a table name or a specific record ID, similar to the select function.
 
+Set dry_run to true to preview the affected records, with their contents before and after the
+change and the differences between them, without persisting any changes.
+
 Examples:
 - delete(["person"])  # Deletes all records from person table
 - delete(["person:john"])  # Deletes specific record
@@ -815,7 +1993,12 @@ Examples:
             targets,
             where_clause,
             parameters,
+            encoding,
+            dry_run,
+            connection,
         } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.delete").increment(1);
         // Output debugging information
@@ -839,17 +2022,25 @@ Examples:
         // Add user-provided parameters if any
         if let Some(variables) = parameters {
             for (key, val) in variables {
-                let val = convert_json_to_surreal(val, &key)
+                let val = encoding
+                    .to_surreal(val, &key)
                     .map_err(|e| McpError::internal_error(e, None))?;
                 params.insert(key, val);
             }
         }
         // Output debugging information
         trace!("Deleting records with query: {query}");
+        // Preview the changes without persisting them if requested
+        if dry_run.unwrap_or(false) {
+            return self
+                .dry_run_internal(connection.as_deref(), query, Some(params))
+                .await?
+                .into_mcp_result(&self.output_options(encoding));
+        }
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
     /// Create a relationship between two records in the database.
@@ -869,6 +2060,9 @@ Optionally, you can provide content data to store on the relationship edge itsel
 This is essential for graph operations and modeling complex relationships like social 
 networks, content authorship, ownership, etc.
 
+Set dry_run to true to preview the affected records, with their contents before and after the
+change and the differences between them, without persisting any changes.
+
 Examples:
 - relate(['person:john'], 'wrote', ['article:surreal_guide'], None) # Relates person:john to article:surreal_guide, with no edge record data
 - relate(['person:john', 'person:jill'], 'knows', ['person:jane'], {"since": "2020-01-01", "strength": "close"}) # Relates multiple records to a single record, with some edge record data
@@ -885,7 +2079,12 @@ Examples:
             table,
             content_data,
             parameters,
+            encoding,
+            dry_run,
+            connection,
         } = params.0;
+        // Get the JSON encoding for this call
+        let encoding = encoding.unwrap_or(self.json_encoding);
         // Increment tool usage counter
         counter!("surrealmcp.tools.relate").increment(1);
         // Output debugging information
@@ -914,27 +2113,39 @@ Examples:
             // Add the data input as a parameter
             params.insert(
                 "data".to_string(),
-                convert_json_to_surreal(v, "data")
+                encoding
+                    .to_surreal(v, "data")
                     .map_err(|e| McpError::internal_error(e, None))?,
             );
         }
         // Add user-provided parameters if any
         if let Some(variables) = parameters {
             for (key, val) in variables {
-                let val = convert_json_to_surreal(val, &key)
+                let val = encoding
+                    .to_surreal(val, &key)
                     .map_err(|e| McpError::internal_error(e, None))?;
                 params.insert(key, val);
             }
         }
         // Output debugging information
         trace!("Relating records with query: {query}");
+        // Preview the changes without persisting them if requested
+        if dry_run.unwrap_or(false) {
+            return self
+                .dry_run_internal(connection.as_deref(), query, Some(params))
+                .await?
+                .into_mcp_result(&self.output_options(encoding));
+        }
         // Execute the final query
-        self.query_internal(query, Some(params))
+        self.query_on(connection.as_deref(), query, Some(params))
             .await?
-            .into_mcp_result()
+            .into_mcp_result(&self.output_options(encoding))
     }
 
-    #[tool(description = "List SurrealDB Cloud organizations")]
+    #[tool(
+        description = "List SurrealDB Cloud organizations",
+        output_schema = schema_for_type::<ListCloudOrganizationsResult>()
+    )]
     pub async fn list_cloud_organizations(
         &self,
         _params: Parameters<CloudParams>,
@@ -949,38 +2160,24 @@ Examples:
             .list_organizations()
             .await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
-        // Convert result to JSON
-        let organisations: Vec<serde_json::Value> = organisations
-            .into_iter()
-            .map(|org| {
-                serde_json::json!({
-                    "id": org.id,
-                    "name": org.name,
-                    "billing_info": org.billing_info,
-                    "payment_info": org.payment_info,
-                    "max_free_instances": org.max_free_instances,
-                    "max_paid_instances": org.max_paid_instances,
-                    "member_count": org.member_count,
-                    "user_role": org.user_role,
-                    "plan": org.plan,
-                })
-            })
-            .collect();
         // Create the result JSON
-        let result = serde_json::json!({
-            "organizations": organisations,
-            "count": organisations.len()
-        });
+        let result = to_structured(ListCloudOrganizationsResult {
+            count: organisations.len(),
+            organizations: organisations,
+        })?;
         // Return the MCP result
         Ok(CallToolResult {
             content: vec![Content::text(result.to_string())],
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(result),
         })
     }
 
-    #[tool(description = "List SurrealDB Cloud instances for a given organization")]
+    #[tool(
+        description = "List SurrealDB Cloud instances for a given organization",
+        output_schema = schema_for_type::<ListCloudInstancesResult>()
+    )]
     pub async fn list_cloud_instances(
         &self,
         params: Parameters<CloudOrganizationParams>,
@@ -999,42 +2196,24 @@ Examples:
             .list_instances(&organization_id)
             .await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
-        // Convert result to JSON
-        let instances: Vec<serde_json::Value> = instances
-            .into_iter()
-            .map(|instance| {
-                serde_json::json!({
-                    "id": instance.id,
-                    "name": instance.name,
-                    "slug": instance.slug,
-                    "version": instance.version,
-                    "available_versions": instance.available_versions,
-                    "host": instance.host,
-                    "region": instance.region,
-                    "organization_id": instance.organization_id,
-                    "compute_units": instance.compute_units,
-                    "state": instance.state,
-                    "storage_size": instance.storage_size,
-                    "can_update_storage_size": instance.can_update_storage_size,
-                    "storage_size_update_cooloff_hours": instance.storage_size_update_cooloff_hours,
-                })
-            })
-            .collect();
         // Create the result JSON
-        let result = serde_json::json!({
-            "instances": instances,
-            "count": instances.len()
-        });
+        let result = to_structured(ListCloudInstancesResult {
+            count: instances.len(),
+            instances,
+        })?;
         // Return the MCP result
         Ok(CallToolResult {
             content: vec![Content::text(result.to_string())],
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(result),
         })
     }
 
-    #[tool(description = "Pause SurrealDB Cloud instance")]
+    #[tool(
+        description = "Pause SurrealDB Cloud instance",
+        output_schema = schema_for_type::<CloudInstanceResult>()
+    )]
     pub async fn pause_cloud_instance(
         &self,
         params: Parameters<CloudInstanceParams>,
@@ -1051,20 +2230,23 @@ Examples:
             .await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         // Create the result JSON
-        let result = serde_json::json!({
-            "message": "Successfully paused cloud instance",
-            "instance": instance,
-        });
+        let result = to_structured(CloudInstanceResult {
+            message: "Successfully paused cloud instance".to_string(),
+            instance,
+        })?;
         // Return the MCP result
         Ok(CallToolResult {
             content: vec![Content::text(result.to_string())],
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(result),
         })
     }
 
-    #[tool(description = "Resume SurrealDB Cloud instance")]
+    #[tool(
+        description = "Resume SurrealDB Cloud instance",
+        output_schema = schema_for_type::<CloudInstanceResult>()
+    )]
     pub async fn resume_cloud_instance(
         &self,
         params: Parameters<CloudInstanceParams>,
@@ -1081,20 +2263,23 @@ Examples:
             .await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         // Create the result JSON
-        let result = serde_json::json!({
-            "message": "Successfully resumed cloud instance",
-            "instance": instance,
-        });
+        let result = to_structured(CloudInstanceResult {
+            message: "Successfully resumed cloud instance".to_string(),
+            instance,
+        })?;
         // Return the MCP result
         Ok(CallToolResult {
             content: vec![Content::text(result.to_string())],
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(result),
         })
     }
 
-    #[tool(description = "Get SurrealDB Cloud instance status")]
+    #[tool(
+        description = "Get SurrealDB Cloud instance status",
+        output_schema = schema_for_type::<CloudInstanceStatusResult>()
+    )]
     pub async fn get_cloud_instance_status(
         &self,
         params: Parameters<CloudInstanceParams>,
@@ -1111,22 +2296,25 @@ Examples:
             .await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         // Create the result JSON
-        let result = serde_json::json!({
-            "instance_id": instance_id,
-            "phase": status.phase,
-            "db_backups": status.db_backups,
-            "backup_count": status.db_backups.len()
-        });
+        let result = to_structured(CloudInstanceStatusResult {
+            instance_id,
+            phase: status.phase,
+            backup_count: status.db_backups.len(),
+            db_backups: status.db_backups,
+        })?;
         // Return the MCP result
         Ok(CallToolResult {
             content: vec![Content::text(result.to_string())],
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(result),
         })
     }
 
-    #[tool(description = "Create SurrealDB Cloud instance")]
+    #[tool(
+        description = "Create SurrealDB Cloud instance",
+        output_schema = schema_for_type::<CloudInstanceResult>()
+    )]
     pub async fn create_cloud_instance(
         &self,
         params: Parameters<CreateCloudInstanceParams>,
@@ -1146,16 +2334,16 @@ Examples:
             .await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
         // Create the result JSON
-        let result = serde_json::json!({
-            "message": "Successfully created cloud instance",
-            "instance": instance,
-        });
+        let result = to_structured(CloudInstanceResult {
+            message: "Successfully created cloud instance".to_string(),
+            instance,
+        })?;
         // Return the MCP result
         Ok(CallToolResult {
             content: vec![Content::text(result.to_string())],
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(result),
         })
     }
 
@@ -1171,6 +2359,7 @@ Examples:
     /// * `database` - The database to use (optional, defaults to "test")
     /// * `username` - Username for authentication (optional, only needed for remote connections)
     /// * `password` - Password for authentication (optional, only needed for remote connections)
+    /// * `connection` - Name to open the connection under, alongside the default connection (optional)
     #[tool(description = r#"
 Connect to a different SurrealDB endpoint.
 
@@ -1183,6 +2372,12 @@ Each client connection is completely isolated, so you can switch between differe
 databases as needed. The connection is persistent until you disconnect or connect to 
 a different endpoint. The username and password are optional.
 
+Set connection to a name to open the connection alongside the default connection,
+instead of replacing it. Pass the same name as the connection parameter of the query,
+select, insert, create, upsert, update, delete, and relate tools to run them on this
+connection, for example to compare data between environments. Connecting again with the
+same name replaces that connection. Use list_connections to list the open connections.
+
 For SurrealDB Cloud instances, use the format 'cloud:instance_id' where instance_id 
 is the ID of your cloud instance. The tool will automatically fetch the authentication 
 token from the SurrealDB Cloud API and establish a secure connection.
@@ -1193,6 +2388,7 @@ Examples:
 - connect_endpoint('ws://localhost:8000', Some('myapp'), Some('production'), Some('root'), Some('password'))  # Remote connection
 - connect_endpoint('rocksdb:/data/mydb', Some('analytics'), Some('events'))  # High-performance local storage
 - connect_endpoint('cloud:abc123def456', Some('myapp'), Some('production'))  # SurrealDB Cloud instance
+- connect_endpoint('ws://staging:8000', Some('myapp'), Some('staging'), connection: 'staging')  # Named connection
 "#)]
     pub async fn connect_endpoint(
         &self,
@@ -1204,6 +2400,7 @@ Examples:
             database,
             username,
             password,
+            connection,
         } = params.0;
         // Start the measurement timer
         let start_time = Instant::now();
@@ -1219,14 +2416,12 @@ Examples:
             "Attempting to connect to SurrealDB endpoint"
         );
         // Check if endpoint is restricted by startup configuration
-        if let Some(configured_endpoint) = &self.endpoint
-            && endpoint != *configured_endpoint
-        {
+        if !self.is_endpoint_allowed(&endpoint) {
             // Output debugging information
             warn!(
                 connection_id = %self.connection_id,
                 requested_endpoint = %endpoint,
-                configured_endpoint = %configured_endpoint,
+                configured_endpoint = self.endpoint.as_deref(),
                 "Connection rejected: endpoint not allowed by server configuration"
             );
             // Increment error metrics
@@ -1236,47 +2431,45 @@ Examples:
             // Return error message
             return Err(McpError::internal_error(
                 format!(
-                    "Cannot connect to endpoint '{endpoint}'. Server is configured to only use endpoint '{configured_endpoint}'"
+                    "Cannot connect to endpoint '{endpoint}'. The endpoint is not allowed by the server configuration"
                 ),
                 None,
             ));
         }
         // Check if namespace is restricted by startup configuration
-        if let Some(configured_namespace) = &self.namespace
-            && let Some(namespace) = &namespace
-            && namespace != configured_namespace
+        if let Some(namespace) = &namespace
+            && !self.is_namespace_allowed(namespace)
         {
             // Output debugging information
             warn!(
                 connection_id = %self.connection_id,
                 requested_namespace = %namespace,
-                configured_namespace = %configured_namespace,
+                configured_namespace = self.namespace.as_deref(),
                 "Connection rejected: namespace not allowed by server configuration"
             );
             // Return error message
             return Err(McpError::internal_error(
                 format!(
-                    "Cannot use namespace '{namespace}'. Server is configured to only use namespace '{configured_namespace}'"
+                    "Cannot use namespace '{namespace}'. The namespace is not allowed by the server configuration"
                 ),
                 None,
             ));
         }
         // Check if database is restricted by startup configuration
-        if let Some(configured_database) = &self.database
-            && let Some(database) = &database
-            && database != configured_database
+        if let Some(database) = &database
+            && !self.is_database_allowed(database)
         {
             // Output debugging information
             warn!(
                 connection_id = %self.connection_id,
                 requested_database = %database,
-                configured_database = %configured_database,
+                configured_database = self.database.as_deref(),
                 "Connection rejected: database not allowed by server configuration"
             );
             // Return error message
             return Err(McpError::internal_error(
                 format!(
-                    "Cannot use database '{database}'. Server is configured to only use database '{configured_database}'"
+                    "Cannot use database '{database}'. The database is not allowed by the server configuration"
                 ),
                 None,
             ));
@@ -1289,270 +2482,259 @@ Examples:
         let user = username.or_else(|| self.user.clone());
         // Get the password to use for authentication
         let pass = password.or_else(|| self.pass.clone());
-        // Check if this is a cloud connection
-        let connection = if endpoint.starts_with("cloud:") {
-            // Extract instance ID from cloud:instance_id format
-            let instance_id = endpoint.strip_prefix("cloud:").ok_or_else(|| {
-                McpError::internal_error(
-                    "Invalid cloud endpoint format. Expected 'cloud:instance_id'".to_string(),
-                    None,
-                )
-            })?;
-            // Get the instance details to get the host
-            let instance = match self.cloud_client.get_instance(instance_id).await {
-                Ok(instance) => instance,
-                Err(e) => {
-                    error!(
-                        connection_id = %self.connection_id,
-                        instance_id = %instance_id,
-                        error = %e,
-                        "Failed to get cloud instance details"
-                    );
-                    counter!("surrealmcp.total_errors").increment(1);
-                    counter!("surrealmcp.total_connection_errors").increment(1);
-                    counter!("surrealmcp.errors.connect_endpoint").increment(1);
-                    return Err(McpError::internal_error(
-                        format!("Failed to get cloud instance details '{instance_id}': {e}"),
-                        None,
-                    ));
-                }
-            };
-            // Get the auth token for the cloud instance
-            let token = match self.cloud_client.get_instance_auth(instance_id).await {
-                Ok(token) => token,
-                Err(e) => {
-                    error!(
-                        connection_id = %self.connection_id,
-                        instance_id = %instance_id,
-                        error = %e,
-                        "Failed to get cloud instance token"
-                    );
-                    counter!("surrealmcp.total_errors").increment(1);
-                    counter!("surrealmcp.total_connection_errors").increment(1);
-                    counter!("surrealmcp.errors.connect_endpoint").increment(1);
-                    return Err(McpError::internal_error(
-                        format!("Failed to get cloud instance auth token '{instance_id}': {e}"),
-                        None,
-                    ));
-                }
-            };
-            // Check if instance state is ready
-            if let Some(state) = &instance.state
-                && state != "ready"
-            {
-                error!(
-                    connection_id = %self.connection_id,
-                    instance_id = %instance_id,
-                    state = %state,
-                    "Cloud instance is not ready to connect"
-                );
-                counter!("surrealmcp.total_errors").increment(1);
-                counter!("surrealmcp.total_connection_errors").increment(1);
-                counter!("surrealmcp.errors.connect_endpoint").increment(1);
-                return Err(McpError::internal_error(
-                    format!("Cloud instance '{instance_id}' is not ready (state: {state})"),
-                    None,
-                ));
-            }
-            // Get the host from the instance
-            let host = instance.host.ok_or_else(|| {
-                error!(
-                    connection_id = %self.connection_id,
-                    instance_id = %instance_id,
-                    "Cloud instance has no host information"
-                );
-                counter!("surrealmcp.total_errors").increment(1);
-                counter!("surrealmcp.total_connection_errors").increment(1);
-                counter!("surrealmcp.errors.connect_endpoint").increment(1);
-                McpError::internal_error(
-                    format!("Cloud instance '{instance_id}' has no host information"),
-                    None,
-                )
-            })?;
-            // Create a new SurrealDB connection with the token
-            db::create_client_connection_with_token(
-                &host,
-                &token,
-                user.as_deref(),
-                pass.as_deref(),
-                ns.as_deref(),
-                db.as_deref(),
-            )
-            .await
-        } else {
-            db::create_client_connection(
-                &endpoint,
-                user.as_deref(),
-                pass.as_deref(),
-                ns.as_deref(),
-                db.as_deref(),
-            )
+        // Get the credentials to authenticate with
+        let credentials = self.credentials(&endpoint, user, pass).await;
+        // Connect to the endpoint
+        self.connect_internal(endpoint, ns, db, credentials, connection, start_time)
             .await
-        };
-        // Create a new SurrealDB connection
-        match connection {
-            Ok(instance) => {
-                // Calculate the elapsed time
-                let duration = start_time.elapsed();
-                // Update the service's database connection
-                let mut db_guard = self.db.lock().await;
-                *db_guard = Some(instance);
-                // Output debugging information
-                info!(
-                    connection_id = %self.connection_id,
-                    endpoint = %endpoint,
-                    namespace = ns.as_deref(),
-                    database = db.as_deref(),
-                    duration_ms = duration.as_millis(),
-                    "Successfully connected to SurrealDB endpoint"
-                );
-                // Return success message
-                let msg = format!("Successfully connected to endpoint '{endpoint}'");
-                Ok(CallToolResult {
-                    content: vec![Content::text(msg)],
-                    is_error: None,
-                    meta: None,
-                    structured_content: None,
-                })
-            }
-            Err(e) => {
-                // Calculate the elapsed time
-                let duration = start_time.elapsed();
-                // Output debugging information
-                error!(
-                    connection_id = %self.connection_id,
-                    endpoint = %endpoint,
-                    namespace = ns.as_deref(),
-                    database = db.as_deref(),
-                    duration_ms = duration.as_millis(),
-                    error = %e,
-                    "Failed to connect to SurrealDB endpoint"
-                );
-                // Increment error metrics
-                counter!("surrealmcp.total_errors").increment(1);
-                counter!("surrealmcp.total_connection_errors").increment(1);
-                counter!("surrealmcp.errors.connect_endpoint").increment(1);
-                // Return error message
-                Err(McpError::internal_error(
-                    format!("Failed to connect to endpoint '{endpoint}': {e}"),
-                    None,
-                ))
-            }
-        }
+            .inspect_err(|_| counter!("surrealmcp.errors.connect_endpoint").increment(1))
     }
 
-    /// List available namespaces on the connected endpoint.
+    /// List the named connection profiles configured on the server.
     ///
-    /// This function lists all namespaces available on the currently connected SurrealDB endpoint.
-    /// It returns a list of namespaces with their names.
+    /// This function lists the connection profiles which can be connected to
+    /// using `connect_profile`. The credentials of each profile are never
+    /// included in the result.
     ///
     /// # Arguments
     /// * `None` - No parameters are required for this tool
     #[tool(description = r#"
-List available namespaces on the connected endpoint.
+List the named connection profiles configured on the server.
 
-This function lists all namespaces available on the currently connected SurrealDB endpoint.
-It returns a list of namespaces with their names."#)]
-    pub async fn list_namespaces(
+Each profile has a name, an optional description, and the endpoint, namespace,
+and database it connects to. Use connect_profile to connect to a profile by name.
+The credentials of profiles are kept on the server and are never returned."#,
+        output_schema = schema_for_type::<ListProfilesResult>()
+    )]
+    pub async fn list_profiles(
         &self,
         _params: Parameters<CloudParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Start the measurement timer
-        let start_time = Instant::now();
         // Increment tool usage counter
-        counter!("surrealmcp.tools.list_namespaces").increment(1);
+        counter!("surrealmcp.tools.list_profiles").increment(1);
         // Output debugging information
-        debug!("Listing available namespaces");
-        // Build the initial query string
-        let query = "INFO FOR ROOT;".to_string();
-        // Execute INFO FOR ROOT
-        let mut exec_res = self.query_internal(query, None).await?;
-        // Match the result of the query
-        match exec_res.result.as_mut() {
-            Some(response) => {
-                // Calculate the elapsed time
-                let duration = start_time.elapsed();
-                let response: &mut engine::IndexedResults = response;
-                // Take the first element of the response as a generic Value
-                let value: surrealdb::types::Value = response
-                    .take(0)
-                    .map_err(|e: surrealdb::Error| McpError::internal_error(e.to_string(), None))?;
-
-                // Convert SurrealDB Value to JSON, then to our struct
-                let json_val = utils::surreal_to_json(value);
-                let info: ListNamespaces = serde_json::from_value(json_val)
-                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
-                // Convert the namespaces to a JSON object
-                let namespaces: Vec<serde_json::Value> = info
-                    .namespaces
-                    .keys()
-                    .map(|name| serde_json::json!({ "name": name }))
-                    .collect();
-                // Convert the namespaces to a JSON object
-                let result = serde_json::json!({
-                    "namespaces": namespaces,
-                    "count": namespaces.len()
-                });
-                // Output debugging information
-                info!(
-                    connection_id = %self.connection_id,
-                    duration_ms = duration.as_millis(),
-                    "Successfully listed available namespaces"
-                );
-                // Return the result
-                Ok(CallToolResult {
-                    content: vec![Content::text(result.to_string())],
-                    is_error: None,
-                    meta: None,
-                    structured_content: None,
-                })
-            }
-            None => {
-                // Calculate the elapsed time
-                let duration = start_time.elapsed();
-                // Output debugging information
-                warn!(
-                    connection_id = %self.connection_id,
-                    duration_ms = duration.as_millis(),
-                    "Unable to list available namespaces"
-                );
-                // Increment error metrics
-                counter!("surrealmcp.total_errors").increment(1);
-                counter!("surrealmcp.errors.list_namespaces").increment(1);
-                // Return error message
-                let err = exec_res
-                    .error
-                    .unwrap_or_else(|| "Unknown error".to_string());
-                Err(McpError::internal_error(err, None))
-            }
-        }
+        debug!("Listing connection profiles");
+        // Convert the profiles, omitting their credentials
+        let profiles: Vec<ProfileResult> = self
+            .profiles
+            .iter()
+            .map(|(name, profile)| ProfileResult {
+                name: name.clone(),
+                description: profile.description.clone(),
+                endpoint: profile.endpoint.clone(),
+                namespace: profile.namespace.clone(),
+                database: profile.database.clone(),
+            })
+            .collect();
+        // Create the result JSON
+        let result = to_structured(ListProfilesResult {
+            count: profiles.len(),
+            profiles,
+        })?;
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
     }
 
-    /// List available databases on the connected endpoint.
+    /// Connect to a named connection profile configured on the server.
     ///
-    /// This function lists all databases available on the currently connected SurrealDB endpoint.
-    /// It returns a list of databases with their names.
+    /// This function connects to the endpoint, namespace, and database of a
+    /// profile, signing in with the credentials of the profile, which are kept
+    /// on the server.
     ///
     /// # Arguments
-    /// * `None` - No parameters are required for this tool
+    /// * `name` - The name of the connection profile
+    /// * `connection` - Name to open the connection under, alongside the default connection (optional)
     #[tool(description = r#"
-List available databases on the connected endpoint.
+Connect to a named connection profile configured on the server.
 
-This function lists all databases available on the currently connected SurrealDB endpoint.
-It returns a list of databases with their names."#)]
-    pub async fn list_databases(
-        &self,
+Profiles define the endpoint, namespace, database, and credentials of a connection,
+so you can switch between environments (such as 'dev', 'staging', and 'production')
+by name without knowing their credentials. Use list_profiles to list the available
+profiles. This replaces the current connection, just like connect_endpoint, unless
+connection is set to a name to open the profile alongside the default connection.
+
+Examples:
+- connect_profile('staging')
+- connect_profile('production')
+- connect_profile('production', connection: 'production')
+"#)]
+    pub async fn connect_profile(
+        &self,
+        params: Parameters<ConnectProfileParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let ConnectProfileParams { name, connection } = params.0;
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.connect_profile").increment(1);
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            profile = %name,
+            "Attempting to connect to SurrealDB profile"
+        );
+        // Get the connection profile
+        let Some(profile) = self.profiles.get(&name) else {
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.connect_profile").increment(1);
+            // Return error message
+            return Err(McpError::invalid_params(
+                format!(
+                    "Unknown connection profile '{name}'. Use list_profiles to list the available profiles"
+                ),
+                None,
+            ));
+        };
+        // Get the password of the profile
+        let password = profile.password().map_err(|e| {
+            // Output debugging information
+            error!(
+                connection_id = %self.connection_id,
+                profile = %name,
+                error = %e,
+                "Failed to load the credentials of the connection profile"
+            );
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.total_configuration_errors").increment(1);
+            counter!("surrealmcp.errors.connect_profile").increment(1);
+            // Return error message
+            McpError::internal_error(
+                format!("Failed to load the credentials of profile '{name}'"),
+                None,
+            )
+        })?;
+        // Get the credentials to authenticate with
+        let credentials = db::Credentials {
+            level: profile.signin_level,
+            username: profile.username.clone(),
+            password,
+            access: profile.access.clone(),
+            subject: self.identity().await.and_then(|v| v.subject),
+            access_key: self.signin.access_key.clone(),
+            token: None,
+        };
+        // Connect to the endpoint of the profile
+        self.connect_internal(
+            profile.endpoint.clone(),
+            profile.namespace.clone(),
+            profile.database.clone(),
+            credentials,
+            connection,
+            start_time,
+        )
+        .await
+        .inspect_err(|_| counter!("surrealmcp.errors.connect_profile").increment(1))
+    }
+
+    /// List the connections which this session holds open.
+    ///
+    /// This function lists the default connection, if connected, along with the
+    /// named connections opened using the `connection` parameter of
+    /// `connect_endpoint` and `connect_profile`, checking the health of each.
+    ///
+    /// # Arguments
+    /// * `None` - No parameters are required for this tool
+    #[tool(description = r#"
+List the connections which this session holds open.
+
+Returns the default connection, if connected, and each named connection opened using the
+connection parameter of connect_endpoint or connect_profile. Each connection includes its
+name, endpoint, namespace, database, the level it is authenticated at (root, namespace,
+database, record, token, or none), and whether it responded to a health check. Pass the
+name of a connection as the connection parameter of the query, select, insert, create,
+upsert, update, delete, and relate tools to use it."#,
+        output_schema = schema_for_type::<ListConnectionsResult>()
+    )]
+    pub async fn list_connections(
+        &self,
+        _params: Parameters<CloudParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.list_connections").increment(1);
+        // Output debugging information
+        debug!("Listing open connections");
+        // Get the default connection, if connected
+        let mut connections = Vec::new();
+        if let Some(db) = &*self.db.lock().await {
+            let target = self
+                .target
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            connections.push((DEFAULT_CONNECTION.to_string(), db.clone(), target));
+        }
+        // Get the named connections
+        for (name, named) in self.named_connections.lock().await.iter() {
+            connections.push((name.clone(), named.db.clone(), named.target.clone()));
+        }
+        // Check the health of each connection concurrently
+        let connections: Vec<ConnectionResult> =
+            futures::future::join_all(connections.into_iter().map(
+                |(name, db, target)| async move {
+                    let healthy = matches!(
+                        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, db.health()).await,
+                        Ok(Ok(()))
+                    );
+                    ConnectionResult {
+                        default: name == DEFAULT_CONNECTION,
+                        name,
+                        endpoint: target.endpoint,
+                        namespace: target.namespace,
+                        database: target.database,
+                        auth: target.auth.map(str::to_string),
+                        healthy,
+                    }
+                },
+            ))
+            .await;
+        // Create the result JSON
+        let result = to_structured(ListConnectionsResult {
+            count: connections.len(),
+            connections,
+        })?;
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// List available namespaces on the connected endpoint.
+    ///
+    /// This function lists all namespaces available on the currently connected SurrealDB endpoint.
+    /// It returns a list of namespaces with their names.
+    ///
+    /// # Arguments
+    /// * `None` - No parameters are required for this tool
+    #[tool(description = r#"
+List available namespaces on the connected endpoint.
+
+This function lists all namespaces available on the currently connected SurrealDB endpoint.
+It returns a list of namespaces with their names."#,
+        output_schema = schema_for_type::<ListNamespacesResult>()
+    )]
+    pub async fn list_namespaces(
+        &self,
         _params: Parameters<CloudParams>,
     ) -> Result<CallToolResult, McpError> {
         // Start the measurement timer
         let start_time = Instant::now();
         // Increment tool usage counter
-        counter!("surrealmcp.tools.list_databases").increment(1);
+        counter!("surrealmcp.tools.list_namespaces").increment(1);
         // Output debugging information
-        debug!("Listing available databases");
+        debug!("Listing available namespaces");
         // Build the initial query string
-        let query = "INFO FOR NAMESPACE;".to_string();
-        // Execute INFO FOR NAMESPACE
+        let query = "INFO FOR ROOT;".to_string();
+        // Execute INFO FOR ROOT
         let mut exec_res = self.query_internal(query, None).await?;
         // Match the result of the query
         match exec_res.result.as_mut() {
@@ -1567,31 +2749,31 @@ It returns a list of databases with their names."#)]
 
                 // Convert SurrealDB Value to JSON, then to our struct
                 let json_val = utils::surreal_to_json(value);
-                let info: ListDatabases = serde_json::from_value(json_val)
+                let info: ListNamespaces = serde_json::from_value(json_val)
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
-                // Convert the databases to a JSON object
-                let databases: Vec<serde_json::Value> = info
-                    .databases
-                    .keys()
-                    .map(|name| serde_json::json!({ "name": name }))
+                // Convert the namespaces to a list of names
+                let namespaces: Vec<NameResult> = info
+                    .namespaces
+                    .into_keys()
+                    .map(|name| NameResult { name })
                     .collect();
-                // Convert the databases to a JSON object
-                let result = serde_json::json!({
-                    "databases": databases,
-                    "count": databases.len()
-                });
+                // Create the result JSON
+                let result = to_structured(ListNamespacesResult {
+                    count: namespaces.len(),
+                    namespaces,
+                })?;
                 // Output debugging information
                 info!(
                     connection_id = %self.connection_id,
                     duration_ms = duration.as_millis(),
-                    "Successfully listed available databases"
+                    "Successfully listed available namespaces"
                 );
                 // Return the result
                 Ok(CallToolResult {
                     content: vec![Content::text(result.to_string())],
                     is_error: None,
                     meta: None,
-                    structured_content: None,
+                    structured_content: Some(result),
                 })
             }
             None => {
@@ -1601,11 +2783,11 @@ It returns a list of databases with their names."#)]
                 warn!(
                     connection_id = %self.connection_id,
                     duration_ms = duration.as_millis(),
-                    "Unable to list available databases"
+                    "Unable to list available namespaces"
                 );
                 // Increment error metrics
                 counter!("surrealmcp.total_errors").increment(1);
-                counter!("surrealmcp.errors.list_databases").increment(1);
+                counter!("surrealmcp.errors.list_namespaces").increment(1);
                 // Return error message
                 let err = exec_res
                     .error
@@ -1615,218 +2797,473 @@ It returns a list of databases with their names."#)]
         }
     }
 
-    /// Change the namespace on the currently connected endpoint.
+    /// List available databases on the connected endpoint.
     ///
-    /// This function allows you to switch to a different namespace on the currently
-    /// connected SurrealDB endpoint. The namespace change will apply to all subsequent
-    /// queries until you change it again or reconnect to a different endpoint.
+    /// This function lists all databases available on the currently connected SurrealDB endpoint.
+    /// It returns a list of databases with their names.
     ///
     /// # Arguments
-    /// * `namespace` - The namespace to switch to
+    /// * `None` - No parameters are required for this tool
     #[tool(description = r#"
-Change the namespace on the currently connected endpoint.
-
-This function allows you to switch to a different namespace on the currently connected 
-SurrealDB endpoint. The namespace change will apply to all subsequent queries until 
-you change it again or reconnect to a different endpoint.
-
-This is useful when you want to:
-- Organize data into different logical groups
-- Switch between development, staging, and production environments
-- Work with multiple applications using the same SurrealDB instance
+List available databases on the connected endpoint.
 
-Examples:
-- use_namespace('development')
-- use_namespace('production')
-- use_namespace('analytics')
-"#)]
-    pub async fn use_namespace(
+This function lists all databases available on the currently connected SurrealDB endpoint.
+It returns a list of databases with their names."#,
+        output_schema = schema_for_type::<ListDatabasesResult>()
+    )]
+    pub async fn list_databases(
         &self,
-        params: Parameters<UseNamespaceParams>,
+        _params: Parameters<CloudParams>,
     ) -> Result<CallToolResult, McpError> {
-        let UseNamespaceParams { namespace } = params.0;
         // Start the measurement timer
         let start_time = Instant::now();
         // Increment tool usage counter
-        counter!("surrealmcp.tools.use_namespace").increment(1);
+        counter!("surrealmcp.tools.list_databases").increment(1);
         // Output debugging information
-        info!(
-            connection_id = %self.connection_id,
-            namespace = %namespace,
-            "Attempting to change namespace"
-        );
-        // Check if namespace is restricted by startup configuration
-        if let Some(configured_namespace) = &self.namespace
-            && namespace != *configured_namespace
-        {
-            // Output debugging information
-            warn!(
-                connection_id = %self.connection_id,
-                requested_namespace = %namespace,
-                configured_namespace = %configured_namespace,
-                "Namespace change rejected: namespace not allowed by server configuration"
-            );
-            // Increment error metrics
-            counter!("surrealmcp.total_errors").increment(1);
-            counter!("surrealmcp.total_configuration_errors").increment(1);
-            counter!("surrealmcp.errors.use_namespace").increment(1);
-            // Return error message
-            return Err(McpError::internal_error(
-                format!(
-                    "Cannot use namespace '{namespace}'. Server is configured to only use namespace '{configured_namespace}'"
-                ),
-                None,
-            ));
-        }
-        // Lock the database connection
-        let db_guard = self.db.lock().await;
-        // Match the database connection
-        match &*db_guard {
-            Some(db) => {
-                // Use the specified namespace
-                match db.use_ns(&namespace).await {
-                    Ok(_) => {
-                        let duration = start_time.elapsed();
-                        // Output debugging information
-                        info!(
-                            connection_id = %self.connection_id,
-                            namespace = %namespace,
-                            duration_ms = duration.as_millis(),
-                            "Successfully changed namespace"
-                        );
-                        // Return success message
-                        let msg = format!("Successfully switched to namespace '{namespace}'");
-                        Ok(CallToolResult {
-                            content: vec![Content::text(msg)],
-                            is_error: None,
-                            meta: None,
-                            structured_content: None,
-                        })
-                    }
-                    Err(e) => {
-                        let duration = start_time.elapsed();
-                        // Output debugging information
-                        error!(
-                            connection_id = %self.connection_id,
-                            namespace = %namespace,
-                            duration_ms = duration.as_millis(),
-                            error = %e,
-                            "Failed to change namespace"
-                        );
-                        // Increment error metrics
-                        counter!("surrealmcp.total_errors").increment(1);
-                        counter!("surrealmcp.total_connection_errors").increment(1);
-                        counter!("surrealmcp.errors.use_namespace").increment(1);
-                        // Return error message
-                        Err(McpError::internal_error(
-                            format!("Failed to change namespace to '{namespace}': {e}"),
-                            None,
-                        ))
-                    }
-                }
+        debug!("Listing available databases");
+        // Build the initial query string
+        let query = "INFO FOR NAMESPACE;".to_string();
+        // Execute INFO FOR NAMESPACE
+        let mut exec_res = self.query_internal(query, None).await?;
+        // Match the result of the query
+        match exec_res.result.as_mut() {
+            Some(response) => {
+                // Calculate the elapsed time
+                let duration = start_time.elapsed();
+                let response: &mut engine::IndexedResults = response;
+                // Take the first element of the response as a generic Value
+                let value: surrealdb::types::Value = response
+                    .take(0)
+                    .map_err(|e: surrealdb::Error| McpError::internal_error(e.to_string(), None))?;
+
+                // Convert SurrealDB Value to JSON, then to our struct
+                let json_val = utils::surreal_to_json(value);
+                let info: ListDatabases = serde_json::from_value(json_val)
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                // Convert the databases to a list of names
+                let databases: Vec<NameResult> = info
+                    .databases
+                    .into_keys()
+                    .map(|name| NameResult { name })
+                    .collect();
+                // Create the result JSON
+                let result = to_structured(ListDatabasesResult {
+                    count: databases.len(),
+                    databases,
+                })?;
+                // Output debugging information
+                info!(
+                    connection_id = %self.connection_id,
+                    duration_ms = duration.as_millis(),
+                    "Successfully listed available databases"
+                );
+                // Return the result
+                Ok(CallToolResult {
+                    content: vec![Content::text(result.to_string())],
+                    is_error: None,
+                    meta: None,
+                    structured_content: Some(result),
+                })
             }
             None => {
+                // Calculate the elapsed time
+                let duration = start_time.elapsed();
                 // Output debugging information
                 warn!(
                     connection_id = %self.connection_id,
-                    namespace = %namespace,
-                    "Namespace change attempted without database connection"
+                    duration_ms = duration.as_millis(),
+                    "Unable to list available databases"
                 );
                 // Increment error metrics
                 counter!("surrealmcp.total_errors").increment(1);
-                counter!("surrealmcp.total_configuration_errors").increment(1);
-                counter!("surrealmcp.errors.no_connection").increment(1);
+                counter!("surrealmcp.errors.list_databases").increment(1);
                 // Return error message
-                Err(McpError::internal_error(
-                    "Not connected to any SurrealDB endpoint. Use connect_endpoint first."
-                        .to_string(),
-                    None,
-                ))
+                let err = exec_res
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                Err(McpError::internal_error(err, None))
             }
         }
     }
 
-    /// Change the database on the currently connected endpoint.
+    /// List the tables defined in the current database.
     ///
-    /// This function allows you to switch to a different database on the currently
-    /// connected SurrealDB endpoint. The database change will apply to all subsequent
-    /// queries until you change it again or reconnect to a different endpoint.
+    /// This function lists all tables defined in the currently selected database,
+    /// returning the structured table definitions including the table type,
+    /// schema mode, comment, and permissions.
     ///
     /// # Arguments
-    /// * `database` - The database to switch to
+    /// * `None` - No parameters are required for this tool
     #[tool(description = r#"
-Change the database on the currently connected endpoint.
+List the tables defined in the current database.
 
-This function allows you to switch to a different database on the currently connected 
-SurrealDB endpoint. The database change will apply to all subsequent queries until 
+This function runs INFO FOR DB and returns the structured definition of every table in the 
+currently selected database, including the table type (normal, relation, any), whether it is 
+schemafull, its comment, and its permissions. Use this to discover the schema before writing 
+queries."#)]
+    pub async fn list_tables(
+        &self,
+        _params: Parameters<CloudParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.list_tables").increment(1);
+        // Output debugging information
+        debug!("Listing defined tables");
+        // Fetch the structured database information
+        let info: DatabaseStructure = self
+            .info_internal("INFO FOR DB STRUCTURE;".to_string())
+            .await
+            .inspect_err(|_| {
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.list_tables").increment(1);
+            })?;
+        // Create the result JSON
+        let result = serde_json::json!({
+            "count": info.tables.len(),
+            "tables": info.tables,
+        });
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Successfully listed defined tables"
+        );
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// Describe the full schema of a table.
+    ///
+    /// This function returns the structured definition of a table together with
+    /// all of its fields, indexes, and events.
+    ///
+    /// # Arguments
+    /// * `table` - The name of the table to describe
+    #[tool(description = r#"
+Describe the full schema of a table.
+
+This function returns the structured definition of the specified table together with all of 
+its fields (including types, defaults, assertions, and permissions), indexes (including the 
+indexed columns and index type), and events (including the WHEN condition and THEN clauses).
+
+Examples:
+- describe_table('person')
+- describe_table('wrote')
+"#)]
+    pub async fn describe_table(
+        &self,
+        params: Parameters<TableParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let TableParams { table } = params.0;
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.describe_table").increment(1);
+        // Output debugging information
+        debug!(table = %table, "Describing table");
+        // Fetch the structured database information
+        let info: DatabaseStructure = self
+            .info_internal("INFO FOR DB STRUCTURE;".to_string())
+            .await
+            .inspect_err(|_| {
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.describe_table").increment(1);
+            })?;
+        // Find the definition of the requested table
+        let definition = info
+            .tables
+            .into_iter()
+            .find(|t| t.get("name").and_then(|v| v.as_str()) == Some(table.as_str()))
+            .ok_or_else(|| {
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.describe_table").increment(1);
+                McpError::invalid_params(format!("Table '{table}' does not exist"), None)
+            })?;
+        // Fetch the structured table information
+        let structure = self.table_structure(&table).await.inspect_err(|_| {
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.describe_table").increment(1);
+        })?;
+        // Create the result JSON
+        let result = serde_json::json!({
+            "table": definition,
+            "fields": structure.fields,
+            "indexes": structure.indexes,
+            "events": structure.events,
+        });
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            table = %table,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Successfully described table"
+        );
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// List the fields defined on a table.
+    ///
+    /// This function returns the structured definition of every field defined
+    /// on the specified table, including types, defaults, and assertions.
+    ///
+    /// # Arguments
+    /// * `table` - The name of the table to inspect
+    #[tool(description = r#"
+List the fields defined on a table.
+
+This function returns the structured definition of every field defined on the specified table, 
+including the field type (kind), default value, assertion, readonly flag, and permissions.
+
+Examples:
+- list_fields('person')
+"#)]
+    pub async fn list_fields(
+        &self,
+        params: Parameters<TableParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let TableParams { table } = params.0;
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.list_fields").increment(1);
+        // Output debugging information
+        debug!(table = %table, "Listing table fields");
+        // Fetch the structured table information
+        let structure = self.table_structure(&table).await.inspect_err(|_| {
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.list_fields").increment(1);
+        })?;
+        // Create the result JSON
+        let result = serde_json::json!({
+            "table": table,
+            "count": structure.fields.len(),
+            "fields": structure.fields,
+        });
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            table = %table,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Successfully listed table fields"
+        );
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// List the indexes defined on a table.
+    ///
+    /// This function returns the structured definition of every index defined
+    /// on the specified table, including the indexed columns and index type.
+    ///
+    /// # Arguments
+    /// * `table` - The name of the table to inspect
+    #[tool(description = r#"
+List the indexes defined on a table.
+
+This function returns the structured definition of every index defined on the specified table, 
+including the indexed columns and the index type (e.g. UNIQUE, FULLTEXT, HNSW).
+
+Examples:
+- list_indexes('person')
+"#)]
+    pub async fn list_indexes(
+        &self,
+        params: Parameters<TableParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let TableParams { table } = params.0;
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.list_indexes").increment(1);
+        // Output debugging information
+        debug!(table = %table, "Listing table indexes");
+        // Fetch the structured table information
+        let structure = self.table_structure(&table).await.inspect_err(|_| {
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.list_indexes").increment(1);
+        })?;
+        // Create the result JSON
+        let result = serde_json::json!({
+            "table": table,
+            "count": structure.indexes.len(),
+            "indexes": structure.indexes,
+        });
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            table = %table,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Successfully listed table indexes"
+        );
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// List the analyzers defined in the current database.
+    ///
+    /// This function returns the structured definition of every full-text search
+    /// analyzer defined in the currently selected database.
+    ///
+    /// # Arguments
+    /// * `None` - No parameters are required for this tool
+    #[tool(description = r#"
+List the analyzers defined in the current database.
+
+This function returns the structured definition of every full-text search analyzer defined in 
+the currently selected database, including its tokenizers and filters."#)]
+    pub async fn list_analyzers(
+        &self,
+        _params: Parameters<CloudParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.list_analyzers").increment(1);
+        // Output debugging information
+        debug!("Listing defined analyzers");
+        // Fetch the structured database information
+        let info: DatabaseStructure = self
+            .info_internal("INFO FOR DB STRUCTURE;".to_string())
+            .await
+            .inspect_err(|_| {
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.list_analyzers").increment(1);
+            })?;
+        // Create the result JSON
+        let result = serde_json::json!({
+            "count": info.analyzers.len(),
+            "analyzers": info.analyzers,
+        });
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            duration_ms = start_time.elapsed().as_millis(),
+            "Successfully listed defined analyzers"
+        );
+        // Return the result
+        Ok(CallToolResult {
+            content: vec![Content::text(result.to_string())],
+            is_error: None,
+            meta: None,
+            structured_content: Some(result),
+        })
+    }
+
+    /// Change the namespace on the currently connected endpoint.
+    ///
+    /// This function allows you to switch to a different namespace on the currently
+    /// connected SurrealDB endpoint. The namespace change will apply to all subsequent
+    /// queries until you change it again or reconnect to a different endpoint.
+    ///
+    /// # Arguments
+    /// * `namespace` - The namespace to switch to
+    #[tool(description = r#"
+Change the namespace on the currently connected endpoint.
+
+This function allows you to switch to a different namespace on the currently connected 
+SurrealDB endpoint. The namespace change will apply to all subsequent queries until 
 you change it again or reconnect to a different endpoint.
 
 This is useful when you want to:
-- Switch between different databases within the same namespace
 - Organize data into different logical groups
+- Switch between development, staging, and production environments
 - Work with multiple applications using the same SurrealDB instance
 
 Examples:
-- use_database('users')
-- use_database('analytics')
-- use_database('events')
+- use_namespace('development')
+- use_namespace('production')
+- use_namespace('analytics')
 "#)]
-    pub async fn use_database(
+    pub async fn use_namespace(
         &self,
-        params: Parameters<UseDatabaseParams>,
+        params: Parameters<UseNamespaceParams>,
     ) -> Result<CallToolResult, McpError> {
-        let UseDatabaseParams { database } = params.0;
+        let UseNamespaceParams { namespace } = params.0;
         // Start the measurement timer
         let start_time = Instant::now();
         // Increment tool usage counter
-        counter!("surrealmcp.tools.use_database").increment(1);
+        counter!("surrealmcp.tools.use_namespace").increment(1);
         // Output debugging information
         info!(
             connection_id = %self.connection_id,
-            database = %database,
-            "Attempting to change database"
+            namespace = %namespace,
+            "Attempting to change namespace"
         );
-        // Check if database is restricted by startup configuration
-        if let Some(configured_database) = &self.database
-            && database != *configured_database
-        {
+        // Check if namespace is restricted by startup configuration
+        if !self.is_namespace_allowed(&namespace) {
             // Output debugging information
             warn!(
                 connection_id = %self.connection_id,
-                requested_database = %database,
-                configured_database = %configured_database,
-                "Database change rejected: database not allowed by server configuration"
+                requested_namespace = %namespace,
+                configured_namespace = self.namespace.as_deref(),
+                "Namespace change rejected: namespace not allowed by server configuration"
             );
             // Increment error metrics
             counter!("surrealmcp.total_errors").increment(1);
             counter!("surrealmcp.total_configuration_errors").increment(1);
-            counter!("surrealmcp.errors.use_database").increment(1);
+            counter!("surrealmcp.errors.use_namespace").increment(1);
             // Return error message
             return Err(McpError::internal_error(
                 format!(
-                    "Cannot use database '{database}'. Server is configured to only use database '{configured_database}'"
+                    "Cannot use namespace '{namespace}'. The namespace is not allowed by the server configuration"
                 ),
                 None,
             ));
         }
+        // Refuse to change the namespace while a transaction is open
+        if self.transaction.is_open().await {
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.use_namespace").increment(1);
+            // Return error message
+            return Err(McpError::invalid_request(
+                "Cannot change the namespace while a transaction is open. Use commit_transaction or rollback_transaction first.",
+                None,
+            ));
+        }
         // Lock the database connection
         let db_guard = self.db.lock().await;
         // Match the database connection
         match &*db_guard {
             Some(db) => {
-                // Use the specified database
-                match db.use_db(&database).await {
+                // Use the specified namespace
+                match db.use_ns(&namespace).await {
                     Ok(_) => {
+                        // Update the namespace in use
+                        self.update_target(|t| t.namespace = Some(namespace.clone()));
                         let duration = start_time.elapsed();
                         // Output debugging information
                         info!(
                             connection_id = %self.connection_id,
-                            database = %database,
+                            namespace = %namespace,
                             duration_ms = duration.as_millis(),
-                            "Successfully changed database"
+                            "Successfully changed namespace"
                         );
                         // Return success message
-                        let msg = format!("Successfully switched to database '{database}'");
+                        let msg = format!("Successfully switched to namespace '{namespace}'");
                         Ok(CallToolResult {
                             content: vec![Content::text(msg)],
                             is_error: None,
@@ -1839,18 +3276,18 @@ Examples:
                         // Output debugging information
                         error!(
                             connection_id = %self.connection_id,
-                            database = %database,
+                            namespace = %namespace,
                             duration_ms = duration.as_millis(),
                             error = %e,
-                            "Failed to change database"
+                            "Failed to change namespace"
                         );
                         // Increment error metrics
                         counter!("surrealmcp.total_errors").increment(1);
                         counter!("surrealmcp.total_connection_errors").increment(1);
-                        counter!("surrealmcp.errors.use_database").increment(1);
+                        counter!("surrealmcp.errors.use_namespace").increment(1);
                         // Return error message
                         Err(McpError::internal_error(
-                            format!("Failed to change database to '{database}': {e}"),
+                            format!("Failed to change namespace to '{namespace}': {e}"),
                             None,
                         ))
                     }
@@ -1860,8 +3297,8 @@ Examples:
                 // Output debugging information
                 warn!(
                     connection_id = %self.connection_id,
-                    database = %database,
-                    "Database change attempted without database connection"
+                    namespace = %namespace,
+                    "Namespace change attempted without database connection"
                 );
                 // Increment error metrics
                 counter!("surrealmcp.total_errors").increment(1);
@@ -1877,30 +3314,204 @@ Examples:
         }
     }
 
-    /// Disconnect from the current SurrealDB endpoint.
+    /// Change the database on the currently connected endpoint.
     ///
-    /// This function disconnects from the currently connected SurrealDB endpoint.
-    /// After disconnecting, you'll need to use connect_endpoint again to establish
-    /// a new connection before you can execute queries.
+    /// This function allows you to switch to a different database on the currently
+    /// connected SurrealDB endpoint. The database change will apply to all subsequent
+    /// queries until you change it again or reconnect to a different endpoint.
+    ///
+    /// # Arguments
+    /// * `database` - The database to switch to
     #[tool(description = r#"
-Disconnect from the current SurrealDB endpoint.
+Change the database on the currently connected endpoint.
 
-This function disconnects from the currently connected SurrealDB endpoint.
-After disconnecting, you'll need to use connect_endpoint again to establish
-a new connection before you can execute queries.
+This function allows you to switch to a different database on the currently connected 
+SurrealDB endpoint. The database change will apply to all subsequent queries until 
+you change it again or reconnect to a different endpoint.
 
 This is useful when you want to:
-- Switch to a different database
-- Clean up resources
-- Ensure no active connections remain
+- Switch between different databases within the same namespace
+- Organize data into different logical groups
+- Work with multiple applications using the same SurrealDB instance
+
+Examples:
+- use_database('users')
+- use_database('analytics')
+- use_database('events')
 "#)]
-    pub async fn disconnect_endpoint(
+    pub async fn use_database(
         &self,
-        _params: Parameters<CloudParams>,
+        params: Parameters<UseDatabaseParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Increment tool usage metrics
-        counter!("surrealmcp.tools.disconnect_endpoint").increment(1);
-        // Output debugging information
+        let UseDatabaseParams { database } = params.0;
+        // Start the measurement timer
+        let start_time = Instant::now();
+        // Increment tool usage counter
+        counter!("surrealmcp.tools.use_database").increment(1);
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            database = %database,
+            "Attempting to change database"
+        );
+        // Check if database is restricted by startup configuration
+        if !self.is_database_allowed(&database) {
+            // Output debugging information
+            warn!(
+                connection_id = %self.connection_id,
+                requested_database = %database,
+                configured_database = self.database.as_deref(),
+                "Database change rejected: database not allowed by server configuration"
+            );
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.total_configuration_errors").increment(1);
+            counter!("surrealmcp.errors.use_database").increment(1);
+            // Return error message
+            return Err(McpError::internal_error(
+                format!(
+                    "Cannot use database '{database}'. The database is not allowed by the server configuration"
+                ),
+                None,
+            ));
+        }
+        // Refuse to change the database while a transaction is open
+        if self.transaction.is_open().await {
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.errors.use_database").increment(1);
+            // Return error message
+            return Err(McpError::invalid_request(
+                "Cannot change the database while a transaction is open. Use commit_transaction or rollback_transaction first.",
+                None,
+            ));
+        }
+        // Lock the database connection
+        let db_guard = self.db.lock().await;
+        // Match the database connection
+        match &*db_guard {
+            Some(db) => {
+                // Use the specified database
+                match db.use_db(&database).await {
+                    Ok(_) => {
+                        // Update the database in use
+                        self.update_target(|t| t.database = Some(database.clone()));
+                        let duration = start_time.elapsed();
+                        // Output debugging information
+                        info!(
+                            connection_id = %self.connection_id,
+                            database = %database,
+                            duration_ms = duration.as_millis(),
+                            "Successfully changed database"
+                        );
+                        // Return success message
+                        let msg = format!("Successfully switched to database '{database}'");
+                        Ok(CallToolResult {
+                            content: vec![Content::text(msg)],
+                            is_error: None,
+                            meta: None,
+                            structured_content: None,
+                        })
+                    }
+                    Err(e) => {
+                        let duration = start_time.elapsed();
+                        // Output debugging information
+                        error!(
+                            connection_id = %self.connection_id,
+                            database = %database,
+                            duration_ms = duration.as_millis(),
+                            error = %e,
+                            "Failed to change database"
+                        );
+                        // Increment error metrics
+                        counter!("surrealmcp.total_errors").increment(1);
+                        counter!("surrealmcp.total_connection_errors").increment(1);
+                        counter!("surrealmcp.errors.use_database").increment(1);
+                        // Return error message
+                        Err(McpError::internal_error(
+                            format!("Failed to change database to '{database}': {e}"),
+                            None,
+                        ))
+                    }
+                }
+            }
+            None => {
+                // Output debugging information
+                warn!(
+                    connection_id = %self.connection_id,
+                    database = %database,
+                    "Database change attempted without database connection"
+                );
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.total_configuration_errors").increment(1);
+                counter!("surrealmcp.errors.no_connection").increment(1);
+                // Return error message
+                Err(McpError::internal_error(
+                    "Not connected to any SurrealDB endpoint. Use connect_endpoint first."
+                        .to_string(),
+                    None,
+                ))
+            }
+        }
+    }
+
+    /// Disconnect from the current SurrealDB endpoint.
+    ///
+    /// This function disconnects from the currently connected SurrealDB endpoint.
+    /// After disconnecting, you'll need to use connect_endpoint again to establish
+    /// a new connection before you can execute queries.
+    ///
+    /// # Arguments
+    /// * `connection` - The name of the connection to close (optional, defaults to the default connection)
+    #[tool(description = r#"
+Disconnect from the current SurrealDB endpoint.
+
+This function disconnects from the currently connected SurrealDB endpoint.
+After disconnecting, you'll need to use connect_endpoint again to establish
+a new connection before you can execute queries. Set connection to the name
+of a named connection to close only that connection.
+
+This is useful when you want to:
+- Switch to a different database
+- Clean up resources
+- Ensure no active connections remain
+"#)]
+    pub async fn disconnect_endpoint(
+        &self,
+        params: Parameters<DisconnectParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let DisconnectParams { connection } = params.0;
+        // Increment tool usage metrics
+        counter!("surrealmcp.tools.disconnect_endpoint").increment(1);
+        // Close the named connection, if specified
+        if let Some(name) = connection.filter(|v| v != DEFAULT_CONNECTION) {
+            // Remove the named connection
+            if self.named_connections.lock().await.remove(&name).is_none() {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "Unknown connection '{name}'. Use list_connections to list the open connections"
+                    ),
+                    None,
+                ));
+            }
+            // Output debugging information
+            info!(
+                connection_id = %self.connection_id,
+                connection = %name,
+                "Closed named SurrealDB connection"
+            );
+            // Return success message
+            return Ok(CallToolResult {
+                content: vec![Content::text(format!(
+                    "Successfully closed connection '{name}'"
+                ))],
+                is_error: None,
+                meta: None,
+                structured_content: None,
+            });
+        }
+        // Output debugging information
         info!(
             connection_id = %self.connection_id,
             "Disconnecting from SurrealDB endpoint"
@@ -1909,6 +3520,14 @@ This is useful when you want to:
         let mut db_guard = self.db.lock().await;
         // Set the database connection to None
         *db_guard = None;
+        // Release any pooled connection
+        self.set_pool_lease(None);
+        // Clear the endpoint, namespace, and database in use
+        self.update_target(|t| *t = SessionTarget::default());
+        // Stop any live queries on the connection
+        self.live_queries.clear();
+        // Roll back any transaction on the connection
+        self.transaction.clear().await;
         // Output debugging information
         info!(
             connection_id = %self.connection_id,
@@ -1934,23 +3553,165 @@ This is useful when you want to:
         &self,
         query_string: String,
         parameters: Option<HashMap<String, Value>>,
+    ) -> Result<engine::Response, McpError> {
+        self.query_on(None, query_string, parameters).await
+    }
+
+    /// Internal query function that executes a SurrealQL query on a connection.
+    ///
+    /// The query is executed on the named connection if specified, or on the
+    /// default connection otherwise.
+    async fn query_on(
+        &self,
+        connection: Option<&str>,
+        query_string: String,
+        parameters: Option<HashMap<String, Value>>,
+    ) -> Result<engine::Response, McpError> {
+        self.query_with_timeout(connection, query_string, parameters, self.query_timeout)
+            .await
+    }
+
+    /// Internal function that executes a mutating statement as a dry run.
+    ///
+    /// The statement is executed within a transaction which is always cancelled,
+    /// returning the id of each affected record along with its contents before
+    /// and after the statement, without persisting any changes.
+    async fn dry_run_internal(
+        &self,
+        connection: Option<&str>,
+        query_string: String,
+        parameters: Option<HashMap<String, Value>>,
+    ) -> Result<engine::Response, McpError> {
+        // Get the named connection to use, if specified
+        let named = self.named_connection(connection).await?;
+        // Dry runs can not be nested within an open transaction
+        if named.is_none() && self.transaction.is_open().await {
+            return Err(McpError::invalid_request(
+                "Cannot preview changes while a transaction is open. Use commit_transaction or rollback_transaction first.",
+                None,
+            ));
+        }
+        // Increment the query counter
+        let query_id = QUERY_COUNTER.fetch_add(1, Ordering::SeqCst);
+        // Get the names of the bound parameters for the audit log
+        let names = parameter_names(parameters.as_ref());
+        // Get a session for the connected database
+        let db = match &named {
+            Some((_, named)) => named.db.clone(),
+            None => self.session().await?,
+        };
+        // Execute the statement within a cancelled transaction
+        let res = engine::execute_dry_run(
+            &db,
+            query_id,
+            query_string,
+            parameters,
+            &self.connection_id,
+            self.read_only,
+            self.query_timeout,
+        )
+        .await;
+        // Record the query in the audit log
+        self.audit(
+            &res,
+            &names,
+            named.as_ref().map(|(name, _)| name.as_str()),
+            named.as_ref().map(|(_, v)| &v.target),
+        );
+        // Return the response
+        Ok(res)
+    }
+
+    /// Internal function that executes a query with a specific timeout.
+    ///
+    /// This function is used by the `query` tool when the default timeout is
+    /// overridden on the tool call. Queries on a named connection are executed
+    /// outside of any transaction open on the default connection.
+    async fn query_with_timeout(
+        &self,
+        connection: Option<&str>,
+        query_string: String,
+        parameters: Option<HashMap<String, Value>>,
+        timeout: Option<Duration>,
     ) -> Result<engine::Response, McpError> {
         // Increment the query counter
         let query_id = QUERY_COUNTER.fetch_add(1, Ordering::SeqCst);
+        // Get the names of the bound parameters for the audit log
+        let names = parameter_names(parameters.as_ref());
+        // Refuse USE statements when the namespace or database is restricted
+        if self.restricts_namespaces() && engine::check_use(&query_string) {
+            // Output debugging information
+            warn!(
+                connection_id = %self.connection_id,
+                query_id,
+                query = %query_string,
+                "Query refused: USE statements are not allowed by server configuration"
+            );
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.total_configuration_errors").increment(1);
+            // Return error message
+            return Err(McpError::invalid_params(
+                "USE statements are not allowed as the server restricts namespaces and databases. Use the use_namespace or use_database tools instead.",
+                None,
+            ));
+        }
+        // Execute the query on the named connection, if specified
+        if let Some((name, named)) = self.named_connection(connection).await? {
+            // Refuse USE statements, which would change the target of the connection
+            if engine::check_use(&query_string) {
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.total_configuration_errors").increment(1);
+                // Return error message
+                return Err(McpError::invalid_params(
+                    format!(
+                        "USE statements are not allowed on named connections. Use connect_endpoint to reconnect '{name}' to another namespace or database instead."
+                    ),
+                    None,
+                ));
+            }
+            // Execute the query on the engine
+            let res = engine::execute_query(
+                &named.db,
+                query_id,
+                query_string,
+                parameters,
+                &self.connection_id,
+                self.read_only,
+                timeout,
+            )
+            .await;
+            // Record the query in the audit log
+            self.audit(&res, &names, Some(&name), Some(&named.target));
+            // Return the response
+            return Ok(res);
+        }
         // Lock the database connection
         let db_guard = self.db.lock().await;
         // Match the database connection
         match &*db_guard {
             Some(db) => {
+                // Lock the open transaction, if any
+                let mut txn_guard = self.transaction.lock().await;
+                // Execute the query within the open transaction, if any
+                let executor = match txn_guard.as_mut() {
+                    Some(open) => engine::Executor::from(open.execute()),
+                    None => engine::Executor::from(db),
+                };
                 // Execute the query on the engine
                 let res = engine::execute_query(
-                    db,
+                    executor,
                     query_id,
                     query_string,
                     parameters,
                     &self.connection_id,
+                    self.read_only,
+                    timeout,
                 )
                 .await;
+                // Record the query in the audit log
+                self.audit(&res, &names, None, None);
                 // Return the response
                 Ok(res)
             }
@@ -1976,76 +3737,917 @@ This is useful when you want to:
         }
     }
 
-    /// Initialize the database connection using startup configuration.
+    /// Internal function that executes a query on a separate session.
     ///
-    /// This method attempts to connect to the database using the configuration
-    /// provided at startup. If no endpoint is configured, this method does nothing.
-    /// If an endpoint is configured, it will connect using the configured settings.
-    pub async fn initialize_connection(&self) -> Result<(), anyhow::Error> {
-        if let Some(endpoint) = &self.endpoint {
-            // Output debugging information
-            info!(
-                connection_id = %self.connection_id,
-                endpoint = %endpoint,
-                namespace = self.namespace.as_deref(),
-                database = self.database.as_deref(),
-                "Initializing database connection with startup configuration"
-            );
-            // Get the configured endpoint details
-            let user = self.user.as_deref();
-            let pass = self.pass.as_deref();
-            let ns = self.namespace.as_deref();
-            let db = self.database.as_deref();
-            // Create a new SurrealDB connection
-            match db::create_client_connection(endpoint, user, pass, ns, db).await {
-                Ok(instance) => {
-                    // Update the service's database connection
-                    let mut db_guard = self.db.lock().await;
-                    *db_guard = Some(instance);
-                    // Output debugging information
-                    info!(
-                        connection_id = %self.connection_id,
-                        endpoint = %endpoint,
-                        "Successfully initialized database connection"
-                    );
-                }
-                Err(e) => {
-                    // Output debugging information
-                    error!(
-                        connection_id = %self.connection_id,
-                        endpoint = %endpoint,
-                        error = %e,
-                        "Failed to initialize database connection"
-                    );
-                    // Increment error metrics
-                    counter!("surrealmcp.total_errors").increment(1);
-                    counter!("surrealmcp.total_connection_errors").increment(1);
-                    counter!("surrealmcp.errors.connect_endpoint").increment(1);
-                    // Return error message
-                    return Err(e);
-                }
+    /// The query is refused in read-only mode if it modifies data, is limited by
+    /// the query timeout, and is recorded in the audit log with the target which
+    /// the session is using.
+    async fn query_session(
+        &self,
+        db: &Surreal<Any>,
+        target: &SessionTarget,
+        query_string: String,
+    ) -> engine::Response {
+        // Increment the query counter
+        let query_id = QUERY_COUNTER.fetch_add(1, Ordering::SeqCst);
+        // Execute the query on the engine
+        let res = engine::execute_query(
+            db,
+            query_id,
+            query_string,
+            None,
+            &self.connection_id,
+            self.read_only,
+            self.query_timeout,
+        )
+        .await;
+        // Record the query in the audit log
+        self.audit(&res, &[], None, Some(target));
+        // Return the response
+        res
+    }
+
+    /// Internal function that reads a templated resource from the connected database.
+    ///
+    /// Resources with a namespace and database in their URI are read using a
+    /// separate session for that namespace and database, and other resources
+    /// using a separate session from the current connection.
+    async fn read_template_resource(
+        &self,
+        provider: &dyn resources::ResourceTemplateProvider,
+        uri: &str,
+        vars: &resources::TemplateVars,
+    ) -> Result<rmcp::model::ReadResourceResult, McpError> {
+        // Get the target which the session uses
+        let mut target = self
+            .target
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        // Get a session for the connected database
+        let db = match provider.scoped() {
+            true => {
+                target.namespace = Some(vars["ns"].clone());
+                target.database = Some(vars["db"].clone());
+                self.scoped_session(&vars["ns"], &vars["db"]).await?
             }
-        } else {
-            debug!(
-                connection_id = %self.connection_id,
-                "No endpoint configured for startup connection"
-            );
-        }
-        // All ok
-        Ok(())
+            false => self.session().await?,
+        };
+        // Read the resource using the session
+        let query = |q: String| -> BoxFuture<'_, engine::Response> {
+            Box::pin(self.query_session(&db, &target, q))
+        };
+        provider.read(&query, uri, vars).await
     }
-}
 
-#[tool_handler]
-impl ServerHandler for SurrealService {
-    /// Get the MCP server info
-    fn get_info(&self) -> ServerInfo {
+    /// Internal function that lists the schema resources of the database in use.
+    async fn schema_resources(&self) -> Vec<rmcp::model::Resource> {
+        // Get the target which the session uses
+        let target = self
+            .target
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        // Get a session for the connected database
+        let Some(db) = self.db.lock().await.clone() else {
+            return vec![];
+        };
+        // List the resources using the session
+        let query = |q: String| -> BoxFuture<'_, engine::Response> {
+            Box::pin(self.query_session(&db, &target, q))
+        };
+        resources::list_schema_resources(&query).await
+    }
+
+    /// Internal function that executes an INFO statement.
+    ///
+    /// This function executes the provided INFO statement and deserializes the
+    /// first statement result into the requested structure, returning an error
+    /// if the query fails or the output does not match the expected shape.
+    async fn info_internal<T: serde::de::DeserializeOwned>(
+        &self,
+        query_string: String,
+    ) -> Result<T, McpError> {
+        // Execute the INFO statement
+        let mut exec_res = self.query_internal(query_string, None).await?;
+        // Match the result of the query
+        match exec_res.result.as_mut() {
+            Some(response) => {
+                // Take the first element of the response as a generic Value
+                let value: Value = response
+                    .take(0)
+                    .map_err(|e: surrealdb::Error| McpError::internal_error(e.to_string(), None))?;
+                // Convert SurrealDB Value to JSON, then to our struct
+                serde_json::from_value(utils::surreal_to_json(value))
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))
+            }
+            None => {
+                let err = exec_res
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                Err(McpError::internal_error(err, None))
+            }
+        }
+    }
+
+    /// Internal function that creates a separate session from the current connection.
+    ///
+    /// The session uses the same authentication, namespace, and database as the
+    /// current connection, but does not hold the connection lock while it is used.
+    async fn session(&self) -> Result<Surreal<Any>, McpError> {
+        match &*self.db.lock().await {
+            Some(db) => Ok(db.clone()),
+            None => {
+                // Update the query errors metric
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.no_connection").increment(1);
+                // Return error message
+                Err(McpError::internal_error(
+                    "Not connected to any SurrealDB endpoint. Use connect_endpoint first."
+                        .to_string(),
+                    None,
+                ))
+            }
+        }
+    }
+
+    /// Internal function that creates a separate session using a specific namespace and database.
+    ///
+    /// The session is cloned from the current connection, so that it has the same
+    /// authentication, but changing its namespace and database does not affect the
+    /// namespace and database used by the tools.
+    async fn scoped_session(
+        &self,
+        namespace: &str,
+        database: &str,
+    ) -> Result<Surreal<Any>, McpError> {
+        // Check if the namespace and database are restricted by startup configuration
+        if !self.is_namespace_allowed(namespace) || !self.is_database_allowed(database) {
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.total_configuration_errors").increment(1);
+            // Return error message
+            return Err(McpError::invalid_params(
+                format!(
+                    "Cannot use namespace '{namespace}' and database '{database}'. They are not allowed by the server configuration"
+                ),
+                None,
+            ));
+        }
+        // Clone the database connection into a new session
+        let db = self.session().await?;
+        // Use the namespace and database in the new session
+        db.use_ns(namespace)
+            .use_db(database)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(db)
+    }
+
+    /// Internal function that fetches the structured definition of a table.
+    async fn table_structure(&self, table: &str) -> Result<TableStructure, McpError> {
+        // Escape the table name for use in the statement
+        let table = utils::parse_table(table);
+        // Fetch the structured table information
+        self.info_internal(format!("INFO FOR TABLE {table} STRUCTURE;"))
+            .await
+    }
+
+    /// Initialize the database connection using startup configuration.
+    ///
+    /// This method attempts to connect to the database using the configuration
+    /// provided at startup. If no endpoint is configured, this method does nothing.
+    /// If an endpoint is configured, it will connect using the configured settings.
+    pub async fn initialize_connection(&self) -> Result<(), anyhow::Error> {
+        if let Some(endpoint) = &self.endpoint {
+            // Output debugging information
+            info!(
+                connection_id = %self.connection_id,
+                endpoint = %endpoint,
+                namespace = self.namespace.as_deref(),
+                database = self.database.as_deref(),
+                "Initializing database connection with startup configuration"
+            );
+            // Get the configured endpoint details
+            let credentials = self
+                .credentials(endpoint, self.user.clone(), self.pass.clone())
+                .await;
+            let ns = self.namespace.as_deref();
+            let db = self.database.as_deref();
+            // Create a session on a pooled connection, or a new SurrealDB connection
+            let connection = match &self.pool {
+                Some(pool) => pool
+                    .acquire(endpoint, &credentials, ns, db)
+                    .await
+                    .map(|(instance, lease)| (instance, Some(lease))),
+                None => db::create_client_connection(endpoint, &credentials, ns, db)
+                    .await
+                    .map(|instance| (instance, None)),
+            };
+            match connection {
+                Ok((instance, lease)) => {
+                    // Update the service's database connection
+                    let mut db_guard = self.db.lock().await;
+                    *db_guard = Some(instance);
+                    // Release any previous pooled connection
+                    self.set_pool_lease(lease);
+                    // Update the endpoint, namespace, and database in use
+                    self.update_target(|t| {
+                        t.endpoint = Some(endpoint.clone());
+                        t.namespace = self.namespace.clone();
+                        t.database = self.database.clone();
+                        t.auth = Some(credentials.auth_level());
+                    });
+                    // Output debugging information
+                    info!(
+                        connection_id = %self.connection_id,
+                        endpoint = %endpoint,
+                        "Successfully initialized database connection"
+                    );
+                }
+                Err(e) => {
+                    // Output debugging information
+                    error!(
+                        connection_id = %self.connection_id,
+                        endpoint = %endpoint,
+                        error = %e,
+                        "Failed to initialize database connection"
+                    );
+                    // Increment error metrics
+                    counter!("surrealmcp.total_errors").increment(1);
+                    counter!("surrealmcp.total_connection_errors").increment(1);
+                    counter!("surrealmcp.errors.connect_endpoint").increment(1);
+                    // Return error message
+                    return Err(e);
+                }
+            }
+        } else {
+            debug!(
+                connection_id = %self.connection_id,
+                "No endpoint configured for startup connection"
+            );
+        }
+        // All ok
+        Ok(())
+    }
+
+    /// Authenticate a request using the identity from its bearer token, if any.
+    ///
+    /// The identity from a refreshed token replaces the identity of the session,
+    /// as long as it is for the same subject. Requests are refused once the token
+    /// for the session has expired.
+    async fn authenticate(
+        &self,
+        identity: Option<&Identity>,
+    ) -> Result<Option<Identity>, McpError> {
+        let mut current = self.identity.write().await;
+        // Store the identity from a refreshed token
+        if let Some(identity) = identity {
+            // Refuse tokens for a different subject than the session
+            if let Some(existing) = &*current
+                && existing.subject != identity.subject
+            {
+                // Output debugging information
+                warn!(
+                    connection_id = %self.connection_id,
+                    subject = ?identity.subject,
+                    session_subject = ?existing.subject,
+                    "Refused request with a token for a different subject"
+                );
+                // Update identity error metrics
+                counter!("surrealmcp.total_identity_errors").increment(1);
+                // Return an error to the client
+                return Err(McpError::invalid_request(
+                    "The bearer token is for a different subject than this session",
+                    None,
+                ));
+            }
+            *current = Some(identity.clone());
+        }
+        // Refuse requests once the token has expired
+        if let Some(identity) = &*current
+            && identity.is_expired()
+        {
+            // Output debugging information
+            warn!(
+                connection_id = %self.connection_id,
+                subject = ?identity.subject,
+                expires_at = ?identity.expires_at,
+                "Refused request with an expired token"
+            );
+            // Update expired token metrics
+            counter!("surrealmcp.total_expired_token_errors").increment(1);
+            // Return an error to the client
+            return Err(McpError::invalid_request(
+                "The bearer token for this session has expired. Reconnect with a new token.",
+                Some(serde_json::json!({ "expires_at": identity.expires_at })),
+            ));
+        }
+        Ok(current.clone())
+    }
+
+    /// Internal function that connects to an endpoint, replacing the current connection.
+    ///
+    /// Cloud endpoints (`cloud:instance_id`) are connected to using the auth
+    /// token of the cloud instance, while other endpoints use the credentials.
+    /// Callers record failures in the error metrics of their tool.
+    async fn connect_internal(
+        &self,
+        endpoint: String,
+        ns: Option<String>,
+        db: Option<String>,
+        credentials: db::Credentials,
+        name: Option<String>,
+        start_time: Instant,
+    ) -> Result<CallToolResult, McpError> {
+        // The default connection is replaced when no name is specified
+        let name = name.filter(|v| v != DEFAULT_CONNECTION);
+        // Check whether the named connection can be opened
+        if let Some(name) = &name {
+            Self::check_connection_name(name)?;
+        }
+        // Cloud connections authenticate with the token of the instance
+        let auth = if endpoint.starts_with("cloud:") {
+            "token"
+        } else {
+            credentials.auth_level()
+        };
+        // Check if this is a cloud connection
+        let connection = if endpoint.starts_with("cloud:") {
+            // Extract instance ID from cloud:instance_id format
+            let instance_id = endpoint.strip_prefix("cloud:").ok_or_else(|| {
+                McpError::internal_error(
+                    "Invalid cloud endpoint format. Expected 'cloud:instance_id'".to_string(),
+                    None,
+                )
+            })?;
+            // Get the instance details to get the host
+            let instance = match self.cloud_client.get_instance(instance_id).await {
+                Ok(instance) => instance,
+                Err(e) => {
+                    error!(
+                        connection_id = %self.connection_id,
+                        instance_id = %instance_id,
+                        error = %e,
+                        "Failed to get cloud instance details"
+                    );
+                    counter!("surrealmcp.total_errors").increment(1);
+                    counter!("surrealmcp.total_connection_errors").increment(1);
+                    return Err(McpError::internal_error(
+                        format!("Failed to get cloud instance details '{instance_id}': {e}"),
+                        None,
+                    ));
+                }
+            };
+            // Get the auth token for the cloud instance
+            let token = match self.cloud_client.get_instance_auth(instance_id).await {
+                Ok(token) => token,
+                Err(e) => {
+                    error!(
+                        connection_id = %self.connection_id,
+                        instance_id = %instance_id,
+                        error = %e,
+                        "Failed to get cloud instance token"
+                    );
+                    counter!("surrealmcp.total_errors").increment(1);
+                    counter!("surrealmcp.total_connection_errors").increment(1);
+                    return Err(McpError::internal_error(
+                        format!("Failed to get cloud instance auth token '{instance_id}': {e}"),
+                        None,
+                    ));
+                }
+            };
+            // Check if instance state is ready
+            if let Some(state) = &instance.state
+                && state != "ready"
+            {
+                error!(
+                    connection_id = %self.connection_id,
+                    instance_id = %instance_id,
+                    state = %state,
+                    "Cloud instance is not ready to connect"
+                );
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.total_connection_errors").increment(1);
+                return Err(McpError::internal_error(
+                    format!("Cloud instance '{instance_id}' is not ready (state: {state})"),
+                    None,
+                ));
+            }
+            // Get the host from the instance
+            let host = instance.host.ok_or_else(|| {
+                error!(
+                    connection_id = %self.connection_id,
+                    instance_id = %instance_id,
+                    "Cloud instance has no host information"
+                );
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.total_connection_errors").increment(1);
+                McpError::internal_error(
+                    format!("Cloud instance '{instance_id}' has no host information"),
+                    None,
+                )
+            })?;
+            // Create a new SurrealDB connection with the cloud token
+            db::create_client_connection_with_token(
+                &host,
+                &token,
+                credentials.username.as_deref(),
+                credentials.password.as_deref(),
+                ns.as_deref(),
+                db.as_deref(),
+            )
+            .await
+        } else {
+            // Create a new SurrealDB connection
+            db::create_client_connection(&endpoint, &credentials, ns.as_deref(), db.as_deref())
+                .await
+        };
+        // Create a new SurrealDB connection
+        match connection {
+            Ok(instance) => {
+                // Calculate the elapsed time
+                let duration = start_time.elapsed();
+                // Get the endpoint, namespace, and database of the connection
+                let target = SessionTarget {
+                    endpoint: Some(endpoint.clone()),
+                    namespace: ns.clone(),
+                    database: db.clone(),
+                    auth: Some(auth),
+                };
+                match &name {
+                    // Hold the connection open alongside the default connection
+                    Some(name) => {
+                        let mut named = self.named_connections.lock().await;
+                        // Check the number of open named connections while holding the lock
+                        Self::check_connection_count(&named, name)?;
+                        named.insert(
+                            name.clone(),
+                            NamedConnection {
+                                db: instance,
+                                target,
+                            },
+                        );
+                    }
+                    // Replace the default connection
+                    None => {
+                        // Update the service's database connection
+                        let mut db_guard = self.db.lock().await;
+                        *db_guard = Some(instance);
+                        // Release any previous pooled connection
+                        self.set_pool_lease(None);
+                        // Update the endpoint, namespace, and database in use
+                        self.update_target(|t| *t = target);
+                        // Stop any live queries on the previous connection
+                        self.live_queries.clear();
+                        // Roll back any transaction on the previous connection
+                        self.transaction.clear().await;
+                    }
+                }
+                // Output debugging information
+                info!(
+                    connection_id = %self.connection_id,
+                    endpoint = %endpoint,
+                    namespace = ns.as_deref(),
+                    database = db.as_deref(),
+                    connection = name.as_deref(),
+                    duration_ms = duration.as_millis(),
+                    "Successfully connected to SurrealDB endpoint"
+                );
+                // Return success message
+                let msg = match &name {
+                    Some(name) => format!(
+                        "Successfully connected to endpoint '{endpoint}' as connection '{name}'"
+                    ),
+                    None => format!("Successfully connected to endpoint '{endpoint}'"),
+                };
+                Ok(CallToolResult {
+                    content: vec![Content::text(msg)],
+                    is_error: None,
+                    meta: None,
+                    structured_content: None,
+                })
+            }
+            Err(e) => {
+                // Calculate the elapsed time
+                let duration = start_time.elapsed();
+                // Output debugging information
+                error!(
+                    connection_id = %self.connection_id,
+                    endpoint = %endpoint,
+                    namespace = ns.as_deref(),
+                    database = db.as_deref(),
+                    duration_ms = duration.as_millis(),
+                    error = %e,
+                    "Failed to connect to SurrealDB endpoint"
+                );
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.total_connection_errors").increment(1);
+                // Return error message
+                Err(McpError::internal_error(
+                    format!("Failed to connect to endpoint '{endpoint}': {e}"),
+                    None,
+                ))
+            }
+        }
+    }
+
+    /// Check whether a connection name can be used for a named connection.
+    fn check_connection_name(name: &str) -> Result<(), McpError> {
+        // Check that the connection name is not empty
+        if name.trim().is_empty() {
+            return Err(McpError::invalid_params(
+                "The connection name must not be empty".to_string(),
+                None,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check whether another named connection can be opened.
+    ///
+    /// A session can hold at most `MAX_NAMED_CONNECTIONS` named connections.
+    /// Opening a connection with the name of an open connection replaces that
+    /// connection.
+    fn check_connection_count(
+        named: &BTreeMap<String, NamedConnection>,
+        name: &str,
+    ) -> Result<(), McpError> {
+        // Check the number of open named connections
+        if !named.contains_key(name) && named.len() >= MAX_NAMED_CONNECTIONS {
+            // Increment error metrics
+            counter!("surrealmcp.total_errors").increment(1);
+            counter!("surrealmcp.total_connection_errors").increment(1);
+            // Return error message
+            return Err(McpError::invalid_request(
+                format!(
+                    "Cannot open more than {MAX_NAMED_CONNECTIONS} named connections. Use disconnect_endpoint to close a connection first."
+                ),
+                None,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the named connection to use for a tool call.
+    ///
+    /// Returns `None` if no connection name is specified, or if the name of the
+    /// default connection is specified, and an error if no connection is open
+    /// with the specified name.
+    async fn named_connection(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Option<(String, NamedConnection)>, McpError> {
+        // Use the default connection if no name is specified
+        let Some(name) = name.filter(|v| *v != DEFAULT_CONNECTION) else {
+            return Ok(None);
+        };
+        // Get the named connection
+        match self.named_connections.lock().await.get(name) {
+            Some(named) => Ok(Some((name.to_string(), named.clone()))),
+            None => {
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.errors.no_connection").increment(1);
+                // Return error message
+                Err(McpError::invalid_params(
+                    format!(
+                        "Unknown connection '{name}'. Use list_connections to list the open connections"
+                    ),
+                    None,
+                ))
+            }
+        }
+    }
+
+    /// Check whether an endpoint can be connected to.
+    ///
+    /// The endpoint configured at startup is always allowed. Other endpoints
+    /// must match the endpoint allow-list, which must not be empty if an
+    /// endpoint was configured at startup.
+    fn is_endpoint_allowed(&self, endpoint: &str) -> bool {
+        match &self.endpoint {
+            Some(v) if v == endpoint => true,
+            Some(_) if self.connections.allow_endpoints.is_empty() => false,
+            _ => self.connections.is_endpoint_allowed(endpoint),
+        }
+    }
+
+    /// Check whether a namespace can be used.
+    ///
+    /// The namespace configured at startup is always allowed. Other namespaces
+    /// must match the namespace allow-list, which must not be empty if a
+    /// namespace was configured at startup.
+    fn is_namespace_allowed(&self, namespace: &str) -> bool {
+        match &self.namespace {
+            Some(v) if v == namespace => true,
+            Some(_) if self.connections.allow_namespaces.is_empty() => false,
+            _ => self.connections.is_namespace_allowed(namespace),
+        }
+    }
+
+    /// Check whether a database can be used.
+    ///
+    /// The database configured at startup is always allowed. Other databases
+    /// must match the database allow-list, which must not be empty if a
+    /// database was configured at startup.
+    fn is_database_allowed(&self, database: &str) -> bool {
+        match &self.database {
+            Some(v) if v == database => true,
+            Some(_) if self.connections.allow_databases.is_empty() => false,
+            _ => self.connections.is_database_allowed(database),
+        }
+    }
+
+    /// Check whether the namespaces or databases which can be used are restricted.
+    fn restricts_namespaces(&self) -> bool {
+        self.namespace.is_some()
+            || self.database.is_some()
+            || !self.connections.allow_namespaces.is_empty()
+            || !self.connections.allow_databases.is_empty()
+    }
+
+    /// Get the credentials to authenticate with when connecting to an endpoint.
+    ///
+    /// The bearer token of the client is forwarded if token forwarding is
+    /// enabled, no username and password were specified for the connection, and
+    /// the endpoint is the one configured at startup, so that the token is never
+    /// sent to an endpoint chosen by the client. Otherwise the configured signin
+    /// level is used, with the authenticated subject passed to record access.
+    async fn credentials(
+        &self,
+        endpoint: &str,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> db::Credentials {
+        // Forward the bearer token to the configured endpoint
+        let token = if self.signin.forward_token
+            && username.is_none()
+            && self.endpoint.as_deref() == Some(endpoint)
+        {
+            self.token.read().await.clone()
+        } else {
+            None
+        };
+        db::Credentials {
+            level: self.signin.level,
+            username,
+            password,
+            access: self.signin.access.clone(),
+            subject: self.identity().await.and_then(|v| v.subject),
+            access_key: self.signin.access_key.clone(),
+            token,
+        }
+    }
+
+    /// Store the bearer token of the client, re-authenticating the connections
+    /// which were authenticated with the token if token forwarding is enabled.
+    async fn store_token(&self, token: &str) -> Result<(), McpError> {
+        // Check whether the token has changed
+        let previous = self.token.write().await.replace(token.to_string());
+        if previous.is_none_or(|v| v == token) || !self.signin.forward_token {
+            return Ok(());
+        }
+        // Only re-authenticate connections to the configured endpoint which
+        // were authenticated with the bearer token
+        let forwarded = |target: &SessionTarget| {
+            target.endpoint.is_some()
+                && target.endpoint == self.endpoint
+                && target.auth == Some("token")
+        };
+        // Get the default and named connections to re-authenticate
+        let mut sessions = Vec::new();
+        let target = self
+            .target
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if forwarded(&target)
+            && let Some(db) = self.db.lock().await.as_ref()
+        {
+            sessions.push(db.clone());
+        }
+        for named in self.named_connections.lock().await.values() {
+            if forwarded(&named.target) {
+                sessions.push(named.db.clone());
+            }
+        }
+        // Re-authenticate the connections with the refreshed token
+        for db in sessions {
+            db.authenticate(token.to_string()).await.map_err(|e| {
+                // Increment error metrics
+                counter!("surrealmcp.total_errors").increment(1);
+                counter!("surrealmcp.total_connection_errors").increment(1);
+                // Return an error to the client
+                McpError::internal_error(
+                    format!("Failed to authenticate with the refreshed bearer token: {e}"),
+                    None,
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Replace the lease on the pooled connection used by the default connection.
+    fn set_pool_lease(&self, lease: Option<PoolLease>) {
+        *self.pool_lease.lock().unwrap_or_else(|e| e.into_inner()) = lease;
+    }
+
+    /// Update the endpoint, namespace, and database which this session is using.
+    fn update_target(&self, f: impl FnOnce(&mut SessionTarget)) {
+        f(&mut self.target.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// Record an executed query in the audit log, if enabled.
+    ///
+    /// The entry includes the tool call and authenticated subject which the
+    /// query was executed for, along with the endpoint, namespace, and database
+    /// which the session was using at the time, or of the named connection which
+    /// the query was executed on, or of the separate session which the query
+    /// was executed on.
+    fn audit(
+        &self,
+        res: &engine::Response,
+        parameters: &[String],
+        connection: Option<&str>,
+        target: Option<&SessionTarget>,
+    ) {
+        // Check whether the audit log is enabled
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        // Get the current tool call and session target
+        let call = CallContext::current().unwrap_or_default();
+        let target = match target {
+            Some(target) => target.clone(),
+            None => self
+                .target
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        };
+        // Append the entry to the audit log
+        audit.record(&AuditEntry {
+            timestamp: audit::timestamp(),
+            connection_id: &self.connection_id,
+            subject: call.subject.as_deref(),
+            endpoint: target.endpoint.as_deref(),
+            namespace: target.namespace.as_deref(),
+            database: target.database.as_deref(),
+            connection,
+            tool: Some(call.tool.as_str()).filter(|v| !v.is_empty()),
+            query_id: res.query_id,
+            query: &res.query,
+            parameters: parameters.iter().map(String::as_str).collect(),
+            outcome: match res.error {
+                Some(_) => "error",
+                None => "ok",
+            },
+            error: res.error.as_deref(),
+            duration_ms: res.duration.as_millis(),
+        });
+    }
+
+    /// Ask the client user to confirm a destructive operation.
+    ///
+    /// The confirmation is requested using MCP elicitation. If the client does not
+    /// support elicitation, the operation is refused unless unconfirmed operations
+    /// are allowed by the confirmation policy.
+    async fn confirm_destructive(
+        &self,
+        tool: &str,
+        operation: &str,
+        peer: &Peer<RoleServer>,
+    ) -> Result<(), McpError> {
+        // Check whether confirmation is required
+        if self.confirmation.confirm == ConfirmDestructive::Never {
+            return Ok(());
+        }
+        // Output debugging information
+        info!(
+            connection_id = %self.connection_id,
+            tool = %tool,
+            operation = %operation,
+            "Requesting confirmation of destructive operation"
+        );
+        // Check whether the client supports elicitation
+        if !peer
+            .supported_elicitation_modes()
+            .contains(&ElicitationMode::Form)
+        {
+            // Run the operation if unconfirmed operations are allowed
+            if self.confirmation.allow_unconfirmed {
+                warn!(
+                    connection_id = %self.connection_id,
+                    tool = %tool,
+                    operation = %operation,
+                    "Running destructive operation without confirmation"
+                );
+                return Ok(());
+            }
+            // Update confirmation metrics
+            counter!("surrealmcp.total_unconfirmed_errors").increment(1);
+            // Return an error to the client
+            return Err(McpError::invalid_request(
+                format!(
+                    "Tool '{tool}' would {operation}, which requires confirmation, but the client does not support elicitation"
+                ),
+                Some(serde_json::json!({ "tool": tool })),
+            ));
+        }
+        // Ask the client user to confirm the operation
+        let message = format!(
+            "The '{tool}' tool would {operation}. This can not be undone. Do you want to continue?"
+        );
+        let res = peer
+            .elicit_with_timeout::<DestructiveConfirmation>(message, Some(CONFIRMATION_TIMEOUT))
+            .await;
+        // Check the response from the client user
+        match res {
+            Ok(Some(DestructiveConfirmation { confirm: true })) => {
+                // Update confirmation metrics
+                counter!("surrealmcp.total_confirmed_operations").increment(1);
+                Ok(())
+            }
+            Ok(_) | Err(ElicitationError::UserDeclined | ElicitationError::UserCancelled) => {
+                // Output debugging information
+                info!(
+                    connection_id = %self.connection_id,
+                    tool = %tool,
+                    "Destructive operation declined by client user"
+                );
+                // Update confirmation metrics
+                counter!("surrealmcp.total_declined_operations").increment(1);
+                // Return an error to the client
+                Err(McpError::invalid_request(
+                    format!("Tool '{tool}' was not run, as the user declined to {operation}"),
+                    Some(serde_json::json!({ "tool": tool })),
+                ))
+            }
+            Err(e) => {
+                // Output debugging information
+                warn!(
+                    connection_id = %self.connection_id,
+                    tool = %tool,
+                    error = %e,
+                    "Failed to request confirmation of destructive operation"
+                );
+                // Update confirmation metrics
+                counter!("surrealmcp.total_unconfirmed_errors").increment(1);
+                // Return an error to the client
+                Err(McpError::internal_error(
+                    format!(
+                        "Tool '{tool}' was not run, as confirmation could not be requested: {e}"
+                    ),
+                    Some(serde_json::json!({ "tool": tool })),
+                ))
+            }
+        }
+    }
+
+    /// Run a tool call, abandoning it if the request is cancelled by the client.
+    ///
+    /// The MCP service only cancels the request token when a cancellation
+    /// notification is received, so the tool call future is dropped here in
+    /// order to abort any in-flight query and release the database connection.
+    async fn cancellable<T>(
+        &self,
+        tool: &str,
+        ct: CancellationToken,
+        call: impl Future<Output = Result<T, McpError>>,
+    ) -> Result<T, McpError> {
+        tokio::select! {
+            res = call => res,
+            _ = ct.cancelled() => {
+                // Output debugging information
+                warn!(
+                    connection_id = %self.connection_id,
+                    tool = %tool,
+                    "Tool call cancelled by client"
+                );
+                // Update cancellation metrics
+                counter!("surrealmcp.total_cancelled_requests").increment(1);
+                // Return an error to the client
+                Err(McpError::internal_error(
+                    format!("Tool '{tool}' was cancelled"),
+                    Some(serde_json::json!({ "tool": tool })),
+                ))
+            }
+        }
+    }
+}
+
+impl ServerHandler for SurrealService {
+    /// Get the MCP server info
+    fn get_info(&self) -> ServerInfo {
         // Output debugging information
         debug!("Getting server info");
         // Get the server info
         ServerInfo {
             capabilities: ServerCapabilities::builder()
                 .enable_resources()
+                .enable_resources_subscribe()
                 .enable_prompts()
                 .enable_tools()
                 .build(),
@@ -2054,264 +4656,1875 @@ impl ServerHandler for SurrealService {
         }
     }
 
-    /// Initialize the MCP server
-    async fn initialize(
-        &self,
-        _req: rmcp::model::InitializeRequestParams,
-        ctx: RequestContext<RoleServer>,
-    ) -> Result<rmcp::model::InitializeResult, McpError> {
-        // Output debugging information
-        debug!("Initializing MCP server");
-        // Get the bearer token from the extensions
-        if let Some(parts) = ctx.extensions.get::<Parts>()
-            && let Some(token) = parts.extensions.get::<String>()
-        {
-            self.cloud_client
-                .client_token
-                .write()
-                .await
-                .replace(token.clone());
-        }
-        // Initialize the connection using startup configuration
-        if let Err(e) = self.initialize_connection().await {
-            error!(
-                connection_id = %self.connection_id,
-                error = %e,
-                "Failed to initialize database connection during MCP initialization"
-            );
-        }
-        Ok(self.get_info())
-    }
+    /// Initialize the MCP server
+    async fn initialize(
+        &self,
+        _req: rmcp::model::InitializeRequestParams,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::InitializeResult, McpError> {
+        // Output debugging information
+        debug!("Initializing MCP server");
+        // Get the bearer token from the extensions
+        if let Some(parts) = ctx.extensions.get::<Parts>()
+            && let Some(token) = parts.extensions.get::<String>()
+        {
+            self.cloud_client
+                .client_token
+                .write()
+                .await
+                .replace(token.clone());
+            self.token.write().await.replace(token.clone());
+        }
+        // Get the authenticated identity from the extensions
+        if let Some(parts) = ctx.extensions.get::<Parts>()
+            && let Some(identity) = parts.extensions.get::<Identity>()
+        {
+            self.identity.write().await.replace(identity.clone());
+        }
+        // Initialize the connection using startup configuration
+        if let Err(e) = self.initialize_connection().await {
+            error!(
+                connection_id = %self.connection_id,
+                error = %e,
+                "Failed to initialize database connection during MCP initialization"
+            );
+        }
+        Ok(self.get_info())
+    }
+
+    /// Call an MCP server tool
+    async fn call_tool(
+        &self,
+        req: rmcp::model::CallToolRequestParams,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        // Get the HTTP request parts, if any
+        let parts = ctx.extensions.get::<Parts>();
+        // Refuse calls once the bearer token has expired
+        let identity = self
+            .authenticate(parts.and_then(|v| v.extensions.get::<Identity>()))
+            .await?;
+        // Store any refreshed bearer token for cloud and database requests
+        if let Some(token) = parts.and_then(|v| v.extensions.get::<String>()) {
+            self.cloud_client
+                .client_token
+                .write()
+                .await
+                .replace(token.clone());
+            self.store_token(token).await?;
+        }
+        // Refuse calls to tools which have been disabled
+        if self.disabled_tools.contains(req.name.as_ref()) {
+            // Output debugging information
+            warn!(
+                connection_id = %self.connection_id,
+                tool = %req.name,
+                "Refused call to disabled tool"
+            );
+            // Update disabled tool error metrics
+            counter!("surrealmcp.total_disabled_tool_errors").increment(1);
+            // Return an error to the client
+            return Err(McpError::invalid_request(
+                format!("Tool '{}' is disabled on this server", req.name),
+                Some(serde_json::json!({ "tool": req.name })),
+            ));
+        }
+        // Get the name of the tool and the request cancellation token
+        let name = req.name.to_string();
+        let ct = ctx.ct.clone();
+        // Ask the client user to confirm destructive operations
+        if let Some(operation) = destructive_operation(&name, req.arguments.as_ref()) {
+            let confirm = self.confirm_destructive(&name, &operation, &ctx.peer);
+            self.cancellable(&name, ct.clone(), confirm).await?;
+        }
+        // Record the tool call for any queries which it executes
+        let call = CallContext {
+            tool: name.clone(),
+            subject: identity.and_then(|v| v.subject),
+        };
+        // Call the tool using the tool router
+        let tcc = ToolCallContext::new(self, req, ctx);
+        call.scope(self.cancellable(&name, ct, self.tool_router.call(tcc)))
+            .await
+    }
+
+    /// List the MCP server tools
+    async fn list_tools(
+        &self,
+        _req: Option<rmcp::model::PaginatedRequestParams>,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListToolsResult, McpError> {
+        // Output debugging information
+        debug!("Listing available tools");
+        // Return the enabled tools
+        Ok(rmcp::model::ListToolsResult {
+            tools: self.tool_router.list_all(),
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    /// Get an MCP server tool
+    fn get_tool(&self, name: &str) -> Option<rmcp::model::Tool> {
+        self.tool_router.get(name).cloned()
+    }
+
+    /// List the MCP server prompts
+    async fn list_prompts(
+        &self,
+        _req: Option<rmcp::model::PaginatedRequestParams>,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListPromptsResult, McpError> {
+        // Output debugging information
+        debug!("Listing available prompts");
+        // Get prompts from the prompts module
+        let prompts = prompts::list_prompts();
+        // Return the prompts
+        Ok(rmcp::model::ListPromptsResult {
+            prompts,
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    /// Get an MCP server prompt
+    async fn get_prompt(
+        &self,
+        req: rmcp::model::GetPromptRequestParams,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::GetPromptResult, McpError> {
+        // Output debugging information
+        debug!(prompt_name = %req.name, "Getting prompt");
+        // Get prompt from the prompts module
+        match prompts::get_prompt_with_arguments(&req.name, req.arguments) {
+            Some((description, messages)) => Ok(rmcp::model::GetPromptResult {
+                description: Some(description),
+                messages,
+            }),
+            None => Err(McpError::internal_error(
+                format!("Unknown prompt: {}", req.name),
+                None,
+            )),
+        }
+    }
+
+    /// List the MCP server resources
+    async fn list_resources(
+        &self,
+        _req: Option<rmcp::model::PaginatedRequestParams>,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListResourcesResult, McpError> {
+        // Output debugging information
+        debug!("Listing available prompts");
+        // Get resources from the resources module
+        let mut resources = resources::list_resources();
+        // Add the live queries running within this session
+        resources.extend(self.live_queries.resources());
+        // Add the schema resources of the database which this session is using
+        resources.extend(self.schema_resources().await);
+        // Return the resources
+        Ok(rmcp::model::ListResourcesResult {
+            resources,
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    /// Get an MCP server resource
+    async fn read_resource(
+        &self,
+        req: rmcp::model::ReadResourceRequestParams,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ReadResourceResult, McpError> {
+        // Output debugging information
+        debug!(resource_uri = %req.uri, "Reading resource");
+        // Read the notifications of a live query
+        if let Some(id) = live::parse_live_uri(&req.uri) {
+            return self.live_queries.read(id).ok_or_else(|| {
+                McpError::resource_not_found(format!("Unknown resource: {}", req.uri), None)
+            });
+        }
+        // Read a templated resource from the connected database
+        if let Some((provider, vars)) = resources::ResourceTemplateRegistry::find_by_uri(&req.uri) {
+            return self
+                .read_template_resource(provider.as_ref(), &req.uri, &vars)
+                .await;
+        }
+        // Get resource from the resources module
+        match resources::read_resource(&req.uri) {
+            Some(resource) => Ok(resource),
+            None => Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", req.uri),
+                Some(serde_json::json!({ "uri": req.uri })),
+            )),
+        }
+    }
+
+    /// List the MCP server resource templates
+    async fn list_resource_templates(
+        &self,
+        _req: Option<rmcp::model::PaginatedRequestParams>,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListResourceTemplatesResult, McpError> {
+        // Output debugging information
+        debug!("Listing available resource templates");
+        // Return the resource templates
+        Ok(rmcp::model::ListResourceTemplatesResult {
+            resource_templates: resources::list_resource_templates(),
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    /// Subscribe to updates of an MCP server resource
+    async fn subscribe(
+        &self,
+        req: rmcp::model::SubscribeRequestParams,
+        ctx: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        // Output debugging information
+        debug!(resource_uri = %req.uri, "Subscribing to resource");
+        // Send live query notifications to this client
+        if let Some(id) = live::parse_live_uri(&req.uri) {
+            return match self.live_queries.subscribe(id, ctx.peer) {
+                true => Ok(()),
+                false => Err(McpError::resource_not_found(
+                    format!("Unknown resource: {}", req.uri),
+                    None,
+                )),
+            };
+        }
+        // Static resources never change, so there is nothing to send
+        match resources::read_resource(&req.uri) {
+            Some(_) => Ok(()),
+            None => Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", req.uri),
+                None,
+            )),
+        }
+    }
+
+    /// Unsubscribe from updates of an MCP server resource
+    async fn unsubscribe(
+        &self,
+        req: rmcp::model::UnsubscribeRequestParams,
+        _ctx: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        // Output debugging information
+        debug!(resource_uri = %req.uri, "Unsubscribing from resource");
+        // Stop sending live query notifications to this client
+        if let Some(id) = live::parse_live_uri(&req.uri) {
+            self.live_queries.unsubscribe(id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::generate_connection_id;
+    use rmcp::model::ResourceContents;
+
+    async fn setup_service() -> SurrealService {
+        let service = SurrealService::with_config(
+            generate_connection_id(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        // Connect to memory
+        service
+            .connect_endpoint(Parameters(ConnectParams {
+                endpoint: "memory".to_string(),
+                namespace: Some("test_ns".to_string()),
+                database: Some("test_db".to_string()),
+                username: None,
+                password: None,
+                connection: None,
+            }))
+            .await
+            .expect("Failed to connect to memory");
+        service
+    }
+
+    #[tokio::test]
+    async fn test_tool_query_simple() {
+        let service = setup_service().await;
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "RETURN 42".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+
+        assert!(!res.is_error.unwrap_or(false));
+        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
+        assert!(
+            result_str.contains("42"),
+            "Expected text content containing '42'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_crud_flow() {
+        let service = setup_service().await;
+
+        // 1. Create
+        let res = service
+            .create(Parameters(CreateParams {
+                target: "person:john".to_string(),
+                data: serde_json::from_value(serde_json::json!({ "name": "John Doe", "age": 30 }))
+                    .unwrap(),
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Create failed");
+        assert!(!res.is_error.unwrap_or(false));
+
+        // 2. Select
+        let res = service
+            .select(Parameters(SelectParams {
+                targets: vec!["person:john".to_string()],
+                where_clause: None,
+                split_clause: None,
+                group_clause: None,
+                order_clause: None,
+                limit_clause: None,
+                start_clause: None,
+                parameters: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Select failed");
+        assert!(!res.is_error.unwrap_or(false));
+
+        // 3. Update
+        let res = service
+            .update(Parameters(UpdateParams {
+                targets: vec!["person:john".to_string()],
+                merge_data: Some(serde_json::from_value(serde_json::json!({ "age": 31 })).unwrap()),
+                patch_data: None,
+                content_data: None,
+                replace_data: None,
+                where_clause: None,
+                parameters: None,
+                encoding: None,
+                dry_run: None,
+                connection: None,
+            }))
+            .await
+            .expect("Update failed");
+        assert!(!res.is_error.unwrap_or(false));
+
+        // 4. Delete
+        let res = service
+            .delete(Parameters(DeleteParams {
+                targets: vec!["person:john".to_string()],
+                where_clause: None,
+                parameters: None,
+                encoding: None,
+                dry_run: None,
+                connection: None,
+            }))
+            .await
+            .expect("Delete failed");
+        assert!(!res.is_error.unwrap_or(false));
+    }
+
+    #[tokio::test]
+    async fn test_tool_relate() {
+        let service = setup_service().await;
+
+        // Create persons
+        service
+            .query(Parameters(QueryParams {
+                query: "CREATE person:a, person:b".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .unwrap();
+
+        // Relate
+        let res = service
+            .relate(Parameters(RelateParams {
+                from: vec!["person:a".to_string()],
+                with: vec!["person:b".to_string()],
+                table: "knows".to_string(),
+                content_data: Some(
+                    serde_json::from_value(serde_json::json!({ "since": 2024 })).unwrap(),
+                ),
+                parameters: None,
+                encoding: None,
+                dry_run: None,
+                connection: None,
+            }))
+            .await
+            .expect("Relate failed");
+
+        assert!(!res.is_error.unwrap_or(false));
+    }
+
+    #[tokio::test]
+    async fn test_tool_metadata() {
+        let service = setup_service().await;
+
+        // List Namespaces
+        let res = service
+            .list_namespaces(Parameters(CloudParams {}))
+            .await
+            .expect("List namespaces failed");
+        assert!(!res.is_error.unwrap_or(false));
+
+        // List Databases
+        let res = service
+            .list_databases(Parameters(CloudParams {}))
+            .await
+            .expect("List databases failed");
+        assert!(!res.is_error.unwrap_or(false));
+    }
+
+    #[tokio::test]
+    async fn test_tool_structured_content() {
+        let service = setup_service().await;
+
+        // Fixed-shape tools should declare output schemas
+        for name in [
+            "list_namespaces",
+            "list_databases",
+            "list_cloud_organizations",
+            "list_cloud_instances",
+            "get_cloud_instance_status",
+            "create_cloud_instance",
+            "pause_cloud_instance",
+            "resume_cloud_instance",
+        ] {
+            let tool = service.get_tool(name).expect("Tool not found");
+            assert!(tool.output_schema.is_some(), "{name} has no output schema");
+        }
+        let schema = service
+            .get_tool("list_databases")
+            .and_then(|tool| tool.output_schema)
+            .unwrap();
+        assert!(schema["properties"]["databases"].is_object());
+        assert!(schema["properties"]["count"].is_object());
+
+        // Fixed-shape results should match the text content
+        let res = service
+            .list_databases(Parameters(CloudParams {}))
+            .await
+            .expect("List databases failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["count"], 1);
+        assert_eq!(structured["databases"][0]["name"], "test_db");
+        let rmcp::model::RawContent::Text(text) = &res.content[0].raw else {
+            panic!("Expected text content");
+        };
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&text.text).unwrap(),
+            structured
+        );
+
+        // Query results should be returned as structured content
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "RETURN [1, 2]; RETURN { a: 1 };".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(
+            structured["statements"][0]["result"],
+            serde_json::json!([1, 2])
+        );
+        assert_eq!(structured["statements"][1]["result"]["a"], 1);
+
+        // First-result mode should wrap the result in an object
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "RETURN [1, 2]".to_string(),
+                parameters: None,
+                first_result_only: Some(true),
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured, serde_json::json!({ "result": [1, 2] }));
+    }
+
+    #[tokio::test]
+    async fn test_tool_schema_introspection() {
+        let service = setup_service().await;
+
+        // Define a schema
+        service
+            .query(Parameters(QueryParams {
+                query: r#"
+                    DEFINE ANALYZER simple TOKENIZERS blank FILTERS lowercase;
+                    DEFINE TABLE person SCHEMAFULL;
+                    DEFINE FIELD name ON person TYPE string DEFAULT 'anon' ASSERT $value != '';
+                    DEFINE INDEX idx_name ON person FIELDS name UNIQUE;
+                    DEFINE EVENT audit ON person WHEN $event = 'CREATE' THEN (CREATE log);
+                "#
+                .to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .unwrap();
+
+        // List Tables
+        let res = service
+            .list_tables(Parameters(CloudParams {}))
+            .await
+            .expect("List tables failed");
+        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
+        assert!(result_str.contains("person"));
+
+        // Describe Table
+        let res = service
+            .describe_table(Parameters(TableParams {
+                table: "person".to_string(),
+            }))
+            .await
+            .expect("Describe table failed");
+        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
+        assert!(result_str.contains("idx_name"));
+        assert!(result_str.contains("audit"));
+        assert!(result_str.contains("anon"));
+
+        // List Fields
+        let res = service
+            .list_fields(Parameters(TableParams {
+                table: "person".to_string(),
+            }))
+            .await
+            .expect("List fields failed");
+        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
+        assert!(result_str.contains("string"));
+
+        // List Indexes
+        let res = service
+            .list_indexes(Parameters(TableParams {
+                table: "person".to_string(),
+            }))
+            .await
+            .expect("List indexes failed");
+        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
+        assert!(result_str.contains("UNIQUE"));
+
+        // List Analyzers
+        let res = service
+            .list_analyzers(Parameters(CloudParams {}))
+            .await
+            .expect("List analyzers failed");
+        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
+        assert!(result_str.contains("simple"));
+
+        // Describe a missing table
+        let res = service
+            .describe_table(Parameters(TableParams {
+                table: "missing".to_string(),
+            }))
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tool_read_only_mode() {
+        let service = setup_service().await.with_read_only(true);
+
+        // Mutating tools should be hidden
+        assert!(!service.tool_router.has_route("create"));
+        assert!(!service.tool_router.has_route("delete"));
+        assert!(!service.tool_router.has_route("create_cloud_instance"));
+        assert!(!service.tool_router.has_route("live_query"));
+        assert!(service.tool_router.has_route("query"));
+        assert!(service.tool_router.has_route("select"));
+
+        // Read-only queries should succeed
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "INFO FOR DB; RETURN 1 + 1;".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await;
+        assert!(res.is_ok());
+
+        // Mutating queries should be refused
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "DEFINE TABLE person".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await;
+        assert!(res.is_err());
+
+        // Mutating tools called directly should also be refused
+        let res = service
+            .create(Parameters(CreateParams {
+                target: "person:john".to_string(),
+                data: serde_json::Map::new(),
+                encoding: None,
+                connection: None,
+            }))
+            .await;
+        assert!(res.is_err());
+
+        // Dry runs of mutating tools should also be refused
+        let res = service
+            .delete(Parameters(DeleteParams {
+                targets: vec!["person".to_string()],
+                where_clause: None,
+                parameters: None,
+                encoding: None,
+                dry_run: Some(true),
+                connection: None,
+            }))
+            .await;
+        assert!(res.unwrap_err().message.contains("read-only mode"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_allow_deny_lists() {
+        let tools = ToolsConfig {
+            allow: vec!["query".into(), "select".into(), "create".into()],
+            deny: vec!["create".into()],
+        };
+        let service = setup_service().await.with_tools_config(&tools);
+
+        // Only allowed tools should be exposed
+        let mut names: Vec<String> = service
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["query", "select"]);
+
+        // Filtered tools should be marked as disabled
+        assert!(service.disabled_tools.contains("create"));
+        assert!(service.disabled_tools.contains("connect_endpoint"));
+        assert!(service.disabled_tools.contains("create_cloud_instance"));
+        assert!(!service.disabled_tools.contains("query"));
+        assert!(service.get_tool("create").is_none());
+        assert!(service.get_tool("query").is_some());
+
+        // Read-only mode should combine with the tool lists
+        let service = setup_service()
+            .await
+            .with_read_only(true)
+            .with_tools_config(&ToolsConfig {
+                allow: vec![],
+                deny: vec!["connect_endpoint".into()],
+            });
+        assert!(service.disabled_tools.contains("delete"));
+        assert!(service.disabled_tools.contains("connect_endpoint"));
+        assert!(service.tool_router.has_route("select"));
+
+        // Tool lists must only reference existing tools
+        assert!(SurrealService::check_tools_config(&tools).is_ok());
+        let res = SurrealService::check_tools_config(&ToolsConfig {
+            allow: vec!["query".into(), "drop_everything".into()],
+            deny: vec![],
+        });
+        assert!(res.unwrap_err().to_string().contains("drop_everything"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_typed_encoding() {
+        let service = setup_service().await;
+
+        // Create a record using typed parameters
+        let res = service
+            .create(Parameters(CreateParams {
+                target: "person:john".to_string(),
+                data: serde_json::from_value(serde_json::json!({
+                    "name": "person:jane",
+                    "friend": { "$record": "person:jane" },
+                    "born": { "$datetime": "1990-01-15T10:30:00Z" },
+                }))
+                .unwrap(),
+                encoding: Some(JsonEncoding::Typed),
+                connection: None,
+            }))
+            .await
+            .expect("Create failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        let record = &structured["result"][0];
+        assert_eq!(record["id"]["$record"]["table"], "person");
+        assert_eq!(record["id"]["$record"]["key"], "john");
+        assert_eq!(record["name"], "person:jane");
+        assert_eq!(record["friend"]["$record"]["key"], "jane");
+        assert_eq!(record["born"]["$datetime"], "1990-01-15T10:30:00Z");
+
+        // The values should have been stored with their types
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "RETURN [type::is_record(person:john.friend), type::is_datetime(person:john.born)]"
+                    .to_string(),
+                parameters: None,
+                first_result_only: Some(true),
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        assert_eq!(
+            res.structured_content.unwrap()["result"],
+            serde_json::json!([true, true])
+        );
+
+        // The simple encoding flattens the values into strings
+        let res = service
+            .select(Parameters(SelectParams {
+                targets: vec!["person:john".to_string()],
+                where_clause: None,
+                split_clause: None,
+                group_clause: None,
+                order_clause: None,
+                limit_clause: None,
+                start_clause: None,
+                parameters: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Select failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["result"][0]["friend"], "person:jane");
+
+        // The server-wide default encoding should be used when not specified
+        let service = service.with_json_encoding(JsonEncoding::Typed);
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "SELECT friend FROM ONLY $id".to_string(),
+                parameters: Some(
+                    serde_json::from_value(
+                        serde_json::json!({ "id": { "$record": "person:john" } }),
+                    )
+                    .unwrap(),
+                ),
+                first_result_only: Some(true),
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["result"]["friend"]["$record"]["key"], "jane");
+    }
+
+    #[tokio::test]
+    async fn test_tool_next_page() {
+        let service = setup_service().await.with_result_limits(ResultLimits {
+            max_rows: 2,
+            max_bytes: 0,
+            max_buffered_bytes: 0,
+        });
 
-    /// List the MCP server prompts
-    async fn list_prompts(
-        &self,
-        _req: Option<rmcp::model::PaginatedRequestParams>,
-        _ctx: RequestContext<RoleServer>,
-    ) -> Result<rmcp::model::ListPromptsResult, McpError> {
-        // Output debugging information
-        debug!("Listing available prompts");
-        // Get prompts from the prompts module
-        let prompts = prompts::list_prompts();
-        // Return the prompts
-        Ok(rmcp::model::ListPromptsResult {
-            prompts,
-            next_cursor: None,
-            meta: None,
-        })
-    }
+        // Large results should be truncated with a cursor
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "RETURN [1, 2, 3, 4, 5]".to_string(),
+                parameters: None,
+                first_result_only: Some(true),
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["result"], serde_json::json!([1, 2]));
+        assert_eq!(structured["remaining_rows"], 3);
+        let cursor = structured["next_cursor"].as_str().unwrap().to_string();
 
-    /// Get an MCP server prompt
-    async fn get_prompt(
-        &self,
-        req: rmcp::model::GetPromptRequestParams,
-        _ctx: RequestContext<RoleServer>,
-    ) -> Result<rmcp::model::GetPromptResult, McpError> {
-        // Output debugging information
-        debug!(prompt_name = %req.name, "Getting prompt");
-        // Get prompt from the prompts module
-        match prompts::get_prompt_with_arguments(&req.name, req.arguments) {
-            Some((description, messages)) => Ok(rmcp::model::GetPromptResult {
-                description: Some(description),
-                messages,
-            }),
-            None => Err(McpError::internal_error(
-                format!("Unknown prompt: {}", req.name),
-                None,
-            )),
-        }
-    }
+        // The next page should continue from the cursor
+        let res = service
+            .next_page(Parameters(NextPageParams {
+                cursor: cursor.clone(),
+            }))
+            .await
+            .expect("Next page failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["result"], serde_json::json!([3, 4]));
+        assert_eq!(structured["remaining_rows"], 1);
 
-    /// List the MCP server resources
-    async fn list_resources(
-        &self,
-        _req: Option<rmcp::model::PaginatedRequestParams>,
-        _ctx: RequestContext<RoleServer>,
-    ) -> Result<rmcp::model::ListResourcesResult, McpError> {
-        // Output debugging information
-        debug!("Listing available prompts");
-        // Get resources from the resources module
-        let resources = resources::list_resources();
-        // Return the resources
-        Ok(rmcp::model::ListResourcesResult {
-            resources,
-            next_cursor: None,
-            meta: None,
-        })
+        // A used cursor should be rejected
+        let err = service
+            .next_page(Parameters(NextPageParams { cursor }))
+            .await
+            .expect_err("Used cursor should be rejected");
+        assert!(err.message.contains("does not exist or has expired"));
+
+        // The last page should not include a cursor
+        let cursor = structured["next_cursor"].as_str().unwrap().to_string();
+        let res = service
+            .next_page(Parameters(NextPageParams { cursor }))
+            .await
+            .expect("Next page failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["result"], serde_json::json!([5]));
+        assert!(structured["next_cursor"].is_null());
     }
 
-    /// Get an MCP server resource
-    async fn read_resource(
-        &self,
-        req: rmcp::model::ReadResourceRequestParams,
-        _ctx: RequestContext<RoleServer>,
-    ) -> Result<rmcp::model::ReadResourceResult, McpError> {
-        // Output debugging information
-        debug!(resource_uri = %req.uri, "Reading resource");
-        // Get resource from the resources module
-        match resources::read_resource(&req.uri) {
-            Some(resource) => Ok(resource),
-            None => Err(McpError::internal_error(
-                format!("Unknown resource: {}", req.uri),
-                None,
-            )),
-        }
+    #[tokio::test]
+    async fn test_tool_query_timeout_and_cancellation() {
+        let service = setup_service()
+            .await
+            .with_query_timeout(Some(Duration::from_secs(30)));
+
+        // A per-call timeout should override the server default
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "SLEEP 5s".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: Some(1),
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect_err("Query should time out");
+        assert!(res.message.contains("timed out after 1.000s"));
+
+        // A per-call timeout can not extend the server default
+        let service = service.with_query_timeout(Some(Duration::from_secs(1)));
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "SLEEP 5s".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: Some(60),
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect_err("Query should time out");
+        assert!(res.message.contains("timed out after 1.000s"));
+
+        // A per-call timeout can not disable the server default
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "SLEEP 5s".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: Some(0),
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect_err("Query should be refused");
+        assert!(res.message.contains("can not be disabled"));
+        let service = service.with_query_timeout(Some(Duration::from_secs(30)));
+
+        // A cancelled tool call should release the database connection
+        let ct = CancellationToken::new();
+        let cancel = ct.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        });
+        let start = Instant::now();
+        let call = service.query(Parameters(QueryParams {
+            query: "SLEEP 5s".to_string(),
+            parameters: None,
+            first_result_only: None,
+            timeout: None,
+            encoding: None,
+            connection: None,
+        }));
+        let err = service
+            .cancellable("query", ct, call)
+            .await
+            .expect_err("Query should be cancelled");
+        assert!(err.message.contains("cancelled"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Subsequent queries should not be blocked
+        service
+            .query(Parameters(QueryParams {
+                query: "RETURN 1".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::utils::generate_connection_id;
+    #[tokio::test]
+    async fn test_tool_explain() {
+        let service = setup_service().await;
+        service
+            .query(Parameters(QueryParams {
+                query: "DEFINE INDEX idx_email ON person FIELDS email; CREATE person:john SET email = 'john@example.com';".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
 
-    async fn setup_service() -> SurrealService {
-        let service = SurrealService::with_config(
-            generate_connection_id(),
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
+        // The plan should show which index was used
+        let res = service
+            .explain(Parameters(ExplainParams {
+                query: "SELECT * FROM person WHERE email = 'john@example.com' LIMIT $limit"
+                    .to_string(),
+                parameters: serde_json::from_value(serde_json::json!({ "limit": 10 })).unwrap(),
+                full: Some(true),
+            }))
+            .await
+            .expect("Explain failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert!(
+            structured["query"]
+                .as_str()
+                .unwrap()
+                .ends_with("EXPLAIN FULL")
         );
-        // Connect to memory
+        assert!(structured["duration"].is_string());
+        let plan = structured["plan"].to_string();
+        assert!(plan.contains("idx_email"), "Missing index in plan: {plan}");
+        assert!(
+            plan.contains("output_rows"),
+            "Missing metrics in plan: {plan}"
+        );
+
+        // Statements which modify data should not be executed
+        let err = service
+            .explain(Parameters(ExplainParams {
+                query: "DELETE person".to_string(),
+                parameters: None,
+                full: Some(true),
+            }))
+            .await
+            .expect_err("Explain should be refused");
+        assert!(err.message.contains("do not modify data"));
+        let res = service
+            .query(Parameters(QueryParams {
+                query: "SELECT VALUE id FROM person".to_string(),
+                parameters: None,
+                first_result_only: Some(true),
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        assert_eq!(structured["result"], serde_json::json!(["person:john"]));
+
+        // The plan should use the JSON encoding of the server
+        let service = service.with_json_encoding(JsonEncoding::Typed);
+        let res = service
+            .explain(Parameters(ExplainParams {
+                query: "SELECT * FROM person:john".to_string(),
+                parameters: None,
+                full: Some(true),
+            }))
+            .await
+            .expect("Explain failed");
+        let plan = &res.structured_content.unwrap()["plan"];
+        assert_eq!(plan["children"][0]["operator"], "RecordIdScan");
+        assert_eq!(plan["metrics"]["output_rows"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_live_query() {
+        let service = setup_service().await.with_max_live_queries(1);
+
+        // Only LIVE SELECT statements can be started
+        let err = service
+            .live_query(Parameters(LiveQueryParams {
+                query: "SELECT * FROM person".to_string(),
+                parameters: None,
+                encoding: None,
+            }))
+            .await
+            .expect_err("Live query should be refused");
+        assert!(err.message.contains("LIVE SELECT"));
+
+        // Start a live query and check its resource
         service
-            .connect_endpoint(Parameters(ConnectParams {
-                endpoint: "memory".to_string(),
-                namespace: Some("test_ns".to_string()),
-                database: Some("test_db".to_string()),
-                username: None,
-                password: None,
+            .query(Parameters(QueryParams {
+                query: "DEFINE TABLE person SCHEMALESS".to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
             }))
             .await
-            .expect("Failed to connect to memory");
+            .expect("Query failed");
+        let res = service
+            .live_query(Parameters(LiveQueryParams {
+                query: "LIVE SELECT * FROM person WHERE age > $min_age".to_string(),
+                parameters: serde_json::from_value(serde_json::json!({ "min_age": 18 })).unwrap(),
+                encoding: None,
+            }))
+            .await
+            .expect("Live query failed");
+        let structured = res.structured_content.expect("Missing structured content");
+        let id = structured["id"].as_str().unwrap().to_string();
+        assert_eq!(structured["uri"], format!("surrealmcp://live/{id}"));
+        let resources = service.live_queries.resources();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, format!("surrealmcp://live/{id}"));
+
+        // The number of concurrent live queries is limited
+        let err = service
+            .live_query(Parameters(LiveQueryParams {
+                query: "LIVE SELECT * FROM article".to_string(),
+                parameters: None,
+                encoding: None,
+            }))
+            .await
+            .expect_err("Live query should be limited");
+        assert!(err.message.contains("maximum of 1"));
+
+        // Changes to matching records should be buffered
+        service
+            .query(Parameters(QueryParams {
+                query: "CREATE person:john SET age = 30; CREATE person:tim SET age = 10;"
+                    .to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        let mut notifications = Vec::new();
+        for _ in 0..50 {
+            let res = service.live_queries.read(&id).expect("Missing live query");
+            let ResourceContents::TextResourceContents { text, .. } = &res.contents[0] else {
+                panic!("Expected text contents");
+            };
+            let content: serde_json::Value = serde_json::from_str(text).unwrap();
+            notifications.extend(content["notifications"].as_array().unwrap().clone());
+            if !notifications.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["action"], "CREATE");
+        assert_eq!(notifications[0]["data"]["id"], "person:john");
+
+        // Stopping the live query removes its resource
         service
+            .kill_live_query(Parameters(KillLiveQueryParams { id: id.clone() }))
+            .await
+            .expect("Kill live query failed");
+        assert!(service.live_queries.is_empty());
+        assert!(service.live_queries.read(&id).is_none());
+        assert!(
+            service
+                .kill_live_query(Parameters(KillLiveQueryParams { id }))
+                .await
+                .is_err()
+        );
     }
 
     #[tokio::test]
-    async fn test_tool_query_simple() {
+    async fn test_schema_resources() {
         let service = setup_service().await;
+        service
+            .query(Parameters(QueryParams {
+                query: "DEFINE TABLE person SCHEMAFULL; DEFINE FIELD name ON person TYPE string;"
+                    .to_string(),
+                parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+
+        // The schema resources of the current database should be listed
+        let resources = service.schema_resources().await;
+        let uris: Vec<_> = resources.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            vec![
+                "surrealdb://test_ns/test_db/schema",
+                "surrealdb://test_ns/test_db/table/person",
+            ]
+        );
+
+        // The table schema should be read from the database
+        let uri = "surrealdb://test_ns/test_db/table/person";
+        let (provider, vars) = resources::ResourceTemplateRegistry::find_by_uri(uri).unwrap();
+        let res = service
+            .read_template_resource(provider.as_ref(), uri, &vars)
+            .await
+            .expect("Read failed");
+        let ResourceContents::TextResourceContents { text, .. } = &res.contents[0] else {
+            panic!("Expected text contents");
+        };
+        let content: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(content["fields"][0]["name"], "name");
+
+        // Reading another database should not change the database used by the tools
+        let uri = "surrealdb://test_ns/other_db/schema";
+        let (provider, vars) = resources::ResourceTemplateRegistry::find_by_uri(uri).unwrap();
+        let res = service
+            .read_template_resource(provider.as_ref(), uri, &vars)
+            .await
+            .expect("Read failed");
+        let ResourceContents::TextResourceContents { text, .. } = &res.contents[0] else {
+            panic!("Expected text contents");
+        };
+        assert!(!text.contains("person"));
         let res = service
             .query(Parameters(QueryParams {
-                query: "RETURN 42".to_string(),
+                query: "RETURN session::db()".to_string(),
+                parameters: None,
+                first_result_only: Some(true),
+                timeout: None,
+                encoding: None,
+                connection: None,
+            }))
+            .await
+            .expect("Query failed");
+        assert_eq!(res.structured_content.unwrap()["result"], "test_db");
+    }
+
+    #[tokio::test]
+    async fn test_record_resources() {
+        let service = setup_service().await;
+        service
+            .query(Parameters(QueryParams {
+                query: "CREATE person:john SET name = 'John'; CREATE person:⟨jane doe⟩ SET name = 'Jane';"
+                    .to_string(),
                 parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
             }))
             .await
             .expect("Query failed");
+        let read = async |uri: &str| {
+            let (provider, vars) = resources::ResourceTemplateRegistry::find_by_uri(uri).unwrap();
+            service
+                .read_template_resource(provider.as_ref(), uri, &vars)
+                .await
+        };
 
-        assert!(!res.is_error.unwrap_or(false));
-        let result_str = serde_json::to_string(&res.content[0]).unwrap_or_default();
-        assert!(
-            result_str.contains("42"),
-            "Expected text content containing '42'"
-        );
+        // Existing records should be returned as JSON
+        let res = read("surrealmcp://record/person/john")
+            .await
+            .expect("Read failed");
+        let ResourceContents::TextResourceContents { text, .. } = &res.contents[0] else {
+            panic!("Expected text contents");
+        };
+        let content: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(content["name"], "John");
+        let res = read("surrealmcp://record/person/jane%20doe")
+            .await
+            .expect("Read failed");
+        let ResourceContents::TextResourceContents { text, .. } = &res.contents[0] else {
+            panic!("Expected text contents");
+        };
+        assert!(text.contains("Jane"));
+
+        // Missing records should return a not-found error
+        let err = read("surrealmcp://record/person/tobie").await.unwrap_err();
+        assert_eq!(err.code, rmcp::model::ErrorCode::RESOURCE_NOT_FOUND);
+        assert!(err.message.contains("does not exist"));
     }
 
     #[tokio::test]
-    async fn test_tool_crud_flow() {
+    async fn test_tool_transaction() {
         let service = setup_service().await;
+        service
+            .query_internal("DEFINE TABLE person".to_string(), None)
+            .await
+            .expect("Query failed");
+        let count = async |db: &Surreal<Any>| {
+            let mut res = db.query("count(SELECT * FROM person)").await.unwrap();
+            let value: Value = res.take(0).unwrap();
+            utils::surreal_to_json(value)
+        };
+        let create = async |target: &str| {
+            service
+                .create(Parameters(CreateParams {
+                    target: target.to_string(),
+                    data: serde_json::Map::new(),
+                    encoding: None,
+                    connection: None,
+                }))
+                .await
+                .expect("Create failed")
+        };
 
-        // 1. Create
+        // Changes within a transaction are only visible within the transaction
         let res = service
-            .create(Parameters(CreateParams {
-                target: "person:john".to_string(),
-                data: serde_json::from_value(serde_json::json!({ "name": "John Doe", "age": 30 }))
-                    .unwrap(),
-            }))
+            .begin_transaction(Parameters(CloudParams {}))
             .await
-            .expect("Create failed");
-        assert!(!res.is_error.unwrap_or(false));
+            .expect("Begin failed");
+        assert_eq!(res.structured_content.unwrap()["status"], "open");
+        create("person:one").await;
+        let res = service
+            .query_internal("count(SELECT * FROM person)".to_string(), None)
+            .await
+            .expect("Query failed");
+        let value: Value = res.result.unwrap().take(0).unwrap();
+        assert_eq!(utils::surreal_to_json(value), 1);
+        assert_eq!(count(&service.session().await.unwrap()).await, 0);
 
-        // 2. Select
+        // Only one transaction can be open, and the namespace can not be changed
+        assert!(
+            service
+                .begin_transaction(Parameters(CloudParams {}))
+                .await
+                .is_err()
+        );
         let res = service
-            .select(Parameters(SelectParams {
-                targets: vec!["person:john".to_string()],
-                where_clause: None,
-                split_clause: None,
-                group_clause: None,
-                order_clause: None,
-                limit_clause: None,
-                start_clause: None,
-                parameters: None,
+            .use_namespace(Parameters(UseNamespaceParams {
+                namespace: "other_ns".to_string(),
             }))
+            .await;
+        assert!(res.is_err());
+
+        // Rolled back changes are discarded
+        let res = service
+            .rollback_transaction(Parameters(CloudParams {}))
             .await
-            .expect("Select failed");
-        assert!(!res.is_error.unwrap_or(false));
+            .expect("Rollback failed");
+        let result = res.structured_content.unwrap();
+        assert_eq!(result["status"], "rolled back");
+        assert_eq!(result["statements"], 2);
+        assert_eq!(count(&service.session().await.unwrap()).await, 0);
 
-        // 3. Update
+        // Committed changes are applied
+        service
+            .begin_transaction(Parameters(CloudParams {}))
+            .await
+            .expect("Begin failed");
+        create("person:one").await;
+        create("person:two").await;
+        let res = service
+            .commit_transaction(Parameters(CloudParams {}))
+            .await
+            .expect("Commit failed");
+        assert_eq!(res.structured_content.unwrap()["status"], "committed");
+        assert_eq!(count(&service.session().await.unwrap()).await, 2);
+
+        // Completing a transaction requires an open transaction
+        assert!(
+            service
+                .commit_transaction(Parameters(CloudParams {}))
+                .await
+                .is_err()
+        );
+        assert!(
+            service
+                .rollback_transaction(Parameters(CloudParams {}))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_dry_run() {
+        let service = setup_service().await;
+        service
+            .query_internal(
+                "CREATE person:john SET age = 30; CREATE person:jane SET age = 20;".to_string(),
+                None,
+            )
+            .await
+            .expect("Query failed");
+
+        // Dry runs should return the affected records and their changes
         let res = service
             .update(Parameters(UpdateParams {
-                targets: vec!["person:john".to_string()],
-                merge_data: Some(serde_json::from_value(serde_json::json!({ "age": 31 })).unwrap()),
+                targets: vec!["person".to_string()],
                 patch_data: None,
+                merge_data: Some(
+                    serde_json::json!({ "age": 40 })
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
                 content_data: None,
                 replace_data: None,
+                where_clause: Some("age > $min".to_string()),
+                parameters: Some(
+                    serde_json::json!({ "min": 25 })
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+                encoding: None,
+                dry_run: Some(true),
+                connection: None,
+            }))
+            .await
+            .expect("Update failed");
+        let result = &res.structured_content.unwrap()["result"];
+        assert_eq!(result.as_array().unwrap().len(), 1);
+        assert_eq!(result[0]["id"], "person:john");
+        assert_eq!(result[0]["before"]["age"], 30);
+        assert_eq!(result[0]["after"]["age"], 40);
+        let res = service
+            .delete(Parameters(DeleteParams {
+                targets: vec!["person".to_string()],
                 where_clause: None,
                 parameters: None,
+                encoding: None,
+                dry_run: Some(true),
+                connection: None,
             }))
             .await
-            .expect("Update failed");
-        assert!(!res.is_error.unwrap_or(false));
+            .expect("Delete failed");
+        let result = &res.structured_content.unwrap()["result"];
+        assert_eq!(result.as_array().unwrap().len(), 2);
+        assert!(result[0]["after"].is_null());
 
-        // 4. Delete
+        // No changes should have been persisted
+        let res = service
+            .query_internal(
+                "SELECT VALUE age FROM person ORDER BY age".to_string(),
+                None,
+            )
+            .await
+            .expect("Query failed");
+        let value: Value = res.result.unwrap().take(0).unwrap();
+        assert_eq!(utils::surreal_to_json(value), serde_json::json!([20, 30]));
+
+        // Dry runs are refused while a transaction is open
+        service
+            .begin_transaction(Parameters(CloudParams {}))
+            .await
+            .expect("Begin failed");
         let res = service
             .delete(Parameters(DeleteParams {
-                targets: vec!["person:john".to_string()],
+                targets: vec!["person".to_string()],
                 where_clause: None,
                 parameters: None,
+                encoding: None,
+                dry_run: Some(true),
+                connection: None,
             }))
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tool_audit_log() {
+        let directory =
+            std::env::temp_dir().join(format!("surrealmcp-audit-{:x}", rand::random::<u64>()));
+        let audit = AuditLog::open(&crate::audit::AuditConfig {
+            directory: directory.to_string_lossy().to_string(),
+            rotation: crate::audit::AuditRotation::Never,
+        })
+        .unwrap();
+        let service = setup_service().await.with_audit_log(Some(Arc::new(audit)));
+
+        // Queries are recorded with the tool call which executed them
+        let call = CallContext {
+            tool: "query".to_string(),
+            subject: Some("user@example.com".to_string()),
+        };
+        let parameters = HashMap::from([
+            (
+                "min".to_string(),
+                utils::convert_json_to_surreal(18, "min").unwrap(),
+            ),
+            (
+                "max".to_string(),
+                utils::convert_json_to_surreal(65, "max").unwrap(),
+            ),
+        ]);
+        call.scope(service.query_internal(
+            "SELECT * FROM person WHERE age > $min AND age < $max".to_string(),
+            Some(parameters),
+        ))
+        .await
+        .expect("Query failed");
+        let res = service
+            .query_internal("SELECT * FROM".to_string(), None)
             .await
-            .expect("Delete failed");
-        assert!(!res.is_error.unwrap_or(false));
+            .expect("Query failed");
+        assert!(res.error.is_some());
+
+        // Resource reads are recorded with the database which they read
+        let uri = "surrealdb://test_ns/other_db/schema";
+        let (provider, vars) = resources::ResourceTemplateRegistry::find_by_uri(uri).unwrap();
+        service
+            .read_template_resource(provider.as_ref(), uri, &vars)
+            .await
+            .expect("Read failed");
+
+        // Entries are flushed once the audit log is dropped
+        drop(service);
+        let contents = std::fs::read_to_string(directory.join("surrealmcp-audit.jsonl")).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|v| serde_json::from_str(v).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["tool"], "query");
+        assert_eq!(lines[0]["subject"], "user@example.com");
+        assert_eq!(lines[0]["endpoint"], "memory");
+        assert_eq!(lines[0]["namespace"], "test_ns");
+        assert_eq!(lines[0]["database"], "test_db");
+        assert_eq!(lines[0]["parameters"], serde_json::json!(["max", "min"]));
+        assert_eq!(lines[0]["outcome"], "ok");
+        assert!(lines[1]["tool"].is_null());
+        assert_eq!(lines[1]["outcome"], "error");
+        assert_eq!(lines[2]["query"], "INFO FOR DB STRUCTURE;");
+        assert_eq!(lines[2]["database"], "other_db");
+        std::fs::remove_dir_all(directory).unwrap();
     }
 
     #[tokio::test]
-    async fn test_tool_relate() {
+    async fn test_authenticate() {
         let service = setup_service().await;
+        let identity = |subject: &str, expires_at: u64| Identity {
+            subject: Some(subject.to_string()),
+            issuer: "https://auth.surrealdb.com/".to_string(),
+            scopes: vec!["openid".to_string()],
+            expires_at: Some(expires_at),
+        };
+        // Requests without a bearer token are allowed
+        assert!(service.authenticate(None).await.unwrap().is_none());
+        // The identity of the session is taken from the bearer token
+        let alice = identity("alice", u64::MAX);
+        let res = service.authenticate(Some(&alice)).await.unwrap();
+        assert_eq!(res.as_ref(), Some(&alice));
+        assert_eq!(service.identity().await.as_ref(), Some(&alice));
+        let res = service.authenticate(None).await.unwrap();
+        assert_eq!(res.as_ref(), Some(&alice));
+        // Tokens for a different subject are refused
+        let bob = identity("bob", u64::MAX);
+        assert!(service.authenticate(Some(&bob)).await.is_err());
+        assert_eq!(service.identity().await.as_ref(), Some(&alice));
+        // Requests are refused once the token has expired
+        let expired = identity("alice", 1);
+        assert!(service.authenticate(Some(&expired)).await.is_err());
+        assert!(service.authenticate(None).await.is_err());
+        // Refreshed tokens are accepted again
+        assert!(service.authenticate(Some(&alice)).await.is_ok());
+    }
 
-        // Create persons
+    #[tokio::test]
+    async fn test_connection_allowlists() {
+        let service = SurrealService::with_config(
+            generate_connection_id(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_connections_config(ConnectionsConfig {
+            allow_endpoints: vec!["memory".to_string()],
+            allow_namespaces: vec!["test_*".to_string()],
+            allow_databases: vec![],
+            deny_embedded: true,
+        });
+        let connect = |endpoint: &str, namespace: &str| ConnectParams {
+            endpoint: endpoint.to_string(),
+            namespace: Some(namespace.to_string()),
+            database: Some("test_db".to_string()),
+            username: None,
+            password: None,
+            connection: None,
+        };
+        // Endpoints and namespaces must match the allow-lists
+        let res = service
+            .connect_endpoint(Parameters(connect("file:/etc/surrealdb", "test_ns")))
+            .await;
+        assert!(res.is_err());
+        let res = service
+            .connect_endpoint(Parameters(connect("memory", "other")))
+            .await;
+        assert!(res.is_err());
         service
-            .query(Parameters(QueryParams {
-                query: "CREATE person:a, person:b".to_string(),
-                parameters: None,
+            .connect_endpoint(Parameters(connect("memory", "test_ns")))
+            .await
+            .unwrap();
+        let res = service
+            .use_namespace(Parameters(UseNamespaceParams {
+                namespace: "other".to_string(),
+            }))
+            .await;
+        assert!(res.is_err());
+        service
+            .use_namespace(Parameters(UseNamespaceParams {
+                namespace: "test_other".to_string(),
             }))
             .await
             .unwrap();
-
-        // Relate
+        // Queries cannot change the namespace with USE statements
         let res = service
-            .relate(Parameters(RelateParams {
-                from: vec!["person:a".to_string()],
-                with: vec!["person:b".to_string()],
-                table: "knows".to_string(),
-                content_data: Some(
-                    serde_json::from_value(serde_json::json!({ "since": 2024 })).unwrap(),
-                ),
+            .query(Parameters(QueryParams {
+                query: "USE NS other; RETURN 1".to_string(),
                 parameters: None,
+                first_result_only: None,
+                timeout: None,
+                encoding: None,
+                connection: None,
             }))
-            .await
-            .expect("Relate failed");
-
-        assert!(!res.is_error.unwrap_or(false));
+            .await;
+        assert!(res.is_err());
+        // A configured namespace cannot be changed without an allow-list
+        let service = SurrealService::with_config(
+            generate_connection_id(),
+            None,
+            Some("test_ns".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(service.is_namespace_allowed("test_ns"));
+        assert!(!service.is_namespace_allowed("test_other"));
+        assert!(service.is_database_allowed("anything"));
     }
 
     #[tokio::test]
-    async fn test_tool_metadata() {
-        let service = setup_service().await;
+    async fn test_connection_profiles() {
+        let profiles = crate::config::ConfigFile::parse(
+            r#"
+            [profiles.dev]
+            description = "Development database"
+            endpoint = "memory"
+            namespace = "test_ns"
+            database = "test_db"
 
-        // List Namespaces
+            [profiles.prod]
+            endpoint = "wss://prod.example.com"
+            username = "admin"
+            password_env = "SURREALMCP_TEST_UNSET_PASSWORD"
+
+            [profiles.test]
+            endpoint = "wss://test.example.com"
+            username = "admin"
+            password = "hunter2"
+            "#,
+        )
+        .unwrap()
+        .profiles;
+        let service = SurrealService::with_config(
+            generate_connection_id(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_profiles(Arc::new(profiles));
+        // Profiles are listed without their credentials
         let res = service
-            .list_namespaces(Parameters(CloudParams {}))
+            .list_profiles(Parameters(CloudParams {}))
             .await
-            .expect("List namespaces failed");
-        assert!(!res.is_error.unwrap_or(false));
+            .unwrap();
+        let result = res.structured_content.unwrap();
+        assert_eq!(result["count"], 3);
+        assert_eq!(result["profiles"][0]["name"], "dev");
+        assert_eq!(result["profiles"][0]["description"], "Development database");
+        assert!(!result.to_string().contains("admin"));
+        assert!(!result.to_string().contains("hunter2"));
+        // Profiles are connected to by name
+        service
+            .connect_profile(Parameters(ConnectProfileParams {
+                name: "dev".to_string(),
+                connection: None,
+            }))
+            .await
+            .unwrap();
+        let target = service.target.lock().unwrap().clone();
+        assert_eq!(target.endpoint.as_deref(), Some("memory"));
+        assert_eq!(target.namespace.as_deref(), Some("test_ns"));
+        // Unknown profiles and missing credentials are refused
+        let connect = |name: &str| ConnectProfileParams {
+            name: name.to_string(),
+            connection: None,
+        };
+        let res = service
+            .connect_profile(Parameters(connect("staging")))
+            .await;
+        assert!(
+            res.unwrap_err()
+                .message
+                .contains("Unknown connection profile")
+        );
+        let res = service.connect_profile(Parameters(connect("prod"))).await;
+        assert!(!res.unwrap_err().message.contains("SURREALMCP_TEST"));
+    }
 
-        // List Databases
+    #[tokio::test]
+    async fn test_named_connections() {
+        let service = SurrealService::with_config(
+            generate_connection_id(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let connect = |ns: &str, connection: Option<&str>| ConnectParams {
+            endpoint: "memory".to_string(),
+            namespace: Some(ns.to_string()),
+            database: Some("test_db".to_string()),
+            username: None,
+            password: None,
+            connection: connection.map(str::to_string),
+        };
+        let query = |query: &str, connection: Option<&str>| QueryParams {
+            query: query.to_string(),
+            parameters: None,
+            first_result_only: Some(true),
+            timeout: None,
+            encoding: None,
+            connection: connection.map(str::to_string),
+        };
+        // Named connections are opened alongside the default connection
+        service
+            .connect_endpoint(Parameters(connect("default_ns", None)))
+            .await
+            .unwrap();
+        service
+            .connect_endpoint(Parameters(connect("other_ns", Some("other"))))
+            .await
+            .unwrap();
+        let target = service.target.lock().unwrap().clone();
+        assert_eq!(target.namespace.as_deref(), Some("default_ns"));
+        // Queries are executed on the specified connection
+        service
+            .query(Parameters(query("CREATE person:one", Some("other"))))
+            .await
+            .unwrap();
+        service
+            .query(Parameters(query("CREATE person:a; CREATE person:b", None)))
+            .await
+            .unwrap();
+        let count = |connection: Option<&str>| {
+            let params = query("RETURN count(SELECT * FROM person)", connection);
+            let service = &service;
+            async move {
+                let res = service.query(Parameters(params)).await.unwrap();
+                res.structured_content.unwrap()["result"].clone()
+            }
+        };
+        assert_eq!(count(Some("other")).await, 1);
+        assert_eq!(count(None).await, 2);
+        assert_eq!(count(Some("default")).await, 2);
         let res = service
-            .list_databases(Parameters(CloudParams {}))
+            .query(Parameters(query("RETURN 1", Some("missing"))))
+            .await;
+        assert!(res.unwrap_err().message.contains("Unknown connection"));
+        // USE statements can not change the target of a named connection
+        let res = service
+            .query(Parameters(query("USE NS test_ns; RETURN 1", Some("other"))))
+            .await;
+        assert!(res.unwrap_err().message.contains("not allowed on named"));
+        assert_eq!(count(Some("other")).await, 1);
+        // Open connections are listed with their auth level and health
+        let res = service
+            .list_connections(Parameters(CloudParams {}))
             .await
-            .expect("List databases failed");
-        assert!(!res.is_error.unwrap_or(false));
+            .unwrap();
+        let result = res.structured_content.unwrap();
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["connections"][0]["name"], "default");
+        assert_eq!(result["connections"][0]["default"], true);
+        assert_eq!(result["connections"][1]["name"], "other");
+        assert_eq!(result["connections"][1]["namespace"], "other_ns");
+        assert_eq!(result["connections"][1]["auth"], "none");
+        assert_eq!(result["connections"][1]["healthy"], true);
+        // Named connections are closed by name
+        service
+            .disconnect_endpoint(Parameters(DisconnectParams {
+                connection: Some("other".to_string()),
+            }))
+            .await
+            .unwrap();
+        assert!(service.named_connections.lock().await.is_empty());
+        assert!(service.db.lock().await.is_some());
+        // The number of named connections is limited
+        for i in 0..MAX_NAMED_CONNECTIONS {
+            let name = format!("conn_{i}");
+            service
+                .connect_endpoint(Parameters(connect("test_ns", Some(&name))))
+                .await
+                .unwrap();
+        }
+        let res = service
+            .connect_endpoint(Parameters(connect("test_ns", Some("extra"))))
+            .await;
+        assert!(res.unwrap_err().message.contains("Cannot open more than"));
+        // Concurrent connections can not exceed the number of named connections
+        service
+            .disconnect_endpoint(Parameters(DisconnectParams {
+                connection: Some("conn_0".to_string()),
+            }))
+            .await
+            .unwrap();
+        let (first, second) = tokio::join!(
+            service.connect_endpoint(Parameters(connect("test_ns", Some("first")))),
+            service.connect_endpoint(Parameters(connect("test_ns", Some("second")))),
+        );
+        assert!(first.is_ok() != second.is_ok());
+        assert_eq!(
+            service.named_connections.lock().await.len(),
+            MAX_NAMED_CONNECTIONS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_credentials() {
+        let service = SurrealService::with_config(
+            generate_connection_id(),
+            Some("ws://localhost:8000".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .with_signin(SigninConfig {
+            level: db::SigninLevel::Record,
+            access: Some("account".to_string()),
+            access_key: Some("secret".to_string()),
+            forward_token: true,
+        });
+        service.token.write().await.replace("token".to_string());
+        service.identity.write().await.replace(Identity {
+            subject: Some("alice".to_string()),
+            issuer: "https://auth.surrealdb.com/".to_string(),
+            scopes: vec![],
+            expires_at: None,
+        });
+        // The bearer token is forwarded to the configured endpoint
+        let credentials = service.credentials("ws://localhost:8000", None, None).await;
+        assert_eq!(credentials.token.as_deref(), Some("token"));
+        // The bearer token is never forwarded to other endpoints
+        let credentials = service
+            .credentials("wss://evil.example.com", None, None)
+            .await;
+        assert!(credentials.token.is_none());
+        assert_eq!(credentials.level, db::SigninLevel::Record);
+        assert_eq!(credentials.access.as_deref(), Some("account"));
+        assert_eq!(credentials.subject.as_deref(), Some("alice"));
+        assert_eq!(credentials.access_key.as_deref(), Some("secret"));
+        // Explicit credentials take precedence over the bearer token
+        let credentials = service
+            .credentials("ws://localhost:8000", Some("root".to_string()), None)
+            .await;
+        assert!(credentials.token.is_none());
+    }
+
+    #[test]
+    fn test_destructive_operation() {
+        let args = |v: serde_json::Value| v.as_object().unwrap().clone();
+        // Deleting whole tables is destructive
+        let op = destructive_operation(
+            "delete",
+            Some(&args(serde_json::json!({ "targets": ["person"] }))),
+        );
+        assert_eq!(op.as_deref(), Some("delete all records from person"));
+        // Deleting specific or matching records is not destructive
+        let op = destructive_operation(
+            "delete",
+            Some(&args(serde_json::json!({ "targets": ["person:john"] }))),
+        );
+        assert!(op.is_none());
+        let op = destructive_operation(
+            "delete",
+            Some(&args(
+                serde_json::json!({ "targets": ["person"], "where_clause": "age < 18" }),
+            )),
+        );
+        assert!(op.is_none());
+        let op = destructive_operation(
+            "delete",
+            Some(&args(
+                serde_json::json!({ "targets": ["person"], "dry_run": true }),
+            )),
+        );
+        assert!(op.is_none());
+        // Queries which remove schema are destructive
+        let op = destructive_operation(
+            "query",
+            Some(&args(serde_json::json!({ "query": "REMOVE TABLE person" }))),
+        );
+        assert!(op.unwrap().contains("REMOVE statements"));
+        let op = destructive_operation(
+            "query",
+            Some(&args(
+                serde_json::json!({ "query": "SELECT * FROM person" }),
+            )),
+        );
+        assert!(op.is_none());
+        // Pausing and creating cloud instances is destructive
+        let op = destructive_operation(
+            "pause_cloud_instance",
+            Some(&args(serde_json::json!({ "instance_id": "abc" }))),
+        );
+        assert_eq!(
+            op.as_deref(),
+            Some("pause the SurrealDB Cloud instance 'abc'")
+        );
+        assert!(destructive_operation("create_cloud_instance", None).is_some());
+        assert!(destructive_operation("select", None).is_none());
     }
 }
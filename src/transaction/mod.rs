@@ -0,0 +1,294 @@
+use crate::utils;
+use metrics::counter;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use surrealdb::method::Transaction;
+use tokio::sync::{Mutex, MutexGuard};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// The default time after which an idle transaction is rolled back
+pub const DEFAULT_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The summary of a transaction returned by the transaction tools
+#[derive(Serialize, schemars::JsonSchema)]
+pub struct TransactionResult {
+    /// The id of the transaction
+    pub id: String,
+    /// The status of the transaction (open, committed, or rolled back)
+    pub status: String,
+    /// The number of queries executed within the transaction
+    pub statements: usize,
+    /// The time since the transaction was started
+    pub duration: String,
+}
+
+/// A transaction which is open on the database
+pub struct OpenTransaction {
+    /// The id of the transaction
+    id: String,
+    /// The transaction on the database
+    txn: Transaction<Any>,
+    /// The time the transaction was started
+    started_at: Instant,
+    /// The time the transaction was last used
+    used_at: Instant,
+    /// The number of queries executed within the transaction
+    statements: usize,
+    /// The task which rolls back the transaction once it has been idle for too long
+    reaper: Option<JoinHandle<()>>,
+}
+
+impl OpenTransaction {
+    /// Get the transaction, recording that a query is executed within it
+    pub fn execute(&mut self) -> &Transaction<Any> {
+        self.used_at = Instant::now();
+        self.statements += 1;
+        &self.txn
+    }
+
+    /// Get the summary of this transaction with the given status
+    fn result(&self, status: &str) -> TransactionResult {
+        TransactionResult {
+            id: self.id.clone(),
+            status: status.to_string(),
+            statements: self.statements,
+            duration: utils::format_duration(self.started_at.elapsed()),
+        }
+    }
+}
+
+/// The transaction which is open within a single client session
+pub struct SessionTransaction {
+    /// The time after which an idle transaction is rolled back (never if not set)
+    timeout: Option<Duration>,
+    /// The open transaction, if any
+    current: Arc<Mutex<Option<OpenTransaction>>>,
+}
+
+impl Default for SessionTransaction {
+    fn default() -> Self {
+        Self::new(Some(DEFAULT_TRANSACTION_TIMEOUT))
+    }
+}
+
+impl SessionTransaction {
+    /// Create a new session transaction with an idle timeout
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            current: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Lock the open transaction
+    pub async fn lock(&self) -> MutexGuard<'_, Option<OpenTransaction>> {
+        self.current.lock().await
+    }
+
+    /// Check whether a transaction is open
+    pub async fn is_open(&self) -> bool {
+        self.current.lock().await.is_some()
+    }
+
+    /// Begin a new transaction on a session of the database connection
+    pub async fn begin(&self, db: &Surreal<Any>) -> Result<TransactionResult, String> {
+        let mut current = self.current.lock().await;
+        // Only one transaction can be open at a time
+        if let Some(open) = &*current {
+            return Err(format!(
+                "Transaction '{}' is already open. Use commit_transaction or rollback_transaction first.",
+                open.id
+            ));
+        }
+        // Begin the transaction on the database
+        let txn = db
+            .clone()
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+        // Generate a new transaction id
+        let id = format!("txn_{:x}", rand::random::<u64>());
+        // Spawn a task to roll back the transaction if it is abandoned
+        let reaper = self
+            .timeout
+            .map(|timeout| tokio::spawn(reap(self.current.clone(), id.clone(), timeout)));
+        // Store the open transaction
+        let open = OpenTransaction {
+            id,
+            txn,
+            started_at: Instant::now(),
+            used_at: Instant::now(),
+            statements: 0,
+            reaper,
+        };
+        let result = open.result("open");
+        *current = Some(open);
+        Ok(result)
+    }
+
+    /// Commit the open transaction
+    pub async fn commit(&self) -> Result<TransactionResult, String> {
+        let open = self
+            .take()
+            .await
+            .ok_or_else(|| "No transaction is open. Use begin_transaction first.".to_string())?;
+        let result = open.result("committed");
+        // Commit the transaction on the database
+        open.txn
+            .commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction '{}': {e}", result.id))?;
+        Ok(result)
+    }
+
+    /// Roll back the open transaction
+    pub async fn rollback(&self) -> Result<TransactionResult, String> {
+        let open = self
+            .take()
+            .await
+            .ok_or_else(|| "No transaction is open. Use begin_transaction first.".to_string())?;
+        let result = open.result("rolled back");
+        // Cancel the transaction on the database
+        open.txn
+            .cancel()
+            .await
+            .map_err(|e| format!("Failed to roll back transaction '{}': {e}", result.id))?;
+        Ok(result)
+    }
+
+    /// Roll back the open transaction, if any, ignoring any errors
+    pub async fn clear(&self) {
+        if let Some(open) = self.take().await {
+            // Output debugging information
+            debug!(transaction_id = %open.id, "Rolling back open transaction");
+            // Cancel the transaction on the database
+            let _ = open.txn.cancel().await;
+        }
+    }
+
+    /// Remove the open transaction, stopping its idle timeout
+    async fn take(&self) -> Option<OpenTransaction> {
+        let mut open = self.current.lock().await.take()?;
+        if let Some(reaper) = open.reaper.take() {
+            reaper.abort();
+        }
+        Some(open)
+    }
+}
+
+/// Roll back a transaction once it has not been used within the timeout
+async fn reap(current: Arc<Mutex<Option<OpenTransaction>>>, id: String, timeout: Duration) {
+    let mut deadline = Instant::now() + timeout;
+    loop {
+        // Wait until the transaction may have expired
+        tokio::time::sleep_until(deadline.into()).await;
+        let mut guard = current.lock().await;
+        // Stop if the transaction has since been completed
+        let Some(open) = guard.as_ref().filter(|v| v.id == id) else {
+            return;
+        };
+        // Wait longer if the transaction has since been used
+        if open.used_at.elapsed() < timeout {
+            deadline = open.used_at + timeout;
+            continue;
+        }
+        // Remove the abandoned transaction
+        let Some(open) = guard.take() else {
+            return;
+        };
+        drop(guard);
+        // Output debugging information
+        warn!(
+            transaction_id = %id,
+            statements = open.statements,
+            timeout = %utils::format_duration(timeout),
+            "Rolling back abandoned transaction"
+        );
+        // Update transaction metrics
+        counter!("surrealmcp.total_transaction_timeouts").increment(1);
+        // Cancel the transaction on the database
+        if let Err(e) = open.txn.cancel().await {
+            warn!(transaction_id = %id, error = %e, "Failed to roll back abandoned transaction");
+        }
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use surrealdb::types::Value;
+
+    async fn setup_db() -> Surreal<Any> {
+        crate::db::create_client_connection(
+            "mem://",
+            &Default::default(),
+            Some("test"),
+            Some("test"),
+        )
+        .await
+        .expect("Failed to connect to in-memory SurrealDB")
+    }
+
+    async fn count(db: &Surreal<Any>) -> usize {
+        let mut res = db.query("count(SELECT * FROM person)").await.unwrap();
+        let value: Value = res.take(0).unwrap();
+        value.into_int().unwrap_or_default() as usize
+    }
+
+    #[tokio::test]
+    async fn test_transaction_commit_and_rollback() {
+        let db = setup_db().await;
+        db.query("DEFINE TABLE person").await.unwrap();
+        let txn = SessionTransaction::default();
+        // Completing a transaction requires an open transaction
+        assert!(txn.commit().await.is_err());
+        assert!(txn.rollback().await.is_err());
+        // Rolled back changes are discarded
+        assert_eq!(txn.begin(&db).await.unwrap().status, "open");
+        assert!(txn.begin(&db).await.is_err());
+        if let Some(open) = txn.lock().await.as_mut() {
+            open.execute().query("CREATE person:one").await.unwrap();
+        }
+        assert_eq!(count(&db).await, 0);
+        let result = txn.rollback().await.unwrap();
+        assert_eq!(result.status, "rolled back");
+        assert_eq!(result.statements, 1);
+        assert_eq!(count(&db).await, 0);
+        // Committed changes are kept
+        txn.begin(&db).await.unwrap();
+        if let Some(open) = txn.lock().await.as_mut() {
+            open.execute().query("CREATE person:one").await.unwrap();
+            open.execute().query("CREATE person:two").await.unwrap();
+        }
+        let result = txn.commit().await.unwrap();
+        assert_eq!(result.status, "committed");
+        assert_eq!(result.statements, 2);
+        assert_eq!(count(&db).await, 2);
+        assert!(!txn.is_open().await);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_timeout() {
+        let db = setup_db().await;
+        db.query("DEFINE TABLE person").await.unwrap();
+        let txn = SessionTransaction::new(Some(Duration::from_millis(200)));
+        txn.begin(&db).await.unwrap();
+        // Using the transaction postpones the timeout
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        if let Some(open) = txn.lock().await.as_mut() {
+            open.execute().query("CREATE person:one").await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(txn.is_open().await);
+        // Abandoned transactions are rolled back
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!txn.is_open().await);
+        assert!(txn.commit().await.is_err());
+        assert_eq!(count(&db).await, 0);
+    }
+}
@@ -1,5 +1,11 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use rust_decimal::Decimal;
+use serde::Deserialize;
 use surrealdb::types::ToSql;
-use surrealdb::types::{Array, Number, Object, RecordId, Table, Value};
+use surrealdb::types::{
+    Array, Bytes, Datetime, Duration, File, Geometry, Number, Object, RecordId, RecordIdKey, Regex,
+    Set, SurrealValue, Table, Uuid, Value,
+};
 use surrealdb::{Surreal, engine::any::Any};
 
 /// Generate a unique connection ID
@@ -145,6 +151,11 @@ pub fn parse_target(value: String) -> Result<String, String> {
     Ok(Value::Table(Table::from(value)).to_sql())
 }
 
+/// Parse a single item into an escaped SurrealQL table name
+pub fn parse_table(value: &str) -> String {
+    Value::Table(Table::from(value)).to_sql()
+}
+
 /// Check if a string is a safe SurrealQL snippet for use in clauses
 ///
 /// This check rejects strings containing unquoted semicolons, comment sequences
@@ -203,6 +214,253 @@ pub fn surreal_to_json(value: Value) -> serde_json::Value {
     }
 }
 
+/// The JSON encoding used for SurrealDB values in tool parameters and results
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, clap::ValueEnum, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonEncoding {
+    /// Plain JSON, with non-JSON types flattened into SurrealQL strings
+    #[default]
+    Simple,
+    /// Plain JSON, with non-JSON types encoded as tagged objects such as `{"$datetime": ".."}`
+    Typed,
+}
+
+impl JsonEncoding {
+    /// Convert a JSON value into a SurrealDB Value using this encoding
+    pub fn to_surreal(
+        self,
+        value: impl Into<serde_json::Value>,
+        name: &str,
+    ) -> Result<Value, String> {
+        match self {
+            JsonEncoding::Simple => convert_json_to_surreal(value, name),
+            JsonEncoding::Typed => convert_typed_json_to_surreal(value, name),
+        }
+    }
+
+    /// Convert a SurrealDB Value into a JSON value using this encoding
+    pub fn to_json(self, value: Value) -> serde_json::Value {
+        match self {
+            JsonEncoding::Simple => surreal_to_json(value),
+            JsonEncoding::Typed => surreal_to_typed_json(value),
+        }
+    }
+}
+
+/// Create a tagged JSON object containing a single typed value
+fn tagged(tag: &str, value: impl Into<serde_json::Value>) -> serde_json::Value {
+    let mut map = serde_json::Map::with_capacity(1);
+    map.insert(tag.to_string(), value.into());
+    serde_json::Value::Object(map)
+}
+
+/// Convert a SurrealDB Value to a lossless typed JSON representation
+///
+/// Values which have a native JSON representation are returned as-is, while
+/// all other values are returned as a tagged object with a single key:
+///
+/// * `{"$none": null}` - the NONE value
+/// * `{"$float": "NaN"}` - a float which is not finite
+/// * `{"$decimal": "1.50"}` - a decimal number
+/// * `{"$datetime": "2024-01-01T00:00:00Z"}` - an RFC 3339 datetime
+/// * `{"$duration": "1h30m"}` - a duration
+/// * `{"$uuid": "0191..."}` - a UUID
+/// * `{"$bytes": "aGVsbG8="}` - base64 encoded bytes
+/// * `{"$table": "person"}` - a table name
+/// * `{"$record": {"table": "person", "key": "john"}}` - a record ID with a typed key
+/// * `{"$geometry": {"type": "Point", "coordinates": [0, 0]}}` - a GeoJSON geometry
+/// * `{"$file": {"bucket": "b", "key": "/k"}}` - a file reference
+/// * `{"$regex": "^a.*"}` - a regular expression
+/// * `{"$range": "1..10"}` - a SurrealQL range
+/// * `{"$set": [..]}` - a set of values
+pub fn surreal_to_typed_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::None => tagged("$none", serde_json::Value::Null),
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(b),
+        Value::Number(n) => match n {
+            Number::Int(i) => i.into(),
+            Number::Float(f) if f.is_finite() => f.into(),
+            Number::Float(f) => tagged("$float", f.to_string()),
+            Number::Decimal(d) => tagged("$decimal", d.to_string()),
+        },
+        Value::String(s) => serde_json::Value::String(s),
+        Value::Bytes(b) => tagged("$bytes", STANDARD.encode(&*b)),
+        Value::Duration(d) => tagged("$duration", d.to_string()),
+        Value::Datetime(d) => tagged("$datetime", d.to_string()),
+        Value::Uuid(u) => tagged("$uuid", u.to_string()),
+        Value::Geometry(g) => tagged("$geometry", surreal_to_json(Value::Object(g.as_object()))),
+        Value::Table(t) => tagged("$table", t.into_string()),
+        Value::RecordId(r) => tagged(
+            "$record",
+            serde_json::json!({
+                "table": r.table.into_string(),
+                "key": surreal_to_typed_json(r.key.into_value()),
+            }),
+        ),
+        Value::File(f) => tagged(
+            "$file",
+            serde_json::json!({ "bucket": f.bucket(), "key": f.key() }),
+        ),
+        Value::Regex(r) => tagged("$regex", r.regex().as_str()),
+        Value::Range(r) => tagged("$range", r.to_sql()),
+        Value::Array(a) => {
+            serde_json::Value::Array(a.into_iter().map(surreal_to_typed_json).collect())
+        }
+        Value::Set(s) => tagged(
+            "$set",
+            serde_json::Value::Array(s.into_iter().map(surreal_to_typed_json).collect()),
+        ),
+        Value::Object(o) => {
+            let mut map = serde_json::Map::new();
+            for (k, v) in o {
+                map.insert(k, surreal_to_typed_json(v));
+            }
+            serde_json::Value::Object(map)
+        }
+    }
+}
+
+/// Convert a lossless typed JSON value to a SurrealDB Value
+///
+/// This function accepts the tagged objects produced by [`surreal_to_typed_json`],
+/// converting them back into the original SurrealDB types. Record IDs can also be
+/// specified as a string, for example `{"$record": "person:john"}`. Objects which
+/// do not consist of a single known tag are converted as normal objects.
+///
+/// # Arguments
+/// * `value` - The typed JSON value to convert
+/// * `name` - The name of the parameter being converted (for error messages)
+pub fn convert_typed_json_to_surreal(
+    value: impl Into<serde_json::Value>,
+    name: &str,
+) -> Result<Value, String> {
+    match value.into() {
+        // NONE is tagged in this encoding, so null is always NULL
+        serde_json::Value::Null => Ok(Value::Null),
+        serde_json::Value::Array(a) => {
+            let mut vals = Vec::with_capacity(a.len());
+            for (i, v) in a.into_iter().enumerate() {
+                vals.push(convert_typed_json_to_surreal(v, &format!("{name}[{i}]"))?);
+            }
+            Ok(Value::Array(Array::from(vals)))
+        }
+        serde_json::Value::Object(o) => {
+            // Check if this is a single tagged value
+            if o.len() == 1
+                && let Some((tag, inner)) = o.iter().next()
+                && let Some(value) = convert_tagged_value(tag, inner, name)
+            {
+                return value;
+            }
+            // Otherwise convert this as a normal object
+            let mut map = std::collections::BTreeMap::new();
+            for (k, v) in o {
+                let v = convert_typed_json_to_surreal(v, &format!("{name}.{k}"))?;
+                map.insert(k, v);
+            }
+            Ok(Value::Object(Object::from(map)))
+        }
+        value => convert_json_to_surreal(value, name),
+    }
+}
+
+/// Convert a tagged JSON value, returning `None` if the tag is not known
+fn convert_tagged_value(
+    tag: &str,
+    inner: &serde_json::Value,
+    name: &str,
+) -> Option<Result<Value, String>> {
+    // Create an error for an invalid tagged value
+    let invalid = |e: &dyn std::fmt::Display| format!("Invalid {tag} value for '{name}': {e}");
+    // Get the inner value as a string
+    let string = || inner.as_str().ok_or_else(|| invalid(&"expected a string"));
+    // Convert the tagged value
+    let result = match tag {
+        "$none" => Ok(Value::None),
+        "$float" => string().and_then(|s| {
+            s.parse::<f64>()
+                .map(|f| Value::Number(Number::Float(f)))
+                .map_err(|e| invalid(&e))
+        }),
+        "$decimal" => string().and_then(|s| {
+            s.parse::<Decimal>()
+                .map(|d| Value::Number(Number::Decimal(d)))
+                .map_err(|e| invalid(&e))
+        }),
+        "$datetime" => string().and_then(|s| {
+            s.parse::<Datetime>()
+                .map(Value::Datetime)
+                .map_err(|e| invalid(&e))
+        }),
+        "$duration" => string().and_then(|s| {
+            s.parse::<Duration>()
+                .map(Value::Duration)
+                .map_err(|e| invalid(&e))
+        }),
+        "$uuid" => {
+            string().and_then(|s| s.parse::<Uuid>().map(Value::Uuid).map_err(|e| invalid(&e)))
+        }
+        "$bytes" => string().and_then(|s| {
+            STANDARD
+                .decode(s)
+                .map(|b| Value::Bytes(Bytes::from(b)))
+                .map_err(|e| invalid(&e))
+        }),
+        "$table" => string().map(|s| Value::Table(Table::from(s))),
+        "$regex" => string().and_then(|s| {
+            s.parse::<Regex>()
+                .map(Value::Regex)
+                .map_err(|e| invalid(&e))
+        }),
+        "$range" => string().and_then(|s| match surrealdb_core::syn::value(s) {
+            Ok(v @ Value::Range(_)) => Ok(v),
+            Ok(_) => Err(invalid(&"expected a range")),
+            Err(e) => Err(invalid(&e)),
+        }),
+        "$set" => convert_typed_json_to_surreal(inner.clone(), name).and_then(|v| match v {
+            Value::Array(a) => Ok(Value::Set(Set::from(a.into_iter().collect::<Vec<_>>()))),
+            _ => Err(invalid(&"expected an array")),
+        }),
+        "$geometry" => convert_json_to_surreal(inner.clone(), name).and_then(|v| {
+            match v {
+                Value::Object(o) => Geometry::try_from_object(&o),
+                _ => None,
+            }
+            .map(Value::Geometry)
+            .ok_or_else(|| invalid(&"expected a GeoJSON geometry"))
+        }),
+        "$file" => match (inner["bucket"].as_str(), inner["key"].as_str()) {
+            (Some(bucket), Some(key)) => Ok(Value::File(File::new(bucket, key))),
+            _ => Err(invalid(&"expected an object with a bucket and a key")),
+        },
+        "$record" => match inner {
+            // Parse record IDs specified as a string
+            serde_json::Value::String(s) => surrealdb_core::syn::record_id(s)
+                .map(Value::RecordId)
+                .map_err(|e| invalid(&e)),
+            // Convert record IDs specified as an object
+            serde_json::Value::Object(o) => match (o.get("table"), o.get("key")) {
+                (Some(serde_json::Value::String(table)), Some(key)) => {
+                    convert_typed_json_to_surreal(key.clone(), &format!("{name}.key")).and_then(
+                        |key| {
+                            RecordIdKey::from_value(key)
+                                .map(|key| Value::RecordId(RecordId::new(table.as_str(), key)))
+                                .map_err(|e| invalid(&e))
+                        },
+                    )
+                }
+                _ => Err(invalid(&"expected an object with a table and a key")),
+            },
+            _ => Err(invalid(&"expected a string or an object")),
+        },
+        _ => return None,
+    };
+    Some(result)
+}
+
 /// Parse a list of items into a list of SurrealQL Values
 ///
 /// This function takes a list of strings and attempts to parse them into SurrealQL Values.
@@ -395,6 +653,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_typed_json_round_trip() {
+        let values = vec![
+            Value::None,
+            Value::Null,
+            Value::Bool(true),
+            Value::Number(Number::Int(42)),
+            Value::Number(Number::Float(1.0)),
+            Value::Number(Number::Float(f64::INFINITY)),
+            Value::Number(Number::Decimal("1.50".parse().unwrap())),
+            Value::String("person:john".to_string()),
+            Value::Bytes(Bytes::from(b"hello".to_vec())),
+            Value::Duration("1h30m".parse().unwrap()),
+            Value::Datetime("2024-01-15T10:30:00Z".parse().unwrap()),
+            Value::Uuid("0191f4e8-7b5a-7c1e-8f3a-2b4c6d8e0f12".parse().unwrap()),
+            Value::Table(Table::from("person")),
+            Value::RecordId(RecordId::new("person", "john")),
+            Value::RecordId(RecordId::new("person", 42)),
+            Value::File(File::new("bucket", "/path/file.txt")),
+            Value::Regex("^a.*z$".parse().unwrap()),
+            surrealdb_core::syn::value("1..10").unwrap(),
+            surrealdb_core::syn::value("(51.5, -0.1)").unwrap(),
+            surrealdb_core::syn::value("person:[1, 'a']").unwrap(),
+            Value::Set(Set::from(vec![Value::from_t(1), Value::from_t(2)])),
+        ];
+        for value in values {
+            let json = surreal_to_typed_json(value.clone());
+            let back = convert_typed_json_to_surreal(json.clone(), "value")
+                .unwrap_or_else(|e| panic!("Failed to convert {json}: {e}"));
+            assert_eq!(back, value, "Value did not round-trip through {json}");
+        }
+        // Nested values should also round-trip
+        let value = surrealdb_core::syn::value(
+            "{ id: person:john, born: d'2024-01-15T10:30:00Z', tags: ['a', u'0191f4e8-7b5a-7c1e-8f3a-2b4c6d8e0f12'] }",
+        )
+        .unwrap();
+        let json = surreal_to_typed_json(value.clone());
+        assert_eq!(json["id"]["$record"]["table"], "person");
+        assert_eq!(json["id"]["$record"]["key"], "john");
+        assert_eq!(json["born"]["$datetime"], "2024-01-15T10:30:00Z");
+        assert_eq!(convert_typed_json_to_surreal(json, "value").unwrap(), value);
+    }
+
+    #[test]
+    fn test_typed_json_distinguishes_strings() {
+        // A record ID and a string should not be indistinguishable
+        let record = surreal_to_typed_json(Value::RecordId(RecordId::new("person", "john")));
+        let string = surreal_to_typed_json(Value::String("person:john".to_string()));
+        assert_ne!(record, string);
+        // The simple encoding flattens both into the same string
+        let record = surreal_to_json(Value::RecordId(RecordId::new("person", "john")));
+        let string = surreal_to_json(Value::String("person:john".to_string()));
+        assert_eq!(record, string);
+    }
+
+    #[test]
+    fn test_convert_typed_json_to_surreal() {
+        // Record IDs can be specified as a string
+        let val = convert_typed_json_to_surreal(json!({"$record": "person:john"}), "id").unwrap();
+        assert_eq!(val, Value::RecordId(RecordId::new("person", "john")));
+        // Unknown tags and objects with multiple keys are normal objects
+        let val = convert_typed_json_to_surreal(json!({"$other": 1}), "data").unwrap();
+        assert!(matches!(val, Value::Object(_)));
+        let val = convert_typed_json_to_surreal(json!({"$uuid": "a", "b": 1}), "data").unwrap();
+        assert!(matches!(val, Value::Object(_)));
+        // Invalid tagged values are rejected with the parameter name
+        let err =
+            convert_typed_json_to_surreal(json!({"x": {"$datetime": "nope"}}), "data").unwrap_err();
+        assert!(err.contains("$datetime"));
+        assert!(err.contains("data.x"));
+        assert!(convert_typed_json_to_surreal(json!({"$uuid": 1}), "id").is_err());
+        assert!(convert_typed_json_to_surreal(json!({"$record": {"table": "a"}}), "id").is_err());
+        // The simple encoding leaves tagged objects as plain objects
+        let val = JsonEncoding::Simple
+            .to_surreal(json!({"$record": "person:john"}), "id")
+            .unwrap();
+        assert!(matches!(val, Value::Object(_)));
+    }
+
+    #[test]
+    fn test_parse_table_escapes_identifiers() {
+        assert_eq!(parse_table("person"), "person");
+        assert_ne!(
+            parse_table("person; REMOVE TABLE x"),
+            "person; REMOVE TABLE x"
+        );
+    }
+
     #[test]
     fn test_is_safe_surrealql_snippet() {
         // Safe snippets